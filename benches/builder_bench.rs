@@ -0,0 +1,49 @@
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use readcon_core::types::ConFrameBuilder;
+use std::hint::black_box;
+
+const ELEMENTS: [(&str, f64); 12] = [
+    ("Fe", 55.845),
+    ("Ni", 58.693),
+    ("Cr", 51.996),
+    ("Co", 58.933),
+    ("Mn", 54.938),
+    ("Cu", 63.546),
+    ("Al", 26.982),
+    ("Ti", 47.867),
+    ("Mo", 95.95),
+    ("W", 183.84),
+    ("Nb", 92.906),
+    ("V", 50.942),
+];
+
+/// A high-entropy-alloy style builder with interleaved symbols, so every
+/// type block is assembled from atoms spread over the whole input.
+fn alloy_builder(num_atoms: usize) -> ConFrameBuilder {
+    let mut builder = ConFrameBuilder::new([100.0, 100.0, 100.0], [90.0, 90.0, 90.0]);
+    for i in 0..num_atoms {
+        let (symbol, mass) = ELEMENTS[(i * 7 + i / 13) % ELEMENTS.len()];
+        let x = (i % 100) as f64;
+        let y = ((i / 100) % 100) as f64;
+        let z = (i / 10_000) as f64;
+        builder.add_atom(symbol, x, y, z, false, i as u64, mass);
+    }
+    builder
+}
+
+fn builder_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Builder");
+
+    group.bench_function("build_100k_atoms_12_types", |b| {
+        b.iter_batched(
+            || alloy_builder(100_000),
+            |builder| black_box(builder.build()),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, builder_bench);
+criterion_main!(benches);
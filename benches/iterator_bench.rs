@@ -1,11 +1,11 @@
 #[path = "../tests/common/mod.rs"]
 mod common;
 
-use std::path::Path;
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{Criterion, criterion_group, criterion_main};
 use readcon_core::iterators::ConFrameIterator;
 use std::fs;
 use std::hint::black_box;
+use std::path::Path;
 
 fn generate_large_file(num_frames: usize) -> String {
     let single_frame = fs::read_to_string(test_case!("tiny_cuh2.con")).expect("Can't find test.");
@@ -149,6 +149,43 @@ fn fast_float_microbench(c: &mut Criterion) {
     group.finish();
 }
 
+fn atom_line_bench(c: &mut Criterion) {
+    let lines: Vec<String> = (0..100_000)
+        .map(|i| {
+            let f = i as f64;
+            format!(
+                "{:.6} {:.6} {:.6} {} {}",
+                f * 0.1,
+                f * 0.2,
+                f * 0.3,
+                i % 2,
+                i
+            )
+        })
+        .collect();
+    let mut group = c.benchmark_group("AtomLineParsing");
+
+    group.bench_function("parse_atom_line_100k", |b| {
+        b.iter(|| {
+            for line in &lines {
+                let _ = black_box(readcon_core::parser::parse_atom_line(black_box(line)).unwrap());
+            }
+        })
+    });
+
+    group.bench_function("parse_line_of_n_100k", |b| {
+        b.iter(|| {
+            for line in &lines {
+                let vals =
+                    readcon_core::parser::parse_line_of_n::<f64>(black_box(line), 5).unwrap();
+                let _ = black_box(vals);
+            }
+        })
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     iterator_bench,
@@ -157,5 +194,6 @@ criterion_group!(
     large_file_bench,
     mmap_vs_read_bench,
     fast_float_microbench,
+    atom_line_bench,
 );
 criterion_main!(benches);
@@ -1,6 +1,7 @@
 #include "readcon-core.h"
 #include <stdio.h>
 #include <stdlib.h>
+#include <string.h>
 
 const size_t MAX_HEADER_LENGTH = 256;
 
@@ -58,9 +59,65 @@ void print_frame_summary(const RKRConFrame *frame_handle) {
     free_c_frame(c_frame);
 }
 
+// Checks that the buffer-filling calls reject a NULL or zero-length buffer
+// and truncate into a short one. Returns the number of failed checks.
+int check_buffer_guards(const RKRConFrame *frame_handle) {
+    int failures = 0;
+    char buffer[MAX_HEADER_LENGTH];
+    char small[4];
+
+#define CHECK(cond)                                                            \
+    do {                                                                       \
+        if (!(cond)) {                                                         \
+            fprintf(stderr, "  FAILED: %s\n", #cond);                          \
+            failures++;                                                        \
+        }                                                                      \
+    } while (0)
+
+    CHECK(rkr_frame_get_header_line(frame_handle, 1, 0, NULL,
+                                    MAX_HEADER_LENGTH) == -1);
+    CHECK(rkr_last_error_code() == RKR_ERR_NULL_POINTER);
+    CHECK(rkr_frame_get_header_line(frame_handle, 1, 0, buffer, 0) == -1);
+    CHECK(rkr_last_error_code() == RKR_ERR_NULL_POINTER);
+
+    // A one-byte buffer only has room for the terminator.
+    buffer[0] = 'x';
+    CHECK(rkr_frame_get_header_line(frame_handle, 1, 0, buffer, 1) == 0);
+    CHECK(buffer[0] == '\0');
+
+    // Longer lines are cut to fit and stay null-terminated.
+    int full = rkr_frame_get_header_line(frame_handle, 1, 0, buffer,
+                                         MAX_HEADER_LENGTH);
+    CHECK(full > (int)sizeof(small) - 1);
+    CHECK(rkr_frame_get_header_line(frame_handle, 1, 0, small,
+                                    sizeof(small)) == (int)sizeof(small) - 1);
+    CHECK(strncmp(small, buffer, sizeof(small) - 1) == 0);
+    CHECK(small[sizeof(small) - 1] == '\0');
+
+    CHECK(rkr_last_error_message(NULL, MAX_HEADER_LENGTH) == -1);
+    CHECK(rkr_last_error_message(buffer, 0) == -1);
+
+#undef CHECK
+    return failures;
+}
+
 int main(int argc, char *argv[]) {
+    if (argc == 3 && strcmp(argv[1], "--check-buffers") == 0) {
+        RKRConFrame *frame = rkr_read_first_frame(argv[2]);
+        if (!frame) {
+            fprintf(stderr, "Failed to read a frame from '%s'.\n", argv[2]);
+            return 1;
+        }
+        int failures = check_buffer_guards(frame);
+        free_rkr_frame(frame);
+        printf("Buffer guard checks: %s\n", failures ? "FAILED" : "passed");
+        return failures ? 1 : 0;
+    }
     if (argc < 2 || argc > 3) {
-        fprintf(stderr, "Usage: %s <input.con> [output.con]\n", argv[0]);
+        fprintf(stderr,
+                "Usage: %s <input.con> [output.con]\n"
+                "       %s --check-buffers <input.con>\n",
+                argv[0], argv[0]);
         return 1;
     }
 
@@ -2,20 +2,39 @@
 //!
 //! Run with: cargo run --example rust_usage -- resources/test/tiny_cuh2.con
 //! Or for convel: cargo run --example rust_usage -- resources/test/tiny_cuh2.convel
+//! To only check a file: cargo run --example rust_usage -- --validate input.con
 
 use std::env;
 use std::fs;
 
-use readcon_core::iterators::ConFrameIterator;
+use readcon_core::iterators::{self, ConFrameIterator};
 use readcon_core::writer::ConFrameWriter;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
         eprintln!("Usage: {} <input.con|convel> [output.con]", args[0]);
+        eprintln!("       {} --validate <input.con|convel>", args[0]);
         std::process::exit(1);
     }
 
+    if args[1] == "--validate" {
+        let Some(input_path) = args.get(2) else {
+            eprintln!("Usage: {} --validate <input.con|convel>", args[0]);
+            std::process::exit(1);
+        };
+        let contents = fs::read_to_string(input_path).expect("Failed to read input file");
+        let errors = iterators::validate(&contents);
+        for (index, err) in &errors {
+            println!("{input_path}: frame {}: {err}", index + 1);
+        }
+        if !errors.is_empty() {
+            std::process::exit(1);
+        }
+        println!("{input_path}: OK");
+        return;
+    }
+
     let input_path = &args[1];
     let contents = fs::read_to_string(input_path).expect("Failed to read input file");
 
@@ -16,6 +16,96 @@
 namespace readcon {
 #endif  // __cplusplus
 
+/**
+ * No error has been recorded on this thread.
+ */
+#define RKR_OK 0
+
+/**
+ * A required pointer argument was NULL.
+ */
+#define RKR_ERR_NULL_POINTER 1
+
+/**
+ * A string argument or file was not valid UTF-8.
+ */
+#define RKR_ERR_INVALID_UTF8 2
+
+/**
+ * An I/O operation failed, e.g. the file could not be opened.
+ */
+#define RKR_ERR_IO 3
+
+/**
+ * An index argument was out of range.
+ */
+#define RKR_ERR_INDEX_OUT_OF_RANGE 4
+
+/**
+ * The file contained no frames.
+ */
+#define RKR_ERR_NO_FRAMES 5
+
+/**
+ * `ParseError::IncompleteHeader`.
+ */
+#define RKR_ERR_INCOMPLETE_HEADER 10
+
+/**
+ * `ParseError::IncompleteFrame`.
+ */
+#define RKR_ERR_INCOMPLETE_FRAME 11
+
+/**
+ * `ParseError::IncompleteVelocitySection`.
+ */
+#define RKR_ERR_INCOMPLETE_VELOCITY_SECTION 12
+
+/**
+ * `ParseError::InvalidVectorLength`.
+ */
+#define RKR_ERR_INVALID_VECTOR_LENGTH 13
+
+/**
+ * `ParseError::MassCountMismatch`.
+ */
+#define RKR_ERR_MASS_COUNT_MISMATCH 14
+
+/**
+ * `ParseError::VelocityBlockMismatch`.
+ */
+#define RKR_ERR_VELOCITY_BLOCK_MISMATCH 15
+
+/**
+ * `ParseError::InvalidNumberFormat`.
+ */
+#define RKR_ERR_INVALID_NUMBER_FORMAT 16
+
+/**
+ * `ParseError::FrameIndexOutOfRange`.
+ */
+#define RKR_ERR_FRAME_INDEX_OUT_OF_RANGE 17
+
+/**
+ * `ParseError::VelocityAtomMismatch`.
+ */
+#define RKR_ERR_VELOCITY_ATOM_MISMATCH 18
+
+/**
+ * `ParseError::InvalidAtomTypeCount`.
+ */
+#define RKR_ERR_INVALID_ATOM_TYPE_COUNT 19
+
+/**
+ * `ParseError::UnsupportedFormat`.
+ */
+#define RKR_ERR_UNSUPPORTED_FORMAT 20
+
+/**
+ * Any other error.
+ */
+#define RKR_ERR_OTHER 99
+
 /**
  * An iterator that lazily parses simulation frames from a `.con` or `.convel`
  * file's contents.
@@ -87,10 +177,38 @@ typedef struct RKRConFrameBuilder {
     uint8_t _private[0];
 } RKRConFrameBuilder;
 
+/**
+ * Receives serialized output for a writer made by
+ * `create_writer_from_callback_c`.
+ *
+ * Called with `len` bytes at `data` and the `user_data` given at creation.
+ * Returns the number of bytes consumed (at most `len`; fewer means the rest
+ * is passed again), or a negative value to report a write error.
+ */
+typedef intptr_t (*RKRWriteCallback)(const uint8_t *data, uintptr_t len, void *user_data);
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
 
+/**
+ * Returns the error code of the most recent failing FFI call on this thread,
+ * or `RKR_OK` (0) if none has failed.
+ *
+ * Codes are stable across releases; see the `RKR_ERR_*` constants. Like
+ * `errno`, the value is not reset by successful calls.
+ */
+int32_t rkr_last_error_code(void);
+
+/**
+ * Copies the message of the most recent failing FFI call on this thread into
+ * a user-provided buffer, truncating it to fit and null-terminating it.
+ * Writes an empty string if no call has failed.
+ * Returns the number of bytes written (excluding null terminator), or -1 if
+ * the buffer is NULL or has zero length.
+ */
+int32_t rkr_last_error_message(char *buffer, uintptr_t len);
+
 /**
  * Creates a new iterator for a .con file.
  * The caller OWNS the returned pointer and MUST call `free_con_frame_iterator`.
@@ -98,9 +216,24 @@ extern "C" {
  */
 struct CConFrameIterator *read_con_file_iterator(const char *filename_c);
 
+/**
+ * Creates a new iterator over a .con file already held in memory.
+ * The `len` bytes at `data` are copied, so the buffer may be freed as soon as
+ * this returns. The caller OWNS the returned pointer and MUST call
+ * `free_con_frame_iterator`.
+ * Returns NULL if `data` is NULL or not valid UTF-8.
+ *
+ * # Safety
+ *
+ * `data` must be NULL or point to at least `len` readable bytes.
+ */
+struct CConFrameIterator *read_con_buffer_iterator(const char *data, uintptr_t len);
+
 /**
  * Reads the next frame from the iterator, returning an opaque handle.
  * The caller OWNS the returned handle and must free it with `free_rkr_frame`.
+ * Returns NULL at the end of the file or on a parse error; only the latter
+ * sets the last error.
  */
 struct RKRConFrame *con_frame_iterator_next(struct CConFrameIterator *iterator);
 
@@ -120,6 +253,51 @@ void free_con_frame_iterator(struct CConFrameIterator *iterator);
  */
 struct CFrame *rkr_frame_to_c_frame(const struct RKRConFrame *frame_handle);
 
+/**
+ * Returns the number of atoms in a frame, or 0 if the handle is NULL.
+ */
+uintptr_t rkr_frame_num_atoms(const struct RKRConFrame *frame_handle);
+
+/**
+ * Copies a single atom into a caller-provided `CAtom`, without building a
+ * whole `CFrame`. The mass is looked up from the atom's type.
+ * Returns 0 on success, or -1 if a pointer is NULL or `index` is out of range.
+ */
+int32_t rkr_frame_get_atom(const struct RKRConFrame *frame_handle,
+                           uintptr_t index,
+                           struct CAtom *out);
+
+/**
+ * Writes the frame's 3x3 lattice matrix, row-major with one lattice vector
+ * per row, into `out`, computed from the full-precision cell lengths and
+ * angles. Returns 0 on success, or -1 if a pointer is NULL.
+ *
+ * # Safety
+ *
+ * `frame_handle` must be NULL or a live handle returned by this library, and
+ * `out` must be NULL or valid for writing 9 doubles.
+ */
+int32_t rkr_frame_get_cell_matrix(const struct RKRConFrame *frame_handle, double *out);
+
+/**
+ * Reports the atom types of a frame in file order: up to `max` symbols and
+ * masses are written to `symbols_out` and `masses_out`. Each symbol is a
+ * newly allocated string that the caller OWNS and MUST free with
+ * `rkr_free_string`; types without atoms get an empty symbol. Either output
+ * may be NULL to skip it.
+ * Returns the total number of types, which may exceed `max`, or -1 if the
+ * frame handle is NULL.
+ *
+ * # Safety
+ *
+ * `frame_handle` must be NULL or a live handle returned by this library, and
+ * each non-NULL output must be valid for writing `max` elements.
+ */
+int32_t rkr_frame_get_mass_types(const struct RKRConFrame *frame_handle,
+                                 char **symbols_out,
+                                 double *masses_out,
+                                 uintptr_t max);
+
 /**
  * Frees the memory of a `CFrame` struct, including its internal atoms array.
  */
@@ -129,6 +307,12 @@ void free_c_frame(struct CFrame *frame);
  * Copies a header string line into a user-provided buffer.
  * This is a C style helper... where the user explicitly sets the buffer.
  * Returns the number of bytes written (excluding null terminator), or -1 on error.
+ * Lines longer than `buffer_len - 1` bytes are truncated at a UTF-8
+ * character boundary; a NULL buffer or a `buffer_len` of zero is an error.
+ *
+ * # Safety
+ *
+ * `buffer` must be NULL or point to at least `buffer_len` writable bytes.
  */
 int32_t rkr_frame_get_header_line(const struct RKRConFrame *frame_handle,
                                   bool is_prebox,
@@ -158,10 +342,25 @@ void rkr_free_string(char *s);
 struct RKRConFrameWriter *create_writer_from_path_c(const char *filename_c);
 
 /**
- * Frees the memory for an `RKRConFrameWriter`, closing the associated file.
+ * Flushes and frees an `RKRConFrameWriter`, closing the associated file.
+ * For a callback writer, any buffered output is passed to the callback first.
+ * A failed flush is recorded for `rkr_last_error_code`.
  */
 void free_rkr_writer(struct RKRConFrameWriter *writer_handle);
 
+/**
+ * Writes a single frame to the file managed by the writer.
+ * Repeated calls append frames to the same file in call order, so frames can
+ * be streamed one at a time instead of batched for `rkr_writer_extend`.
+ * Returns 0 on success, -1 on error.
+ *
+ * # Safety
+ *
+ * Both handles must be NULL or live handles returned by this library.
+ */
+int32_t rkr_writer_write_frame(struct RKRConFrameWriter *writer_handle,
+                               const struct RKRConFrame *frame_handle);
+
 /**
  * Writes multiple frames from an array of handles to the file managed by the writer.
  */
@@ -176,6 +375,22 @@ int32_t rkr_writer_extend(struct RKRConFrameWriter *writer_handle,
 struct RKRConFrameWriter *create_writer_from_path_with_precision_c(const char *filename_c,
                                                                    uint8_t precision);
 
+/**
+ * Creates a frame writer that passes its output to `write_cb` instead of a
+ * file, so the host can stream frames to sockets, buffers or logs.
+ * Output is buffered; it reaches the callback in chunks, and in full by the
+ * time `free_rkr_writer` returns. A negative return from the callback fails
+ * the write in progress with `RKR_ERR_IO`.
+ * The caller OWNS the returned pointer and MUST call `free_rkr_writer`.
+ * Returns NULL if `write_cb` is NULL.
+ *
+ * # Safety
+ *
+ * `write_cb` must be safe to call with `user_data` until the writer is freed.
+ */
+struct RKRConFrameWriter *create_writer_from_callback_c(RKRWriteCallback write_cb,
+                                                        void *user_data);
+
 /**
  * Creates a new frame builder with the given cell dimensions, angles, and header lines.
  * The caller OWNS the returned pointer and MUST call `free_rkr_frame_builder` or
@@ -183,6 +183,13 @@ class ConFrameWriter {
      */
     void extend(const std::vector<ConFrame> &frames);
 
+    /**
+     * @brief Writes a single frame; repeated calls append in call order.
+     * @param frame The ConFrame to write.
+     * @throws std::runtime_error if the write operation fails.
+     */
+    void write_frame(const ConFrame &frame);
+
   private:
     struct WriterDeleter {
         void operator()(RKRConFrameWriter *ptr) const {
@@ -246,6 +253,16 @@ class ConFrameBuilder {
 
 // --- Convenience free functions ---
 
+/**
+ * @brief Returns the message of the most recent failing call into the C API
+ * on this thread, or an empty string if none has failed.
+ */
+inline std::string last_error_message() {
+    std::array<char, 512> buffer{};
+    int32_t len = rkr_last_error_message(buffer.data(), buffer.size());
+    return len > 0 ? std::string(buffer.data(), len) : std::string();
+}
+
 /**
  * @brief Reads the first frame from a .con file using mmap.
  * @throws std::runtime_error on failure.
@@ -254,7 +271,7 @@ inline ConFrame read_first_frame(const std::filesystem::path &path) {
     RKRConFrame *handle = rkr_read_first_frame(path.c_str());
     if (!handle) {
         throw std::runtime_error("Failed to read first frame from: " +
-                                 path.string());
+                                 path.string() + ": " + last_error_message());
     }
     return ConFrame(handle);
 }
@@ -268,7 +285,7 @@ inline std::vector<ConFrame> read_all_frames(const std::filesystem::path &path)
     RKRConFrame **handles = rkr_read_all_frames(path.c_str(), &num_frames);
     if (!handles) {
         throw std::runtime_error("Failed to read frames from: " +
-                                 path.string());
+                                 path.string() + ": " + last_error_message());
     }
     std::vector<ConFrame> frames;
     frames.reserve(num_frames);
@@ -290,7 +307,7 @@ inline ConFrameIterator::ConFrameIterator(const std::filesystem::path &path) {
     CConFrameIterator *iter_ptr = read_con_file_iterator(path.c_str());
     if (!iter_ptr) {
         throw std::runtime_error("Failed to open .con file for iteration: " +
-                                 path.string());
+                                 path.string() + ": " + last_error_message());
     }
     iterator_ptr_.reset(iter_ptr);
 }
@@ -430,7 +447,7 @@ inline ConFrameWriter::ConFrameWriter(const std::filesystem::path &path,
     }
     if (!writer_handle_) {
         throw std::runtime_error("Failed to create writer for file: " +
-                                 path.string());
+                                 path.string() + ": " + last_error_message());
     }
 }
 
@@ -446,7 +463,15 @@ inline void ConFrameWriter::extend(const std::vector<ConFrame> &frames) {
 
     if (rkr_writer_extend(writer_handle_.get(), handles.data(),
                           handles.size()) != 0) {
-        throw std::runtime_error("Failed to write multiple frames.");
+        throw std::runtime_error("Failed to write multiple frames: " +
+                                 last_error_message());
+    }
+}
+
+inline void ConFrameWriter::write_frame(const ConFrame &frame) {
+    if (rkr_writer_write_frame(writer_handle_.get(), frame.get_handle()) != 0) {
+        throw std::runtime_error("Failed to write frame: " +
+                                 last_error_message());
     }
 }
 
@@ -6,8 +6,43 @@ pub enum ParseError {
     IncompleteHeader,
     IncompleteFrame,
     IncompleteVelocitySection,
-    InvalidVectorLength { expected: usize, found: usize },
+    InvalidVectorLength {
+        expected: usize,
+        found: usize,
+    },
+    /// The header's mass line does not hold one mass per atom type.
+    MassCountMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// A velocity block did not start with the lines the parse options call for.
+    VelocityBlockMismatch {
+        component: usize,
+        expected_symbol_line: bool,
+        found: String,
+    },
     InvalidNumberFormat(String),
+    /// A frame was requested past the end of a `FrameIndex`.
+    FrameIndexOutOfRange {
+        index: usize,
+        len: usize,
+    },
+    /// With `ParseOptions::check_velocity_ids`, a velocity line's atom ID
+    /// differs from the ID of the atom at the same position in the coordinate
+    /// blocks.
+    VelocityAtomMismatch {
+        atom_id: u64,
+    },
+    /// The header's atom type count is zero for a frame that lists atoms, or
+    /// exceeds `parser::MAX_ATOM_TYPES`.
+    InvalidAtomTypeCount {
+        found: usize,
+    },
+    /// The input is in a layout this crate cannot read, such as a VASP 4
+    /// POSCAR without an element symbol line.
+    UnsupportedFormat(String),
+    /// The input held no frames where at least one was required.
+    NoFrames,
 }
 
 impl fmt::Display for ParseError {
@@ -25,9 +60,51 @@ impl fmt::Display for ParseError {
             ParseError::InvalidVectorLength { expected, found } => {
                 write!(f, "expected {expected} values on line, found {found}")
             }
+            ParseError::MassCountMismatch { expected, found } => {
+                write!(
+                    f,
+                    "expected {expected} masses (one per atom type) in header, found {found}"
+                )
+            }
+            ParseError::VelocityBlockMismatch {
+                component,
+                expected_symbol_line,
+                found,
+            } => {
+                let layout = if *expected_symbol_line {
+                    "a symbol line followed by \"Velocities of Component N\""
+                } else {
+                    "\"Velocities of Component N\" (no symbol line)"
+                };
+                write!(
+                    f,
+                    "velocity block {component}: expected {layout}, found {found:?}; \
+                     check ParseOptions::velocity_symbol_line"
+                )
+            }
             ParseError::InvalidNumberFormat(msg) => {
                 write!(f, "invalid number format: {msg}")
             }
+            ParseError::FrameIndexOutOfRange { index, len } => {
+                write!(
+                    f,
+                    "frame {index} requested, but the index holds {len} frames"
+                )
+            }
+            ParseError::VelocityAtomMismatch { atom_id } => {
+                write!(
+                    f,
+                    "velocity line for atom {atom_id} does not match the atom at the same \
+                     position in the coordinate blocks"
+                )
+            }
+            ParseError::InvalidAtomTypeCount { found } => {
+                write!(f, "invalid number of atom types in header: {found}")
+            }
+            ParseError::UnsupportedFormat(msg) => {
+                write!(f, "unsupported format: {msg}")
+            }
+            ParseError::NoFrames => write!(f, "No frames found in file"),
         }
     }
 }
@@ -45,3 +122,193 @@ impl From<ParseIntError> for ParseError {
         ParseError::InvalidNumberFormat(e.to_string())
     }
 }
+
+/// Errors from operations on an already-built `ConFrame`.
+#[derive(Debug, PartialEq)]
+pub enum FrameError {
+    /// The cell has zero volume, so fractional coordinates are undefined.
+    DegenerateCell,
+    /// A per-atom input did not have one entry per atom.
+    AtomCountMismatch { expected: usize, found: usize },
+    /// An operation over several frames was given none.
+    NoFrames,
+    /// The frame at index `frame` has a different cell from the first frame.
+    CellMismatch { frame: usize },
+    /// An atom order was not a permutation of `0..len`.
+    InvalidPermutation { len: usize },
+    /// An atom order would split the atoms of `symbol` into more than one
+    /// block, which the `.con` format cannot represent.
+    InterleavedTypes { symbol: String },
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::DegenerateCell => {
+                write!(f, "cell is degenerate (zero volume)")
+            }
+            FrameError::AtomCountMismatch { expected, found } => {
+                write!(f, "expected {expected} per-atom values, found {found}")
+            }
+            FrameError::NoFrames => write!(f, "no frames given"),
+            FrameError::CellMismatch { frame } => {
+                write!(f, "frame {frame} has a different cell from the first frame")
+            }
+            FrameError::InvalidPermutation { len } => {
+                write!(f, "atom order is not a permutation of {len} atoms")
+            }
+            FrameError::InterleavedTypes { symbol } => {
+                write!(
+                    f,
+                    "atom order splits the {symbol} atoms into several blocks"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// Errors from [`crate::types::ConFrameBuilder::build_checked`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// The atom at `atom_index` (in insertion order) has a different mass
+    /// from the first atom with the same symbol.
+    InconsistentMass {
+        symbol: String,
+        atom_index: usize,
+        expected: f64,
+        found: f64,
+    },
+    /// Only some atoms carry velocities.
+    PartialVelocities { with: usize, without: usize },
+    /// A per-atom column passed to
+    /// [`crate::types::ConFrameBuilder::add_atoms_soa`] does not have one
+    /// entry per symbol.
+    LengthMismatch {
+        field: &'static str,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::InconsistentMass {
+                symbol,
+                atom_index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "atom {atom_index} ({symbol}) has mass {found}, but earlier {symbol} atoms \
+                 have mass {expected}"
+            ),
+            BuildError::PartialVelocities { with, without } => write!(
+                f,
+                "{with} atoms have velocities but {without} do not; \
+                 velocities must be given for all atoms or none"
+            ),
+            BuildError::LengthMismatch {
+                field,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{field} has {found} entries, expected one per symbol ({expected})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// A self-consistency problem found in an already-built `ConFrame`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// `natm_types`, `natms_per_type` and `masses_per_type` disagree on the
+    /// number of atom types.
+    TypeCountMismatch {
+        natm_types: usize,
+        natms_per_type: usize,
+        masses_per_type: usize,
+    },
+    /// The per-type atom counts do not add up to the number of atoms.
+    AtomCountMismatch { expected: usize, found: usize },
+    /// The atom at `atom_index` has a different symbol from the rest of its type block.
+    MixedSymbols {
+        type_index: usize,
+        atom_index: usize,
+    },
+    /// Only some atoms carry velocities.
+    PartialVelocities { with: usize, without: usize },
+    /// The atom at `atom_index` has a non-finite coordinate or velocity.
+    NonFiniteValue { atom_index: usize },
+    /// A cell length is negative or a cell length or angle is non-finite.
+    InvalidCell,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::TypeCountMismatch {
+                natm_types,
+                natms_per_type,
+                masses_per_type,
+            } => write!(
+                f,
+                "natm_types is {natm_types} but there are {natms_per_type} atom counts \
+                 and {masses_per_type} masses"
+            ),
+            ValidationIssue::AtomCountMismatch { expected, found } => {
+                write!(f, "header lists {expected} atoms, frame holds {found}")
+            }
+            ValidationIssue::MixedSymbols {
+                type_index,
+                atom_index,
+            } => write!(
+                f,
+                "atom {atom_index} does not match the symbol of type block {type_index}"
+            ),
+            ValidationIssue::PartialVelocities { with, without } => {
+                write!(f, "{with} atoms have velocities but {without} do not")
+            }
+            ValidationIssue::NonFiniteValue { atom_index } => {
+                write!(
+                    f,
+                    "atom {atom_index} has a non-finite coordinate or velocity"
+                )
+            }
+            ValidationIssue::InvalidCell => {
+                write!(
+                    f,
+                    "cell has a negative length or a non-finite length or angle"
+                )
+            }
+        }
+    }
+}
+
+/// Error from [`crate::types::ConFrame::validate`], holding every issue found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsistencyError {
+    /// The problems found, in the order [`crate::types::ConFrame::validation_issues`]
+    /// reports them; never empty.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl fmt::Display for ConsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "frame is inconsistent: ")?;
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConsistencyError {}
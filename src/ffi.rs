@@ -1,12 +1,179 @@
+use crate::error::ParseError;
 use crate::helpers::symbol_to_atomic_number;
 use crate::iterators::{self, ConFrameIterator};
-use crate::types::{ConFrame, ConFrameBuilder};
+use crate::types::{AtomDatum, ConFrame, ConFrameBuilder};
 use crate::writer::ConFrameWriter;
-use std::ffi::{c_char, CStr, CString};
+use std::cell::RefCell;
+use std::error::Error;
+use std::ffi::{CStr, CString, c_char, c_void};
 use std::fs::{self, File};
+use std::io::{self, Write};
 use std::path::Path;
 use std::ptr;
 
+//=============================================================================
+// Error Reporting
+//=============================================================================
+
+/// No error has been recorded on this thread.
+pub const RKR_OK: i32 = 0;
+/// A required pointer argument was NULL.
+pub const RKR_ERR_NULL_POINTER: i32 = 1;
+/// A string argument or file was not valid UTF-8.
+pub const RKR_ERR_INVALID_UTF8: i32 = 2;
+/// An I/O operation failed, e.g. the file could not be opened.
+pub const RKR_ERR_IO: i32 = 3;
+/// An index argument was out of range.
+pub const RKR_ERR_INDEX_OUT_OF_RANGE: i32 = 4;
+/// The file contained no frames.
+pub const RKR_ERR_NO_FRAMES: i32 = 5;
+/// `ParseError::IncompleteHeader`.
+pub const RKR_ERR_INCOMPLETE_HEADER: i32 = 10;
+/// `ParseError::IncompleteFrame`.
+pub const RKR_ERR_INCOMPLETE_FRAME: i32 = 11;
+/// `ParseError::IncompleteVelocitySection`.
+pub const RKR_ERR_INCOMPLETE_VELOCITY_SECTION: i32 = 12;
+/// `ParseError::InvalidVectorLength`.
+pub const RKR_ERR_INVALID_VECTOR_LENGTH: i32 = 13;
+/// `ParseError::MassCountMismatch`.
+pub const RKR_ERR_MASS_COUNT_MISMATCH: i32 = 14;
+/// `ParseError::VelocityBlockMismatch`.
+pub const RKR_ERR_VELOCITY_BLOCK_MISMATCH: i32 = 15;
+/// `ParseError::InvalidNumberFormat`.
+pub const RKR_ERR_INVALID_NUMBER_FORMAT: i32 = 16;
+/// `ParseError::FrameIndexOutOfRange`.
+pub const RKR_ERR_FRAME_INDEX_OUT_OF_RANGE: i32 = 17;
+/// `ParseError::VelocityAtomMismatch`.
+pub const RKR_ERR_VELOCITY_ATOM_MISMATCH: i32 = 18;
+/// `ParseError::InvalidAtomTypeCount`.
+pub const RKR_ERR_INVALID_ATOM_TYPE_COUNT: i32 = 19;
+/// `ParseError::UnsupportedFormat`.
+pub const RKR_ERR_UNSUPPORTED_FORMAT: i32 = 20;
+/// Any other error.
+pub const RKR_ERR_OTHER: i32 = 99;
+
+thread_local! {
+    /// Code and message of the most recent failing FFI call on this thread.
+    static LAST_ERROR: RefCell<Option<(i32, String)>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(code: i32, message: impl Into<String>) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some((code, message.into())));
+}
+
+/// Records `err` as the last error, mapping it to a stable code.
+fn set_last_error_from(err: &(dyn Error + 'static)) {
+    set_last_error(error_code(err), err.to_string());
+}
+
+fn parse_error_code(err: &ParseError) -> i32 {
+    match err {
+        ParseError::IncompleteHeader => RKR_ERR_INCOMPLETE_HEADER,
+        ParseError::IncompleteFrame => RKR_ERR_INCOMPLETE_FRAME,
+        ParseError::IncompleteVelocitySection => RKR_ERR_INCOMPLETE_VELOCITY_SECTION,
+        ParseError::InvalidVectorLength { .. } => RKR_ERR_INVALID_VECTOR_LENGTH,
+        ParseError::MassCountMismatch { .. } => RKR_ERR_MASS_COUNT_MISMATCH,
+        ParseError::VelocityBlockMismatch { .. } => RKR_ERR_VELOCITY_BLOCK_MISMATCH,
+        ParseError::InvalidNumberFormat(_) => RKR_ERR_INVALID_NUMBER_FORMAT,
+        ParseError::FrameIndexOutOfRange { .. } => RKR_ERR_FRAME_INDEX_OUT_OF_RANGE,
+        ParseError::VelocityAtomMismatch { .. } => RKR_ERR_VELOCITY_ATOM_MISMATCH,
+        ParseError::InvalidAtomTypeCount { .. } => RKR_ERR_INVALID_ATOM_TYPE_COUNT,
+        ParseError::UnsupportedFormat(_) => RKR_ERR_UNSUPPORTED_FORMAT,
+        ParseError::NoFrames => RKR_ERR_NO_FRAMES,
+    }
+}
+
+fn error_code(err: &(dyn Error + 'static)) -> i32 {
+    if let Some(e) = err.downcast_ref::<ParseError>() {
+        parse_error_code(e)
+    } else if err.is::<std::str::Utf8Error>() || err.is::<std::string::FromUtf8Error>() {
+        RKR_ERR_INVALID_UTF8
+    } else if err.is::<std::io::Error>() {
+        RKR_ERR_IO
+    } else {
+        RKR_ERR_OTHER
+    }
+}
+
+/// Borrows a C string argument, recording an error if it is NULL or not UTF-8.
+unsafe fn str_arg<'a>(s: *const c_char, name: &str) -> Option<&'a str> {
+    if s.is_null() {
+        null_arg_error(name);
+        return None;
+    }
+    match unsafe { CStr::from_ptr(s) }.to_str() {
+        Ok(s) => Some(s),
+        Err(e) => {
+            set_last_error(
+                RKR_ERR_INVALID_UTF8,
+                format!("{name} is not valid UTF-8: {e}"),
+            );
+            None
+        }
+    }
+}
+
+fn null_arg_error(name: &str) {
+    set_last_error(RKR_ERR_NULL_POINTER, format!("{name} is NULL"));
+}
+
+/// Returns the error code of the most recent failing FFI call on this thread,
+/// or `RKR_OK` (0) if none has failed.
+///
+/// Codes are stable across releases; see the `RKR_ERR_*` constants. Like
+/// `errno`, the value is not reset by successful calls.
+#[unsafe(no_mangle)]
+pub extern "C" fn rkr_last_error_code() -> i32 {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(RKR_OK, |(code, _)| *code))
+}
+
+/// Copies the message of the most recent failing FFI call on this thread into
+/// a user-provided buffer, truncating it to fit and null-terminating it.
+/// Writes an empty string if no call has failed.
+/// Returns the number of bytes written (excluding null terminator), or -1 if
+/// the buffer is NULL or has zero length.
+///
+/// # Safety
+///
+/// `buffer` must be NULL or point to at least `len` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkr_last_error_message(buffer: *mut c_char, len: usize) -> i32 {
+    LAST_ERROR.with(|last| {
+        let last = last.borrow();
+        let message = last.as_ref().map_or("", |(_, message)| message.as_str());
+        unsafe { copy_to_c_buffer(message, buffer, len) }
+    })
+}
+
+/// Copies `text` into a caller-provided buffer of `len` bytes and
+/// null-terminates it. Longer text is truncated at a UTF-8 character
+/// boundary, so the buffer always holds valid UTF-8.
+///
+/// Returns the number of bytes written (excluding the terminator), or -1 if
+/// `buffer` is NULL or `len` is zero.
+///
+/// # Safety
+///
+/// `buffer` must be NULL or point to at least `len` writable bytes.
+unsafe fn copy_to_c_buffer(text: &str, buffer: *mut c_char, len: usize) -> i32 {
+    // One byte is always reserved for the terminator.
+    let Some(capacity) = len.checked_sub(1) else {
+        return -1;
+    };
+    if buffer.is_null() {
+        return -1;
+    }
+    let mut len_to_copy = text.len().min(capacity).min(i32::MAX as usize);
+    while !text.is_char_boundary(len_to_copy) {
+        len_to_copy -= 1;
+    }
+    unsafe {
+        ptr::copy_nonoverlapping(text.as_ptr(), buffer as *mut u8, len_to_copy);
+        *buffer.add(len_to_copy) = 0;
+    }
+    len_to_copy as i32
+}
+
 //=============================================================================
 // C-Compatible Structs & Handles
 //=============================================================================
@@ -25,6 +192,10 @@ pub struct RKRConFrameWriter {
     _private: [u8; 0],
 }
 
+/// The writer behind an `RKRConFrameWriter` handle, over a file or a host
+/// callback.
+type FfiWriter = ConFrameWriter<Box<dyn Write>>;
+
 /// A transparent, "lossy" C-struct containing only the core atomic data.
 /// This can be extracted from an `RKRConFrame` handle for direct data access.
 /// The caller is responsible for freeing the `atoms` array using `free_c_frame`.
@@ -69,18 +240,60 @@ pub struct CConFrameIterator {
 pub unsafe extern "C" fn read_con_file_iterator(
     filename_c: *const c_char,
 ) -> *mut CConFrameIterator {
-    if filename_c.is_null() {
+    let Some(filename) = (unsafe { str_arg(filename_c, "filename") }) else {
         return ptr::null_mut();
-    }
-    let filename = match unsafe { CStr::from_ptr(filename_c).to_str() } {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
     };
-    let file_contents_box = match fs::read_to_string(filename) {
-        Ok(contents) => Box::new(contents),
-        Err(_) => return ptr::null_mut(),
+    let bytes = match fs::read(filename) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            set_last_error_from(&e);
+            return ptr::null_mut();
+        }
     };
-    let file_contents_ptr = Box::into_raw(file_contents_box);
+    match String::from_utf8(bytes) {
+        Ok(contents) => iterator_over_contents(contents),
+        Err(e) => {
+            set_last_error_from(&e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a new iterator over a .con file already held in memory.
+/// The `len` bytes at `data` are copied, so the buffer may be freed as soon as
+/// this returns. The caller OWNS the returned pointer and MUST call
+/// `free_con_frame_iterator`.
+/// Returns NULL if `data` is NULL or not valid UTF-8.
+///
+/// # Safety
+///
+/// `data` must be NULL or point to at least `len` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn read_con_buffer_iterator(
+    data: *const c_char,
+    len: usize,
+) -> *mut CConFrameIterator {
+    if data.is_null() {
+        null_arg_error("data");
+        return ptr::null_mut();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(data as *const u8, len) };
+    match String::from_utf8(bytes.to_vec()) {
+        Ok(contents) => iterator_over_contents(contents),
+        Err(e) => {
+            set_last_error(
+                RKR_ERR_INVALID_UTF8,
+                format!("data is not valid UTF-8: {e}"),
+            );
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Leaks `file_contents` into a `CConFrameIterator` that borrows it for
+/// `'static`; `free_con_frame_iterator` drops the iterator before the contents.
+fn iterator_over_contents(file_contents: String) -> *mut CConFrameIterator {
+    let file_contents_ptr = Box::into_raw(Box::new(file_contents));
     let static_file_contents: &'static str = unsafe { &*file_contents_ptr };
     let iterator = Box::new(ConFrameIterator::new(static_file_contents));
     let c_iterator = Box::new(CConFrameIterator {
@@ -92,17 +305,24 @@ pub unsafe extern "C" fn read_con_file_iterator(
 
 /// Reads the next frame from the iterator, returning an opaque handle.
 /// The caller OWNS the returned handle and must free it with `free_rkr_frame`.
+/// Returns NULL at the end of the file or on a parse error; only the latter
+/// sets the last error.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn con_frame_iterator_next(
     iterator: *mut CConFrameIterator,
 ) -> *mut RKRConFrame {
     if iterator.is_null() {
+        null_arg_error("iterator");
         return ptr::null_mut();
     }
     let iter = unsafe { &mut *(*iterator).iterator };
     match iter.next() {
         Some(Ok(frame)) => Box::into_raw(Box::new(frame)) as *mut RKRConFrame,
-        _ => ptr::null_mut(),
+        Some(Err(e)) => {
+            set_last_error_from(&e);
+            ptr::null_mut()
+        }
+        None => ptr::null_mut(),
     }
 }
 
@@ -137,35 +357,21 @@ pub unsafe extern "C" fn free_con_frame_iterator(iterator: *mut CConFrameIterato
 pub unsafe extern "C" fn rkr_frame_to_c_frame(frame_handle: *const RKRConFrame) -> *mut CFrame {
     let frame = match unsafe { (frame_handle as *const ConFrame).as_ref() } {
         Some(f) => f,
-        None => return ptr::null_mut(),
+        None => {
+            null_arg_error("frame_handle");
+            return ptr::null_mut();
+        }
     };
 
-    let masses_iter = frame
-        .header
-        .natms_per_type
-        .iter()
-        .zip(frame.header.masses_per_type.iter())
-        .flat_map(|(num_atoms, mass)| std::iter::repeat_n(*mass, *num_atoms));
+    let masses = frame.header.per_atom_masses();
 
     let has_velocities = frame.has_velocities();
 
     let mut c_atoms: Vec<CAtom> = frame
         .atom_data
         .iter()
-        .zip(masses_iter)
-        .map(|(atom_datum, mass)| CAtom {
-            atomic_number: symbol_to_atomic_number(&atom_datum.symbol),
-            x: atom_datum.x,
-            y: atom_datum.y,
-            z: atom_datum.z,
-            is_fixed: atom_datum.is_fixed,
-            atom_id: atom_datum.atom_id,
-            mass,
-            vx: atom_datum.vx.unwrap_or(0.0),
-            vy: atom_datum.vy.unwrap_or(0.0),
-            vz: atom_datum.vz.unwrap_or(0.0),
-            has_velocity: atom_datum.has_velocity(),
-        })
+        .zip(masses)
+        .map(|(atom_datum, mass)| c_atom(atom_datum, mass))
         .collect();
 
     let atoms_ptr = c_atoms.as_mut_ptr();
@@ -183,6 +389,171 @@ pub unsafe extern "C" fn rkr_frame_to_c_frame(frame_handle: *const RKRConFrame)
     Box::into_raw(c_frame)
 }
 
+/// Converts one atom to its C representation.
+fn c_atom(atom_datum: &AtomDatum, mass: f64) -> CAtom {
+    CAtom {
+        atomic_number: symbol_to_atomic_number(atom_datum.symbol_str()),
+        x: atom_datum.x,
+        y: atom_datum.y,
+        z: atom_datum.z,
+        is_fixed: atom_datum.is_fixed,
+        atom_id: atom_datum.atom_id,
+        mass,
+        vx: atom_datum.vx.unwrap_or(0.0),
+        vy: atom_datum.vy.unwrap_or(0.0),
+        vz: atom_datum.vz.unwrap_or(0.0),
+        has_velocity: atom_datum.has_velocity(),
+    }
+}
+
+/// Returns the number of atoms in a frame, or 0 if the handle is NULL.
+///
+/// # Safety
+///
+/// `frame_handle` must be NULL or a live handle returned by this library.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkr_frame_num_atoms(frame_handle: *const RKRConFrame) -> usize {
+    match unsafe { (frame_handle as *const ConFrame).as_ref() } {
+        Some(frame) => frame.atom_data.len(),
+        None => {
+            null_arg_error("frame_handle");
+            0
+        }
+    }
+}
+
+/// Copies a single atom into a caller-provided `CAtom`, without building a
+/// whole `CFrame`. The mass is looked up from the atom's type.
+/// Returns 0 on success, or -1 if a pointer is NULL or `index` is out of range.
+///
+/// # Safety
+///
+/// `frame_handle` must be NULL or a live handle returned by this library, and
+/// `out` must be NULL or valid for writing one `CAtom`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkr_frame_get_atom(
+    frame_handle: *const RKRConFrame,
+    index: usize,
+    out: *mut CAtom,
+) -> i32 {
+    let frame = match unsafe { (frame_handle as *const ConFrame).as_ref() } {
+        Some(f) => f,
+        None => {
+            null_arg_error("frame_handle");
+            return -1;
+        }
+    };
+    if out.is_null() {
+        null_arg_error("out");
+        return -1;
+    }
+    let Some(atom_datum) = frame.atom_data.get(index) else {
+        set_last_error(
+            RKR_ERR_INDEX_OUT_OF_RANGE,
+            format!(
+                "atom index {index} is out of range for {} atoms",
+                frame.atom_data.len()
+            ),
+        );
+        return -1;
+    };
+
+    // Find the type block holding `index` to get its mass.
+    let mut block_end = 0;
+    let mut mass = 0.0;
+    for (type_idx, &count) in frame.header.natms_per_type.iter().enumerate() {
+        block_end += count;
+        if index < block_end {
+            mass = frame
+                .header
+                .masses_per_type
+                .get(type_idx)
+                .copied()
+                .unwrap_or(0.0);
+            break;
+        }
+    }
+
+    unsafe { out.write(c_atom(atom_datum, mass)) };
+    0
+}
+
+/// Writes the frame's 3x3 lattice matrix, row-major with one lattice vector
+/// per row, into `out`, computed from the full-precision cell lengths and
+/// angles. Returns 0 on success, or -1 if a pointer is NULL.
+///
+/// # Safety
+///
+/// `frame_handle` must be NULL or a live handle returned by this library, and
+/// `out` must be NULL or valid for writing 9 doubles.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkr_frame_get_cell_matrix(
+    frame_handle: *const RKRConFrame,
+    out: *mut f64,
+) -> i32 {
+    let frame = match unsafe { (frame_handle as *const ConFrame).as_ref() } {
+        Some(f) => f,
+        None => {
+            null_arg_error("frame_handle");
+            return -1;
+        }
+    };
+    if out.is_null() {
+        null_arg_error("out");
+        return -1;
+    }
+    let matrix = frame.header.cell_matrix();
+    let flat = matrix.as_flattened();
+    unsafe { ptr::copy_nonoverlapping(flat.as_ptr(), out, flat.len()) };
+    0
+}
+
+/// Reports the atom types of a frame in file order: up to `max` symbols and
+/// masses are written to `symbols_out` and `masses_out`. Each symbol is a
+/// newly allocated string that the caller OWNS and MUST free with
+/// `rkr_free_string`; types without atoms get an empty symbol. Either output
+/// may be NULL to skip it.
+/// Returns the total number of types, which may exceed `max`, or -1 if the
+/// frame handle is NULL.
+///
+/// # Safety
+///
+/// `frame_handle` must be NULL or a live handle returned by this library, and
+/// each non-NULL output must be valid for writing `max` elements.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkr_frame_get_mass_types(
+    frame_handle: *const RKRConFrame,
+    symbols_out: *mut *mut c_char,
+    masses_out: *mut f64,
+    max: usize,
+) -> i32 {
+    let frame = match unsafe { (frame_handle as *const ConFrame).as_ref() } {
+        Some(f) => f,
+        None => {
+            null_arg_error("frame_handle");
+            return -1;
+        }
+    };
+    let header = &frame.header;
+    let mut offset = 0;
+    for (type_idx, &count) in header.natms_per_type.iter().enumerate().take(max) {
+        if !symbols_out.is_null() {
+            let symbol = match frame.atom_data.get(offset) {
+                Some(atom) if count > 0 => atom.symbol_str(),
+                _ => "",
+            };
+            let c_symbol = CString::new(symbol).unwrap_or_default();
+            unsafe { *symbols_out.add(type_idx) = c_symbol.into_raw() };
+        }
+        if !masses_out.is_null() {
+            let mass = header.masses_per_type.get(type_idx).copied().unwrap_or(0.0);
+            unsafe { *masses_out.add(type_idx) = mass };
+        }
+        offset += count;
+    }
+    header.natms_per_type.len() as i32
+}
+
 /// Frees the memory of a `CFrame` struct, including its internal atoms array.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn free_c_frame(frame: *mut CFrame) {
@@ -198,6 +569,12 @@ pub unsafe extern "C" fn free_c_frame(frame: *mut CFrame) {
 /// Copies a header string line into a user-provided buffer.
 /// This is a C style helper... where the user explicitly sets the buffer.
 /// Returns the number of bytes written (excluding null terminator), or -1 on error.
+/// Lines longer than `buffer_len - 1` bytes are truncated at a UTF-8
+/// character boundary; a NULL buffer or a `buffer_len` of zero is an error.
+///
+/// # Safety
+///
+/// `buffer` must be NULL or point to at least `buffer_len` writable bytes.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn rkr_frame_get_header_line(
     frame_handle: *const RKRConFrame,
@@ -208,22 +585,27 @@ pub unsafe extern "C" fn rkr_frame_get_header_line(
 ) -> i32 {
     let frame = match unsafe { (frame_handle as *const ConFrame).as_ref() } {
         Some(f) => f,
-        None => return -1,
+        None => {
+            null_arg_error("frame_handle");
+            return -1;
+        }
     };
+    if buffer.is_null() || buffer_len == 0 {
+        set_last_error(RKR_ERR_NULL_POINTER, "buffer is NULL or empty");
+        return -1;
+    }
     let line_to_copy = if is_prebox {
         frame.header.prebox_header.get(line_index)
     } else {
         frame.header.postbox_header.get(line_index)
     };
     if let Some(line) = line_to_copy {
-        let bytes = line.as_bytes();
-        let len_to_copy = std::cmp::min(bytes.len(), buffer_len - 1);
-        unsafe {
-            ptr::copy_nonoverlapping(bytes.as_ptr(), buffer as *mut u8, len_to_copy);
-            *buffer.add(len_to_copy) = 0;
-        }
-        len_to_copy as i32
+        unsafe { copy_to_c_buffer(line, buffer, buffer_len) }
     } else {
+        set_last_error(
+            RKR_ERR_INDEX_OUT_OF_RANGE,
+            format!("header line index {line_index} is out of range"),
+        );
         -1
     }
 }
@@ -240,7 +622,10 @@ pub unsafe extern "C" fn rkr_frame_get_header_line_cpp(
 ) -> *mut c_char {
     let frame = match unsafe { (frame_handle as *const ConFrame).as_ref() } {
         Some(f) => f,
-        None => return ptr::null_mut(),
+        None => {
+            null_arg_error("frame_handle");
+            return ptr::null_mut();
+        }
     };
 
     let line_to_copy = if is_prebox {
@@ -253,10 +638,18 @@ pub unsafe extern "C" fn rkr_frame_get_header_line_cpp(
         // Convert the Rust string slice to a C-compatible, heap-allocated string.
         match CString::new(line.as_str()) {
             Ok(c_string) => c_string.into_raw(), // Give ownership to the C caller
-            Err(_) => ptr::null_mut(),           // In case the string contains a null byte
+            Err(e) => {
+                // In case the string contains a null byte
+                set_last_error(RKR_ERR_OTHER, e.to_string());
+                ptr::null_mut()
+            }
         }
     } else {
-        ptr::null_mut() // Index out of bounds
+        set_last_error(
+            RKR_ERR_INDEX_OUT_OF_RANGE,
+            format!("header line index {line_index} is out of range"),
+        );
+        ptr::null_mut()
     }
 }
 
@@ -279,24 +672,67 @@ pub unsafe extern "C" fn rkr_free_string(s: *mut c_char) {
 pub unsafe extern "C" fn create_writer_from_path_c(
     filename_c: *const c_char,
 ) -> *mut RKRConFrameWriter {
-    if filename_c.is_null() {
+    let Some(filename) = (unsafe { str_arg(filename_c, "filename") }) else {
         return ptr::null_mut();
-    }
-    let filename = match unsafe { CStr::from_ptr(filename_c).to_str() } {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
     };
-    match crate::writer::ConFrameWriter::from_path(filename) {
-        Ok(writer) => Box::into_raw(Box::new(writer)) as *mut RKRConFrameWriter,
-        Err(_) => ptr::null_mut(),
+    match File::create(filename) {
+        Ok(file) => {
+            let writer: FfiWriter = ConFrameWriter::new(Box::new(file));
+            Box::into_raw(Box::new(writer)) as *mut RKRConFrameWriter
+        }
+        Err(e) => {
+            set_last_error_from(&e);
+            ptr::null_mut()
+        }
     }
 }
 
-/// Frees the memory for an `RKRConFrameWriter`, closing the associated file.
+/// Flushes and frees an `RKRConFrameWriter`, closing the associated file.
+/// For a callback writer, any buffered output is passed to the callback first.
+/// A failed flush is recorded for `rkr_last_error_code`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn free_rkr_writer(writer_handle: *mut RKRConFrameWriter) {
     if !writer_handle.is_null() {
-        let _ = unsafe { Box::from_raw(writer_handle as *mut ConFrameWriter<File>) };
+        let writer = unsafe { Box::from_raw(writer_handle as *mut FfiWriter) };
+        if let Err(e) = writer.finish() {
+            set_last_error_from(&e);
+        }
+    }
+}
+
+/// Writes a single frame to the file managed by the writer.
+/// Repeated calls append frames to the same file in call order, so frames can
+/// be streamed one at a time instead of batched for `rkr_writer_extend`.
+/// Returns 0 on success, -1 on error.
+///
+/// # Safety
+///
+/// Both handles must be NULL or live handles returned by this library.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkr_writer_write_frame(
+    writer_handle: *mut RKRConFrameWriter,
+    frame_handle: *const RKRConFrame,
+) -> i32 {
+    let writer = match unsafe { (writer_handle as *mut FfiWriter).as_mut() } {
+        Some(w) => w,
+        None => {
+            null_arg_error("writer_handle");
+            return -1;
+        }
+    };
+    let frame = match unsafe { (frame_handle as *const ConFrame).as_ref() } {
+        Some(f) => f,
+        None => {
+            null_arg_error("frame_handle");
+            return -1;
+        }
+    };
+    match writer.write_frame(frame) {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error_from(&e);
+            -1
+        }
     }
 }
 
@@ -307,11 +743,15 @@ pub unsafe extern "C" fn rkr_writer_extend(
     frame_handles: *const *const RKRConFrame,
     num_frames: usize,
 ) -> i32 {
-    let writer = match unsafe { (writer_handle as *mut ConFrameWriter<File>).as_mut() } {
+    let writer = match unsafe { (writer_handle as *mut FfiWriter).as_mut() } {
         Some(w) => w,
-        None => return -1,
+        None => {
+            null_arg_error("writer_handle");
+            return -1;
+        }
     };
     if frame_handles.is_null() {
+        null_arg_error("frame_handles");
         return -1;
     }
 
@@ -320,6 +760,7 @@ pub unsafe extern "C" fn rkr_writer_extend(
     if handles_slice.iter().any(|&handle| handle.is_null()) {
         // Fail fast if any handle is null, as this indicates a bug on the
         // caller's side.
+        null_arg_error("a frame handle");
         return -1;
     }
     for &handle in handles_slice.iter() {
@@ -333,7 +774,10 @@ pub unsafe extern "C" fn rkr_writer_extend(
 
     match writer.extend(rust_frames.into_iter()) {
         Ok(_) => 0,
-        Err(_) => -1,
+        Err(e) => {
+            set_last_error_from(&e);
+            -1
+        }
     }
 }
 
@@ -348,19 +792,89 @@ pub unsafe extern "C" fn create_writer_from_path_with_precision_c(
     filename_c: *const c_char,
     precision: u8,
 ) -> *mut RKRConFrameWriter {
-    if filename_c.is_null() {
+    let Some(filename) = (unsafe { str_arg(filename_c, "filename") }) else {
         return ptr::null_mut();
-    }
-    let filename = match unsafe { CStr::from_ptr(filename_c).to_str() } {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
     };
-    match ConFrameWriter::from_path_with_precision(filename, precision as usize) {
-        Ok(writer) => Box::into_raw(Box::new(writer)) as *mut RKRConFrameWriter,
-        Err(_) => ptr::null_mut(),
+    match File::create(filename) {
+        Ok(file) => {
+            let writer: FfiWriter =
+                ConFrameWriter::with_precision(Box::new(file), precision as usize);
+            Box::into_raw(Box::new(writer)) as *mut RKRConFrameWriter
+        }
+        Err(e) => {
+            set_last_error_from(&e);
+            ptr::null_mut()
+        }
     }
 }
 
+//=============================================================================
+// Writer over a Host Callback
+//=============================================================================
+
+/// Receives serialized output for a writer made by
+/// `create_writer_from_callback_c`.
+///
+/// Called with `len` bytes at `data` and the `user_data` given at creation.
+/// Returns the number of bytes consumed (at most `len`; fewer means the rest
+/// is passed again), or a negative value to report a write error.
+pub type RKRWriteCallback =
+    unsafe extern "C" fn(data: *const u8, len: usize, user_data: *mut c_void) -> isize;
+
+/// Adapts a host write callback to `io::Write`.
+struct CallbackWriter {
+    write_cb: RKRWriteCallback,
+    user_data: *mut c_void,
+}
+
+impl Write for CallbackWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = unsafe { (self.write_cb)(buf.as_ptr(), buf.len(), self.user_data) };
+        match usize::try_from(written) {
+            Ok(n) if n <= buf.len() => Ok(n),
+            Ok(n) => Err(io::Error::other(format!(
+                "write callback consumed {n} bytes of {}",
+                buf.len()
+            ))),
+            Err(_) => Err(io::Error::other(format!(
+                "write callback failed with {written}"
+            ))),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Creates a frame writer that passes its output to `write_cb` instead of a
+/// file, so the host can stream frames to sockets, buffers or logs.
+/// Output is buffered; it reaches the callback in chunks, and in full by the
+/// time `free_rkr_writer` returns. A negative return from the callback fails
+/// the write in progress with `RKR_ERR_IO`.
+/// The caller OWNS the returned pointer and MUST call `free_rkr_writer`.
+/// Returns NULL if `write_cb` is NULL.
+///
+/// # Safety
+///
+/// `write_cb` must be safe to call with `user_data` until the writer is freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn create_writer_from_callback_c(
+    write_cb: Option<RKRWriteCallback>,
+    user_data: *mut c_void,
+) -> *mut RKRConFrameWriter {
+    let Some(write_cb) = write_cb else {
+        null_arg_error("write_cb");
+        return ptr::null_mut();
+    };
+    let sink = CallbackWriter {
+        write_cb,
+        user_data,
+    };
+    let writer: FfiWriter = ConFrameWriter::new(Box::new(sink));
+    Box::into_raw(Box::new(writer)) as *mut RKRConFrameWriter
+}
+
 //=============================================================================
 // Frame Builder FFI (construct ConFrame from C data)
 //=============================================================================
@@ -385,6 +899,7 @@ pub unsafe extern "C" fn rkr_frame_new(
     postbox1: *const c_char,
 ) -> *mut RKRConFrameBuilder {
     if cell.is_null() || angles.is_null() {
+        null_arg_error("cell or angles");
         return ptr::null_mut();
     }
     let cell_arr = unsafe { [*cell, *cell.add(1), *cell.add(2)] };
@@ -421,14 +936,14 @@ pub unsafe extern "C" fn rkr_frame_add_atom(
     atom_id: u64,
     mass: f64,
 ) -> i32 {
-    if builder_handle.is_null() || symbol.is_null() {
+    if builder_handle.is_null() {
+        null_arg_error("builder_handle");
         return -1;
     }
-    let builder = unsafe { &mut *(builder_handle as *mut ConFrameBuilder) };
-    let sym = match unsafe { CStr::from_ptr(symbol).to_str() } {
-        Ok(s) => s,
-        Err(_) => return -1,
+    let Some(sym) = (unsafe { str_arg(symbol, "symbol") }) else {
+        return -1;
     };
+    let builder = unsafe { &mut *(builder_handle as *mut ConFrameBuilder) };
     builder.add_atom(sym, x, y, z, is_fixed, atom_id, mass);
     0
 }
@@ -449,14 +964,14 @@ pub unsafe extern "C" fn rkr_frame_add_atom_with_velocity(
     vy: f64,
     vz: f64,
 ) -> i32 {
-    if builder_handle.is_null() || symbol.is_null() {
+    if builder_handle.is_null() {
+        null_arg_error("builder_handle");
         return -1;
     }
-    let builder = unsafe { &mut *(builder_handle as *mut ConFrameBuilder) };
-    let sym = match unsafe { CStr::from_ptr(symbol).to_str() } {
-        Ok(s) => s,
-        Err(_) => return -1,
+    let Some(sym) = (unsafe { str_arg(symbol, "symbol") }) else {
+        return -1;
     };
+    let builder = unsafe { &mut *(builder_handle as *mut ConFrameBuilder) };
     builder.add_atom_with_velocity(sym, x, y, z, is_fixed, atom_id, mass, vx, vy, vz);
     0
 }
@@ -470,6 +985,7 @@ pub unsafe extern "C" fn rkr_frame_builder_build(
     builder_handle: *mut RKRConFrameBuilder,
 ) -> *mut RKRConFrame {
     if builder_handle.is_null() {
+        null_arg_error("builder_handle");
         return ptr::null_mut();
     }
     let builder = unsafe { *Box::from_raw(builder_handle as *mut ConFrameBuilder) };
@@ -495,19 +1011,16 @@ pub unsafe extern "C" fn free_rkr_frame_builder(builder_handle: *mut RKRConFrame
 /// The caller OWNS the returned handle and MUST call `free_rkr_frame`.
 /// Returns NULL on error.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn rkr_read_first_frame(
-    filename_c: *const c_char,
-) -> *mut RKRConFrame {
-    if filename_c.is_null() {
+pub unsafe extern "C" fn rkr_read_first_frame(filename_c: *const c_char) -> *mut RKRConFrame {
+    let Some(filename) = (unsafe { str_arg(filename_c, "filename") }) else {
         return ptr::null_mut();
-    }
-    let filename = match unsafe { CStr::from_ptr(filename_c).to_str() } {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
     };
     match iterators::read_first_frame(Path::new(filename)) {
         Ok(frame) => Box::into_raw(Box::new(frame)) as *mut RKRConFrame,
-        Err(_) => ptr::null_mut(),
+        Err(e) => {
+            set_last_error_from(e.as_ref());
+            ptr::null_mut()
+        }
     }
 }
 
@@ -521,12 +1034,12 @@ pub unsafe extern "C" fn rkr_read_all_frames(
     filename_c: *const c_char,
     num_frames: *mut usize,
 ) -> *mut *mut RKRConFrame {
-    if filename_c.is_null() || num_frames.is_null() {
+    if num_frames.is_null() {
+        null_arg_error("num_frames");
         return ptr::null_mut();
     }
-    let filename = match unsafe { CStr::from_ptr(filename_c).to_str() } {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
+    let Some(filename) = (unsafe { str_arg(filename_c, "filename") }) else {
+        return ptr::null_mut();
     };
     match iterators::read_all_frames(Path::new(filename)) {
         Ok(frames) => {
@@ -540,17 +1053,17 @@ pub unsafe extern "C" fn rkr_read_all_frames(
             unsafe { *num_frames = count };
             ptr
         }
-        Err(_) => ptr::null_mut(),
+        Err(e) => {
+            set_last_error_from(e.as_ref());
+            ptr::null_mut()
+        }
     }
 }
 
 /// Frees an array of frame handles returned by `rkr_read_all_frames`.
 /// Each frame is freed individually, then the array itself.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn free_rkr_frame_array(
-    frames: *mut *mut RKRConFrame,
-    num_frames: usize,
-) {
+pub unsafe extern "C" fn free_rkr_frame_array(frames: *mut *mut RKRConFrame, num_frames: usize) {
     if frames.is_null() {
         return;
     }
@@ -1,197 +1,283 @@
+/// Chemical symbols and standard atomic masses (amu), indexed by atomic
+/// number minus one, from H to U. Elements without a stable isotope use the
+/// mass number of their longest-lived isotope.
+#[rustfmt::skip]
+const ELEMENTS: [(&str, f64); 92] = [
+    ("H", 1.008), ("He", 4.0026), ("Li", 6.94), ("Be", 9.0122), ("B", 10.81), ("C", 12.011),
+    ("N", 14.007), ("O", 15.999), ("F", 18.998), ("Ne", 20.180), ("Na", 22.990), ("Mg", 24.305),
+    ("Al", 26.982), ("Si", 28.085), ("P", 30.974), ("S", 32.06), ("Cl", 35.45), ("Ar", 39.948),
+    ("K", 39.098), ("Ca", 40.078), ("Sc", 44.956), ("Ti", 47.867), ("V", 50.942), ("Cr", 51.996),
+    ("Mn", 54.938), ("Fe", 55.845), ("Co", 58.933), ("Ni", 58.693), ("Cu", 63.546), ("Zn", 65.38),
+    ("Ga", 69.723), ("Ge", 72.630), ("As", 74.922), ("Se", 78.971), ("Br", 79.904), ("Kr", 83.798),
+    ("Rb", 85.468), ("Sr", 87.62), ("Y", 88.906), ("Zr", 91.224), ("Nb", 92.906), ("Mo", 95.95),
+    ("Tc", 98.0), ("Ru", 101.07), ("Rh", 102.91), ("Pd", 106.42), ("Ag", 107.87), ("Cd", 112.41),
+    ("In", 114.82), ("Sn", 118.71), ("Sb", 121.76), ("Te", 127.60), ("I", 126.90), ("Xe", 131.29),
+    ("Cs", 132.91), ("Ba", 137.33), ("La", 138.91), ("Ce", 140.12), ("Pr", 140.91), ("Nd", 144.24),
+    ("Pm", 145.0), ("Sm", 150.36), ("Eu", 151.96), ("Gd", 157.25), ("Tb", 158.93), ("Dy", 162.50),
+    ("Ho", 164.93), ("Er", 167.26), ("Tm", 168.93), ("Yb", 173.05), ("Lu", 174.97), ("Hf", 178.49),
+    ("Ta", 180.95), ("W", 183.84), ("Re", 186.21), ("Os", 190.23), ("Ir", 192.22), ("Pt", 195.08),
+    ("Au", 196.97), ("Hg", 200.59), ("Tl", 204.38), ("Pb", 207.2), ("Bi", 208.98), ("Po", 209.0),
+    ("At", 210.0), ("Rn", 222.0), ("Fr", 223.0), ("Ra", 226.0), ("Ac", 227.0), ("Th", 232.04),
+    ("Pa", 231.04), ("U", 238.03),
+];
+
+/// Single-bond covalent radii (angstrom) in the same order as `ELEMENTS`, from
+/// Cordero et al., Dalton Trans. (2008) 2832, as also used by ASE. Carbon is
+/// sp3; Mn, Fe and Co take their low-spin values.
+#[rustfmt::skip]
+const COVALENT_RADII: [f64; 92] = [
+    0.31, 0.28, 1.28, 0.96, 0.84, 0.76, 0.71, 0.66, 0.57, 0.58, 1.66, 1.41, 1.21, 1.11, 1.07,
+    1.05, 1.02, 1.06, 2.03, 1.76, 1.70, 1.60, 1.53, 1.39, 1.39, 1.32, 1.26, 1.24, 1.32, 1.22,
+    1.22, 1.20, 1.19, 1.20, 1.20, 1.16, 2.20, 1.95, 1.90, 1.75, 1.64, 1.54, 1.47, 1.46, 1.42,
+    1.39, 1.45, 1.44, 1.42, 1.39, 1.39, 1.38, 1.39, 1.40, 2.44, 2.15, 2.07, 2.04, 2.03, 2.01,
+    1.99, 1.98, 1.98, 1.96, 1.94, 1.92, 1.92, 1.89, 1.90, 1.87, 1.87, 1.75, 1.70, 1.62, 1.51,
+    1.44, 1.41, 1.36, 1.36, 1.32, 1.45, 1.46, 1.48, 1.40, 1.50, 1.50, 2.60, 2.21, 2.15, 2.06,
+    2.00, 1.96,
+];
+
+/// Reference data for one element, as returned by [`element_info`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElementInfo {
+    /// The chemical symbol, such as `"Cu"`.
+    pub symbol: &'static str,
+    /// The atomic number, from 1 (H) to 92 (U).
+    pub atomic_number: u64,
+    /// The standard atomic mass (amu), as from [`standard_atomic_mass`].
+    pub mass: f64,
+    /// The single-bond covalent radius (angstrom). Two atoms are commonly
+    /// taken to be bonded when closer than the sum of their radii times a
+    /// tolerance slightly above 1.
+    pub covalent_radius: f64,
+}
+
+/// Returns the reference data for a chemical symbol, or `None` if the symbol
+/// is not in the element table (H to U). Symbols are matched as by
+/// [`symbol_to_atomic_number`].
+pub fn element_info(symbol: &str) -> Option<ElementInfo> {
+    let idx = element_index(symbol)?;
+    let (symbol, mass) = ELEMENTS[idx];
+    Some(ElementInfo {
+        symbol,
+        atomic_number: idx as u64 + 1,
+        mass,
+        covalent_radius: COVALENT_RADII[idx],
+    })
+}
+
+/// Position of `symbol` in `ELEMENTS`, ignoring surrounding whitespace and
+/// letter case; element symbols stay unique without case.
+fn element_index(symbol: &str) -> Option<usize> {
+    let symbol = symbol.trim();
+    ELEMENTS
+        .iter()
+        .position(|&(s, _)| s.eq_ignore_ascii_case(symbol))
+}
+
 // TODO(rg): Drop the comparisons in matter, integrate with readcon
+/// Converts a chemical symbol to its atomic number, or 0 if it is unknown.
+///
+/// Surrounding whitespace and letter case are ignored, so `" CU "` and `"cu"`
+/// both give 29.
 pub fn symbol_to_atomic_number(symbol: &str) -> u64 {
-    match symbol {
-        "H" => 1,
-        "He" => 2,
-        "Li" => 3,
-        "Be" => 4,
-        "B" => 5,
-        "C" => 6,
-        "N" => 7,
-        "O" => 8,
-        "F" => 9,
-        "Ne" => 10,
-        "Na" => 11,
-        "Mg" => 12,
-        "Al" => 13,
-        "Si" => 14,
-        "P" => 15,
-        "S" => 16,
-        "Cl" => 17,
-        "Ar" => 18,
-        "K" => 19,
-        "Ca" => 20,
-        "Sc" => 21,
-        "Ti" => 22,
-        "V" => 23,
-        "Cr" => 24,
-        "Mn" => 25,
-        "Fe" => 26,
-        "Co" => 27,
-        "Ni" => 28,
-        "Cu" => 29,
-        "Zn" => 30,
-        "Ga" => 31,
-        "Ge" => 32,
-        "As" => 33,
-        "Se" => 34,
-        "Br" => 35,
-        "Kr" => 36,
-        "Rb" => 37,
-        "Sr" => 38,
-        "Y" => 39,
-        "Zr" => 40,
-        "Nb" => 41,
-        "Mo" => 42,
-        "Tc" => 43,
-        "Ru" => 44,
-        "Rh" => 45,
-        "Pd" => 46,
-        "Ag" => 47,
-        "Cd" => 48,
-        "In" => 49,
-        "Sn" => 50,
-        "Sb" => 51,
-        "Te" => 52,
-        "I" => 53,
-        "Xe" => 54,
-        "Cs" => 55,
-        "Ba" => 56,
-        "La" => 57,
-        "Ce" => 58,
-        "Pr" => 59,
-        "Nd" => 60,
-        "Pm" => 61,
-        "Sm" => 62,
-        "Eu" => 63,
-        "Gd" => 64,
-        "Tb" => 65,
-        "Dy" => 66,
-        "Ho" => 67,
-        "Er" => 68,
-        "Tm" => 69,
-        "Yb" => 70,
-        "Lu" => 71,
-        "Hf" => 72,
-        "Ta" => 73,
-        "W" => 74,
-        "Re" => 75,
-        "Os" => 76,
-        "Ir" => 77,
-        "Pt" => 78,
-        "Au" => 79,
-        "Hg" => 80,
-        "Tl" => 81,
-        "Pb" => 82,
-        "Bi" => 83,
-        "Po" => 84,
-        "At" => 85,
-        "Rn" => 86,
-        "Fr" => 87,
-        "Ra" => 88,
-        "Ac" => 89,
-        "Th" => 90,
-        "Pa" => 91,
-        "U" => 92,
-        _ => 0, // Unknown
+    element_index(symbol).map_or(0, |idx| idx as u64 + 1)
+}
+
+/// Converts an atomic number to its chemical symbol.
+///
+/// Returns `None` for 0 and for atomic numbers beyond the element table.
+pub fn atomic_number_to_symbol(atomic_number: u64) -> Option<&'static str> {
+    let idx = usize::try_from(atomic_number.checked_sub(1)?).ok()?;
+    ELEMENTS.get(idx).map(|&(symbol, _)| symbol)
+}
+
+/// Returns the standard atomic mass (amu) of a chemical symbol, or `None` if
+/// the symbol is not in the element table. Symbols are matched as by
+/// [`symbol_to_atomic_number`].
+pub fn standard_atomic_mass(symbol: &str) -> Option<f64> {
+    element_index(symbol).map(|idx| ELEMENTS[idx].1)
+}
+
+/// Cosine of an angle in degrees, exact for right angles.
+fn cos_deg(angle: f64) -> f64 {
+    if angle == 90.0 {
+        0.0
+    } else {
+        angle.to_radians().cos()
+    }
+}
+
+/// Sine of an angle in degrees, exact for right angles.
+fn sin_deg(angle: f64) -> f64 {
+    if angle == 90.0 {
+        1.0
+    } else {
+        angle.to_radians().sin()
+    }
+}
+
+/// Converts cell lengths `[a, b, c]` and angles `[alpha, beta, gamma]` (degrees)
+/// into a 3x3 cell matrix whose rows are the lattice vectors.
+///
+/// Follows the usual crystallographic convention (the same as ASE's
+/// `cellpar_to_cell`): `a` lies along x, `b` lies in the xy plane, and the
+/// matrix is lower-triangular. An orthorhombic cell (all angles exactly 90)
+/// yields an exact diagonal matrix.
+pub fn cell_matrix(lengths: [f64; 3], angles: [f64; 3]) -> [[f64; 3]; 3] {
+    let [a, b, c] = lengths;
+    if angles == [90.0, 90.0, 90.0] {
+        return [[a, 0.0, 0.0], [0.0, b, 0.0], [0.0, 0.0, c]];
     }
+    let cos_alpha = cos_deg(angles[0]);
+    let cos_beta = cos_deg(angles[1]);
+    let cos_gamma = cos_deg(angles[2]);
+    let sin_gamma = sin_deg(angles[2]);
+
+    let cy = (cos_alpha - cos_beta * cos_gamma) / sin_gamma;
+    let cz = (1.0 - cos_beta * cos_beta - cy * cy).max(0.0).sqrt();
+    [
+        [a, 0.0, 0.0],
+        [b * cos_gamma, b * sin_gamma, 0.0],
+        [c * cos_beta, c * cy, c * cz],
+    ]
+}
+
+/// Converts a 3x3 cell matrix whose rows are the lattice vectors into cell
+/// lengths `[a, b, c]` and angles `[alpha, beta, gamma]` (degrees).
+///
+/// This inverts [`cell_matrix`] up to a rotation of the lattice. Exactly
+/// perpendicular vectors give an angle of exactly 90, so an orthorhombic cell
+/// maps back to a diagonal matrix.
+pub fn cell_parameters(m: &[[f64; 3]; 3]) -> ([f64; 3], [f64; 3]) {
+    let dot = |u: [f64; 3], v: [f64; 3]| u[0] * v[0] + u[1] * v[1] + u[2] * v[2];
+    let lengths = m.map(|v| dot(v, v).sqrt());
+    let angle = |i: usize, j: usize| {
+        let d = dot(m[i], m[j]);
+        if d == 0.0 {
+            90.0
+        } else {
+            (d / (lengths[i] * lengths[j]))
+                .clamp(-1.0, 1.0)
+                .acos()
+                .to_degrees()
+        }
+    };
+    (lengths, [angle(1, 2), angle(0, 2), angle(0, 1)])
+}
+
+/// Inverts a 3x3 matrix, returning `None` if it is singular.
+pub fn invert_3x3(m: &[[f64; 3]; 3]) -> Option<[[f64; 3]; 3]> {
+    let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+    if det == 0.0 || !det.is_finite() {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    Some([
+        [
+            (m[1][1] * m[2][2] - m[1][2] * m[2][1]) * inv_det,
+            (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv_det,
+            (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv_det,
+        ],
+        [
+            (m[1][2] * m[2][0] - m[1][0] * m[2][2]) * inv_det,
+            (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv_det,
+            (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv_det,
+        ],
+        [
+            (m[1][0] * m[2][1] - m[1][1] * m[2][0]) * inv_det,
+            (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv_det,
+            (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv_det,
+        ],
+    ])
+}
+
+/// Multiplies a row vector by a 3x3 matrix (`v * m`).
+pub fn row_times_matrix(v: [f64; 3], m: &[[f64; 3]; 3]) -> [f64; 3] {
+    [
+        v[0] * m[0][0] + v[1] * m[1][0] + v[2] * m[2][0],
+        v[0] * m[0][1] + v[1] * m[1][1] + v[2] * m[2][1],
+        v[0] * m[0][2] + v[1] * m[1][2] + v[2] * m[2][2],
+    ]
 }
 
-/// Converts an atomic number to its corresponding chemical symbol.
-pub fn atomic_number_to_symbol(atomic_number: u64) -> &'static str {
-    match atomic_number {
-        1 => "H",
-        2 => "He",
-        3 => "Li",
-        4 => "Be",
-        5 => "B",
-        6 => "C",
-        7 => "N",
-        8 => "O",
-        9 => "F",
-        10 => "Ne",
-        11 => "Na",
-        12 => "Mg",
-        13 => "Al",
-        14 => "Si",
-        15 => "P",
-        16 => "S",
-        17 => "Cl",
-        18 => "Ar",
-        19 => "K",
-        20 => "Ca",
-        21 => "Sc",
-        22 => "Ti",
-        23 => "V",
-        24 => "Cr",
-        25 => "Mn",
-        26 => "Fe",
-        27 => "Co",
-        28 => "Ni",
-        29 => "Cu",
-        30 => "Zn",
-        31 => "Ga",
-        32 => "Ge",
-        33 => "As",
-        34 => "Se",
-        35 => "Br",
-        36 => "Kr",
-        37 => "Rb",
-        38 => "Sr",
-        39 => "Y",
-        40 => "Zr",
-        41 => "Nb",
-        42 => "Mo",
-        43 => "Tc",
-        44 => "Ru",
-        45 => "Rh",
-        46 => "Pd",
-        47 => "Ag",
-        48 => "Cd",
-        49 => "In",
-        50 => "Sn",
-        51 => "Sb",
-        52 => "Te",
-        53 => "I",
-        54 => "Xe",
-        55 => "Cs",
-        56 => "Ba",
-        57 => "La",
-        58 => "Ce",
-        59 => "Pr",
-        60 => "Nd",
-        61 => "Pm",
-        62 => "Sm",
-        63 => "Eu",
-        64 => "Gd",
-        65 => "Tb",
-        66 => "Dy",
-        67 => "Ho",
-        68 => "Er",
-        69 => "Tm",
-        70 => "Yb",
-        71 => "Lu",
-        72 => "Hf",
-        73 => "Ta",
-        74 => "W",
-        75 => "Re",
-        76 => "Os",
-        77 => "Ir",
-        78 => "Pt",
-        79 => "Au",
-        80 => "Hg",
-        81 => "Tl",
-        82 => "Pb",
-        83 => "Bi",
-        84 => "Po",
-        85 => "At",
-        86 => "Rn",
-        87 => "Fr",
-        88 => "Ra",
-        89 => "Ac",
-        90 => "Th",
-        91 => "Pa",
-        92 => "U",
-        _ => "X", // Represents an unknown element
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_element_info() {
+        let cu = element_info("Cu").unwrap();
+        assert_eq!(cu.symbol, "Cu");
+        assert_eq!(cu.atomic_number, 29);
+        assert_eq!(cu.mass, 63.546);
+        assert_eq!(cu.covalent_radius, 1.32);
+        let u = element_info("U").unwrap();
+        assert_eq!((u.atomic_number, u.covalent_radius), (92, 1.96));
+        assert_eq!(element_info("X"), None);
+    }
+
+    #[test]
+    fn test_cell_parameters_inverts_cell_matrix() {
+        let (lengths, angles) = cell_parameters(&cell_matrix([3.0, 3.0, 5.0], [90.0, 90.0, 120.0]));
+        assert_eq!(angles[..2], [90.0, 90.0]);
+        assert!((angles[2] - 120.0).abs() < 1e-12);
+        assert!(
+            lengths
+                .iter()
+                .zip([3.0, 3.0, 5.0])
+                .all(|(l, e)| (l - e).abs() < 1e-12)
+        );
+
+        let diagonal = [[2.0, 0.0, 0.0], [0.0, 3.0, 0.0], [0.0, 0.0, 4.0]];
+        assert_eq!(cell_parameters(&diagonal), ([2.0, 3.0, 4.0], [90.0; 3]));
+    }
+
+    #[test]
+    fn test_atomic_number_symbol_roundtrip() {
+        for z in 1..=ELEMENTS.len() as u64 {
+            let symbol = atomic_number_to_symbol(z).unwrap();
+            assert_eq!(symbol_to_atomic_number(symbol), z);
+        }
+        assert_eq!(atomic_number_to_symbol(29), Some("Cu"));
+        assert_eq!(atomic_number_to_symbol(0), None);
+        assert_eq!(atomic_number_to_symbol(93), None);
+        assert_eq!(atomic_number_to_symbol(u64::MAX), None);
+        assert_eq!(symbol_to_atomic_number("Xx"), 0);
+    }
+
+    #[test]
+    fn test_symbol_to_atomic_number_normalizes() {
+        for symbol in ["h", "H", " H ", "\tH"] {
+            assert_eq!(symbol_to_atomic_number(symbol), 1, "{symbol:?}");
+        }
+        assert_eq!(symbol_to_atomic_number("HE"), 2);
+        assert_eq!(symbol_to_atomic_number("he"), 2);
+        assert_eq!(symbol_to_atomic_number(" CU "), 29);
+        assert_eq!(symbol_to_atomic_number("Uuo"), 0);
+        assert_eq!(symbol_to_atomic_number(""), 0);
+        assert_eq!(symbol_to_atomic_number("H e"), 0);
+    }
+
+    #[test]
+    fn test_element_lookups_agree_on_case() {
+        for symbol in ["cu", "CU", " Cu ", "\tcU"] {
+            assert_eq!(symbol_to_atomic_number(symbol), 29, "{symbol:?}");
+            assert_eq!(standard_atomic_mass(symbol), Some(63.546), "{symbol:?}");
+            assert_eq!(element_info(symbol), element_info("Cu"), "{symbol:?}");
+        }
+        for symbol in ["", "Xx", "C u"] {
+            assert_eq!(symbol_to_atomic_number(symbol), 0, "{symbol:?}");
+            assert_eq!(standard_atomic_mass(symbol), None, "{symbol:?}");
+            assert_eq!(element_info(symbol), None, "{symbol:?}");
+        }
+    }
+
+    #[test]
+    fn test_standard_atomic_mass() {
+        assert_eq!(standard_atomic_mass("Cu"), Some(63.546));
+        assert_eq!(standard_atomic_mass("H"), Some(1.008));
+        assert_eq!(standard_atomic_mass("Xx"), None);
+        assert!(ELEMENTS.iter().all(|&(_, mass)| mass > 0.0));
     }
 }
@@ -2,9 +2,15 @@
 // The Public API - A clean iterator for users of our library
 //=============================================================================
 
-use crate::parser::{parse_single_frame, parse_velocity_section};
+use crate::parser::{
+    ParseOptions, SymbolInterner, is_coordinate_label, is_velocity_component_line,
+    is_velocity_data_line, parse_frame_header, parse_single_frame_interned,
+    parse_velocity_section_with_options,
+};
 use crate::{error, types};
+use std::collections::BTreeSet;
 use std::iter::Peekable;
+use std::ops::Range;
 use std::path::Path;
 
 /// An iterator that lazily parses simulation frames from a `.con` or `.convel`
@@ -17,8 +23,17 @@ use std::path::Path;
 ///
 /// The iterator yields items of type `Result<ConFrame, ParseError>`, allowing for
 /// robust error handling for each frame.
+///
+/// Chemical symbols are interned for the lifetime of the iterator, so every
+/// frame it yields shares one `Rc<String>` per distinct symbol.
 pub struct ConFrameIterator<'a> {
     lines: Peekable<std::str::Lines<'a>>,
+    options: ParseOptions,
+    symbols: SymbolInterner,
+    /// Index of the next frame to be read or skipped.
+    frame_index: usize,
+    /// Set once a strict-mode error has been yielded.
+    finished: bool,
 }
 
 impl<'a> ConFrameIterator<'a> {
@@ -28,8 +43,19 @@ impl<'a> ConFrameIterator<'a> {
     ///
     /// * `file_contents` - A string slice containing the text of one or more `.con` frames.
     pub fn new(file_contents: &'a str) -> Self {
+        Self::with_options(file_contents, ParseOptions::default())
+    }
+
+    /// Creates a new `ConFrameIterator` that parses with the given `ParseOptions`.
+    ///
+    /// A leading UTF-8 byte order mark is skipped.
+    pub fn with_options(file_contents: &'a str, options: ParseOptions) -> Self {
         ConFrameIterator {
-            lines: file_contents.lines().peekable(),
+            lines: strip_bom(file_contents).lines().peekable(),
+            options,
+            symbols: SymbolInterner::default(),
+            frame_index: 0,
+            finished: false,
         }
     }
 
@@ -45,10 +71,23 @@ impl<'a> ConFrameIterator<'a> {
     /// * `Some(Err(ParseError::...))` if there's an error parsing the header.
     /// * `None` if the iterator is already at the end.
     pub fn forward(&mut self) -> Option<Result<(), error::ParseError>> {
+        self.skip_frame(|_| {}).map(|result| result.map(drop))
+    }
+
+    /// Skips the next frame as [`forward`](Self::forward) does, reporting
+    /// what the skipped lines revealed about it. The symbol line of each
+    /// coordinate block is passed to `on_symbol`, so callers that do not need
+    /// the symbols collect nothing.
+    fn skip_frame(
+        &mut self,
+        mut on_symbol: impl FnMut(&'a str),
+    ) -> Option<Result<SkippedFrame, error::ParseError>> {
+        self.skip_lines_before_frame();
         // Skip frame by parsing only required header fields to avoid full parsing overhead
-        if self.lines.peek().is_none() {
+        if self.finished || self.lines.peek().is_none() {
             return None;
         }
+        self.frame_index += 1;
 
         // Manually consume the first 6 lines of the header, which we don't need for skipping.
         for _ in 0..6 {
@@ -66,9 +105,9 @@ impl<'a> ConFrameIterator<'a> {
             None => return Some(Err(error::ParseError::IncompleteHeader)),
         };
 
-        // Line 8: natms_per_type. We need this to sum the total number of atoms.
+        // Line 8: natms_per_type. We need this to know how many atom lines to skip.
         let natms_per_type: Vec<usize> = match self.lines.next() {
-            Some(line) => match crate::parser::parse_line_of_n(line, natm_types) {
+            Some(line) => match crate::parser::parse_type_counts(line, natm_types) {
                 Ok(v) => v,
                 Err(e) => return Some(Err(e)),
             },
@@ -80,29 +119,52 @@ impl<'a> ConFrameIterator<'a> {
             return Some(Err(error::ParseError::IncompleteHeader));
         }
 
-        // Calculate how many more lines to skip for coordinate blocks.
-        let total_atoms: usize = natms_per_type.iter().sum();
-        // For each atom type, there is a symbol line and a "Coordinates..." line.
-        let non_atom_lines = natm_types * 2;
-        let lines_to_skip = total_atoms + non_atom_lines;
-
-        // Advance the iterator by skipping the coordinate block lines.
-        for _ in 0..lines_to_skip {
-            if self.lines.next().is_none() {
-                // The file ended before the header's promise was fulfilled.
-                return Some(Err(error::ParseError::IncompleteFrame));
+        // Skip the coordinate blocks: a symbol line, a "Coordinates..." line
+        // and the atom lines for each type. Only the symbol is reported.
+        for &count in &natms_per_type {
+            match self.lines.next() {
+                Some(symbol) => on_symbol(symbol.trim()),
+                None => return Some(Err(error::ParseError::IncompleteFrame)),
+            }
+            let has_label = !self.options.optional_coordinate_labels
+                || self
+                    .lines
+                    .peek()
+                    .is_some_and(|line| is_coordinate_label(line, count));
+            for _ in 0..count.saturating_add(has_label as usize) {
+                if self.lines.next().is_none() {
+                    // The file ended before the header's promise was fulfilled.
+                    return Some(Err(error::ParseError::IncompleteFrame));
+                }
             }
         }
+        let total_atoms = natms_per_type
+            .iter()
+            .fold(0usize, |sum, &n| sum.saturating_add(n));
 
         // Check for an optional velocity section (blank separator followed by
         // velocity blocks with the same structure as coordinate blocks).
-        if let Some(line) = self.lines.peek() {
-            if line.trim().is_empty() {
-                // Consume the blank separator
-                self.lines.next();
-                // Skip the velocity blocks: same structure as coordinate blocks
-                let vel_lines_to_skip = total_atoms + non_atom_lines;
-                for _ in 0..vel_lines_to_skip {
+        let has_velocities = if self.options.skip_blank_lines {
+            self.velocity_section_follows()
+        } else {
+            self.lines.peek().is_some_and(|line| line.trim().is_empty())
+        };
+        if has_velocities {
+            // Consume the blank separator
+            self.lines.next();
+            // Skip the velocity blocks: same structure as coordinate blocks,
+            // minus the symbol lines if the options say there are none and
+            // minus any "Velocities of Component N" lines left out.
+            for &count in &natms_per_type {
+                if self.options.velocity_symbol_line && self.lines.next().is_none() {
+                    return Some(Err(error::ParseError::IncompleteVelocitySection));
+                }
+                let has_label = !self.options.optional_velocity_labels
+                    || self
+                        .lines
+                        .peek()
+                        .is_some_and(|line| is_velocity_component_line(line));
+                for _ in 0..count.saturating_add(has_label as usize) {
                     if self.lines.next().is_none() {
                         return Some(Err(error::ParseError::IncompleteVelocitySection));
                     }
@@ -110,7 +172,190 @@ impl<'a> ConFrameIterator<'a> {
             }
         }
 
-        Some(Ok(()))
+        Some(Ok(SkippedFrame {
+            total_atoms,
+            has_velocities,
+        }))
+    }
+
+    /// Skips up to `n` frames without fully parsing them, as repeated calls
+    /// to [`forward`](Self::forward) would.
+    ///
+    /// Returns how many frames were skipped, which is less than `n` only if
+    /// the input ran out first.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first frame that cannot be skipped and returns its error.
+    pub fn skip_frames(&mut self, n: usize) -> Result<usize, error::ParseError> {
+        for skipped in 0..n {
+            match self.forward() {
+                Some(Ok(())) => {}
+                Some(Err(e)) => return Err(e),
+                None => return Ok(skipped),
+            }
+        }
+        Ok(n)
+    }
+
+    /// Returns an iterator over every `k`-th frame, starting with the first.
+    ///
+    /// Only the yielded frames are parsed; the `k - 1` frames between them are
+    /// skipped with [`skip_frames`](Self::skip_frames). With `k == 1` this
+    /// behaves like the iterator itself. An error while skipping is yielded
+    /// in place of the next frame and ends the iteration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
+    pub fn stride(
+        mut self,
+        k: usize,
+    ) -> impl Iterator<Item = Result<types::ConFrame, error::ParseError>> + 'a {
+        assert!(k > 0, "stride must be at least 1");
+        let mut first = true;
+        std::iter::from_fn(move || {
+            if !std::mem::take(&mut first)
+                && let Err(e) = self.skip_frames(k - 1)
+            {
+                self.finished = true;
+                return Some(Err(e));
+            }
+            self.next()
+        })
+    }
+
+    /// Parses the header of the next frame without advancing the iterator.
+    ///
+    /// Returns `None` at the end of the input. A following call to `next()`
+    /// or [`forward`](Self::forward) still reads the whole frame.
+    pub fn peek_header(&mut self) -> Option<Result<types::FrameHeader, error::ParseError>> {
+        self.skip_lines_before_frame();
+        if self.finished || self.lines.peek().is_none() {
+            return None;
+        }
+        Some(parse_frame_header(&mut self.lines.clone()))
+    }
+
+    /// Returns an iterator over the frames whose header satisfies `pred`.
+    ///
+    /// Each header is parsed once and passed to `pred`; rejected frames are
+    /// skipped with [`forward`](Self::forward), so their atoms are never
+    /// parsed. A frame with a malformed header is not offered to `pred` and
+    /// its error is yielded as `next()` would. An error while skipping a
+    /// rejected frame is yielded in its place and ends the iteration.
+    pub fn filter_by_header<F>(
+        mut self,
+        mut pred: F,
+    ) -> impl Iterator<Item = Result<types::ConFrame, error::ParseError>> + 'a
+    where
+        F: FnMut(&types::FrameHeader) -> bool + 'a,
+    {
+        std::iter::from_fn(move || {
+            loop {
+                match self.peek_header()? {
+                    Ok(header) if !pred(&header) => {
+                        if let Some(Err(e)) = self.forward() {
+                            self.finished = true;
+                            return Some(Err(e));
+                        }
+                    }
+                    _ => return self.next(),
+                }
+            }
+        })
+    }
+
+    /// Byte offset of the next unread line within `file_contents`, which must
+    /// be the string this iterator was created from.
+    fn byte_offset(&mut self, file_contents: &'a str) -> usize {
+        self.lines.peek().map_or(file_contents.len(), |line| {
+            line.as_ptr() as usize - file_contents.as_ptr() as usize
+        })
+    }
+
+    /// Parses one frame, and its velocity section if present, from the
+    /// current position.
+    fn parse_next_frame(&mut self) -> Result<types::ConFrame, error::ParseError> {
+        let mut frame = parse_single_frame_interned(
+            &mut self.lines,
+            &mut self.symbols,
+            self.options.optional_coordinate_labels,
+        )?;
+        // In tolerant mode, blank lines not followed by velocity blocks just
+        // separate frames and are skipped on the next call.
+        if self.options.skip_blank_lines && !self.velocity_section_follows() {
+            return Ok(frame);
+        }
+        // Attempt to parse optional velocity section
+        parse_velocity_section_with_options(
+            &mut self.lines,
+            &frame.header,
+            &mut frame.atom_data,
+            &self.options,
+        )?;
+        Ok(frame)
+    }
+
+    /// Advances line by line until the remaining input starts with a frame
+    /// that parses completely, or the input is exhausted.
+    fn resync(&mut self) {
+        while self.lines.peek().is_some() {
+            let mut probe = self.lines.clone();
+            let mut symbols = SymbolInterner::default();
+            let optional_labels = self.options.optional_coordinate_labels;
+            if parse_single_frame_interned(&mut probe, &mut symbols, optional_labels).is_ok() {
+                return;
+            }
+            self.lines.next();
+        }
+    }
+
+    /// Consumes the lines the options allow in front of a frame: blank lines
+    /// with `skip_blank_lines`, and lines starting with `comment_prefix`.
+    fn skip_lines_before_frame(&mut self) {
+        let skip_blank = self.options.skip_blank_lines;
+        let prefix = self
+            .options
+            .comment_prefix
+            .as_deref()
+            .filter(|p| !p.is_empty());
+        while self.lines.peek().is_some_and(|line| {
+            (skip_blank && line.trim().is_empty())
+                || prefix.is_some_and(|prefix| line.trim_start().starts_with(prefix))
+        }) {
+            self.lines.next();
+        }
+    }
+
+    /// Looks ahead (without consuming) to decide whether the upcoming blank
+    /// lines introduce a velocity section rather than separate two frames.
+    /// Extra blank lines before a velocity section are consumed so exactly
+    /// one separator remains.
+    fn velocity_section_follows(&mut self) -> bool {
+        let mut lookahead = self.lines.clone();
+        let mut blank_lines = 0;
+        while lookahead.peek().is_some_and(|line| line.trim().is_empty()) {
+            lookahead.next();
+            blank_lines += 1;
+        }
+        if blank_lines == 0 {
+            return false;
+        }
+        if self.options.velocity_symbol_line {
+            lookahead.next();
+        }
+        let optional = self.options.optional_velocity_labels;
+        let starts_block = lookahead.next().is_some_and(|line| {
+            is_velocity_component_line(line) || (optional && is_velocity_data_line(line))
+        });
+        if !starts_block {
+            return false;
+        }
+        for _ in 1..blank_lines {
+            self.lines.next();
+        }
+        true
     }
 }
 
@@ -123,28 +368,254 @@ impl<'a> Iterator for ConFrameIterator<'a> {
 
     /// Advances the iterator and attempts to parse the next frame.
     ///
-    /// This method will return `None` only when there are no more lines to consume.
-    /// If there are lines but they do not form a complete frame, it will return
-    /// `Some(Err(ParseError::...))`.
+    /// This method returns `None` when there are no more lines to consume.
+    /// If there are lines but they do not form a complete frame, it returns
+    /// `Some(Err(ParseError::...))` after passing the error to
+    /// `ParseOptions::on_error`. The iterator then ends, unless
+    /// `ParseOptions::recover` is set, in which case it resumes at the next
+    /// parseable frame.
     fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        self.skip_lines_before_frame();
         // If there are no more lines at all, the iterator is exhausted.
         if self.lines.peek().is_none() {
             return None;
         }
-        // Otherwise, attempt to parse the next frame from the available lines.
-        let mut frame = match parse_single_frame(&mut self.lines) {
-            Ok(f) => f,
-            Err(e) => return Some(Err(e)),
-        };
-        // Attempt to parse optional velocity section
-        match parse_velocity_section(&mut self.lines, &frame.header, &mut frame.atom_data) {
-            Ok(_) => {}
-            Err(e) => return Some(Err(e)),
+        let index = self.frame_index;
+        self.frame_index += 1;
+        let start = self.lines.clone();
+        match self.parse_next_frame() {
+            Ok(frame) => Some(Ok(frame)),
+            Err(e) => {
+                if let Some(on_error) = &self.options.on_error {
+                    (on_error.borrow_mut())(index, &e);
+                }
+                if self.options.recover {
+                    // Restart just past the failed frame's first line.
+                    self.lines = start;
+                    self.lines.next();
+                    self.resync();
+                } else {
+                    self.finished = true;
+                }
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Where a frame starts within a file's contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameOffset {
+    /// Byte offset of the frame's first header line.
+    pub byte_offset: usize,
+    /// Zero-based line index of the frame's first header line.
+    pub line_index: usize,
+}
+
+/// Byte offsets of every frame in a file, for random access to single frames.
+///
+/// Building the index reads each frame header and skips the atom lines, as
+/// [`ConFrameIterator::forward`] does, so it is much cheaper than a full
+/// parse. A malformed atom line is only reported when its frame is read with
+/// [`FrameIndex::frame`]. With the `serde` feature the index can be persisted
+/// next to the file and reused, as long as the file does not change.
+///
+/// # Example
+/// ```
+/// # use readcon_core::iterators::FrameIndex;
+/// # let contents = std::fs::read_to_string("resources/test/tiny_multi_cuh2.con").unwrap();
+/// let index = FrameIndex::build(&contents).unwrap();
+/// let last = index.frame(&contents, index.len() - 1).unwrap();
+/// # assert_eq!(last.atom_data.len(), 4);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameIndex {
+    frames: Vec<FrameOffset>,
+}
+
+impl FrameIndex {
+    /// Scans `file_contents` and records where each frame begins.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error met while skipping frames, e.g. a malformed
+    /// header or a truncated frame.
+    pub fn build(file_contents: &str) -> Result<Self, error::ParseError> {
+        let mut frames = Vec::new();
+        let mut line_index = 0;
+        let mut previous = 0;
+        for span in frame_spans(file_contents) {
+            let byte_offset = span?.start;
+            line_index += file_contents.as_bytes()[previous..byte_offset]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count();
+            previous = byte_offset;
+            frames.push(FrameOffset {
+                byte_offset,
+                line_index,
+            });
+        }
+        Ok(FrameIndex { frames })
+    }
+
+    /// Number of indexed frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if no frames were indexed.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Start positions of all indexed frames, in file order.
+    pub fn offsets(&self) -> &[FrameOffset] {
+        &self.frames
+    }
+
+    /// Parses frame `i` from `file_contents`, which must be the contents the
+    /// index was built from.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`error::ParseError::FrameIndexOutOfRange`] if `i` is past the
+    /// last frame, [`error::ParseError::IncompleteFrame`] if the frame's span
+    /// does not fit `file_contents`, or the frame's parse error.
+    pub fn frame(
+        &self,
+        file_contents: &str,
+        i: usize,
+    ) -> Result<types::ConFrame, error::ParseError> {
+        let start = self
+            .frames
+            .get(i)
+            .ok_or(error::ParseError::FrameIndexOutOfRange {
+                index: i,
+                len: self.frames.len(),
+            })?
+            .byte_offset;
+        let end = self
+            .frames
+            .get(i + 1)
+            .map_or(file_contents.len(), |next| next.byte_offset);
+        let text = file_contents
+            .get(start..end)
+            .ok_or(error::ParseError::IncompleteFrame)?;
+        ConFrameIterator::new(text)
+            .next()
+            .unwrap_or(Err(error::ParseError::IncompleteFrame))
+    }
+}
+
+/// Reads frames through a [`FrameIndex`], from either end.
+///
+/// Unlike [`ConFrameIterator`], which streams frames front to back, this
+/// reader knows where every frame starts, so it implements
+/// [`DoubleEndedIterator`]: `next_back()` yields frames from the end of the
+/// file. Mixing `next()` and `next_back()` meets in the middle, after which
+/// both return `None`. It is also an [`ExactSizeIterator`], so `len()` gives
+/// the number of frames left before any are parsed.
+pub struct IndexedFrameReader<'a> {
+    file_contents: &'a str,
+    index: FrameIndex,
+    /// Index of the next frame returned by `next()`.
+    front: usize,
+    /// One past the index of the next frame returned by `next_back()`.
+    back: usize,
+}
+
+impl<'a> IndexedFrameReader<'a> {
+    /// Indexes `file_contents` and returns a reader over all of its frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error met while building the [`FrameIndex`].
+    pub fn new(file_contents: &'a str) -> Result<Self, error::ParseError> {
+        Ok(Self::from_index(
+            file_contents,
+            FrameIndex::build(file_contents)?,
+        ))
+    }
+
+    /// Creates a reader from an index previously built for `file_contents`.
+    pub fn from_index(file_contents: &'a str, index: FrameIndex) -> Self {
+        let back = index.len();
+        IndexedFrameReader {
+            file_contents,
+            index,
+            front: 0,
+            back,
         }
-        Some(Ok(frame))
+    }
+
+    /// The index this reader walks.
+    pub fn index(&self) -> &FrameIndex {
+        &self.index
     }
 }
 
+impl Iterator for IndexedFrameReader<'_> {
+    type Item = Result<types::ConFrame, error::ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        let frame = self.index.frame(self.file_contents, self.front);
+        self.front += 1;
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+/// The remaining frame count is known from the index, so `len()` is exact.
+impl ExactSizeIterator for IndexedFrameReader<'_> {}
+
+impl DoubleEndedIterator for IndexedFrameReader<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.index.frame(self.file_contents, self.back))
+    }
+}
+
+/// Finds the byte span of every frame by reading the headers and skipping
+/// the atom lines with [`ConFrameIterator::forward`].
+///
+/// A frame that cannot be skipped yields an `Err` in its place; the scan then
+/// resumes at the next line that starts a parseable frame.
+fn frame_spans(file_contents: &str) -> Vec<Result<Range<usize>, error::ParseError>> {
+    let mut spans = Vec::new();
+    let mut scanner = ConFrameIterator::new(file_contents);
+    loop {
+        let start_lines = scanner.lines.clone();
+        let start = scanner.byte_offset(file_contents);
+        match scanner.forward() {
+            None => break,
+            Some(Ok(())) => spans.push(Ok(start..scanner.byte_offset(file_contents))),
+            Some(Err(e)) => {
+                spans.push(Err(e));
+                scanner.lines = start_lines;
+                scanner.lines.next();
+                scanner.resync();
+            }
+        }
+    }
+    spans
+}
+
 /// Size threshold below which we use `read_to_string` instead of mmap.
 /// For small files, the fixed overhead of mmap (VMA creation, page fault,
 /// munmap) exceeds the cost of a simple `read` syscall + heap allocation.
@@ -153,11 +624,29 @@ const MMAP_THRESHOLD: u64 = 64 * 1024;
 
 /// Reads file contents, choosing between `read_to_string` (small files) and
 /// mmap (large files) based on [`MMAP_THRESHOLD`].
-fn read_file_contents(path: &Path) -> Result<FileContents, Box<dyn std::error::Error>> {
+///
+/// With the `gzip` feature, files ending in `.gz` or starting with the gzip
+/// magic bytes are decompressed into memory instead.
+pub(crate) fn read_file_contents(path: &Path) -> Result<FileContents, Box<dyn std::error::Error>> {
     let file = std::fs::File::open(path)?;
+    #[cfg(feature = "gzip")]
+    let file = {
+        use std::io::{BufRead, Read};
+        let mut reader = std::io::BufReader::new(file);
+        if is_gzip(path, reader.fill_buf()?) {
+            let mut bytes = Vec::new();
+            flate2::bufread::MultiGzDecoder::new(reader).read_to_end(&mut bytes)?;
+            return Ok(FileContents::Owned(String::from_utf8(bytes)?));
+        }
+        // The peeked bytes are dropped with the buffer, which is fine: both
+        // paths below read from the start of the file regardless of the cursor.
+        reader.into_inner()
+    };
     let metadata = file.metadata()?;
     if metadata.len() < MMAP_THRESHOLD {
-        let contents = std::fs::read_to_string(path)?;
+        // Decode separately so invalid UTF-8 surfaces as a `FromUtf8Error`
+        // rather than an opaque `io::ErrorKind::InvalidData`.
+        let contents = String::from_utf8(std::fs::read(path)?)?;
         Ok(FileContents::Owned(contents))
     } else {
         let mmap = unsafe { memmap2::Mmap::map(&file)? };
@@ -165,18 +654,171 @@ fn read_file_contents(path: &Path) -> Result<FileContents, Box<dyn std::error::E
     }
 }
 
+/// Whether `path` names a gzip file, judged by its extension or, failing
+/// that, by `head`, the first bytes of the file.
+#[cfg(feature = "gzip")]
+fn is_gzip(path: &Path, head: &[u8]) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz") || head.starts_with(&[0x1f, 0x8b])
+}
+
 /// Holds file contents either as an owned String or a memory-mapped region.
-enum FileContents {
+pub(crate) enum FileContents {
     Owned(String),
     Mapped(memmap2::Mmap),
 }
 
 impl FileContents {
-    fn as_str(&self) -> Result<&str, std::str::Utf8Error> {
-        match self {
-            FileContents::Owned(s) => Ok(s.as_str()),
-            FileContents::Mapped(m) => std::str::from_utf8(m),
+    /// The file text as UTF-8, without any leading byte order mark.
+    pub(crate) fn as_str(&self) -> Result<&str, std::str::Utf8Error> {
+        let text = match self {
+            FileContents::Owned(s) => s.as_str(),
+            FileContents::Mapped(m) => std::str::from_utf8(m)?,
+        };
+        Ok(strip_bom(text))
+    }
+}
+
+/// Drops a leading UTF-8 byte order mark, which some editors prepend and which
+/// would otherwise end up glued to the first header line.
+fn strip_bom(text: &str) -> &str {
+    text.strip_prefix('\u{FEFF}').unwrap_or(text)
+}
+
+/// Streams frames from a buffered reader, holding the text of only one frame
+/// at a time.
+///
+/// Where [`ConFrameIterator`] borrows the whole file, this reads line by line,
+/// so a large or compressed trajectory never has to sit in memory at once.
+/// Frames are split by the line counts in their headers, which assumes the
+/// layout of [`ParseOptions::default`]: every block has its symbol and
+/// "Coordinates/Velocities of Component N" lines, and a blank line after the
+/// coordinate blocks starts a velocity section.
+///
+/// As with [`ConFrameIterator`] in its default strict mode, iteration stops
+/// after the first error: a malformed header leaves no reliable way to find
+/// the next frame, so the reader returns `None` from then on.
+///
+/// # Example
+///
+/// ```
+/// use readcon_core::iterators::ConFrameReader;
+///
+/// let reader = ConFrameReader::open("resources/test/tiny_multi_cuh2.con".as_ref()).unwrap();
+/// let frames: Vec<_> = reader.map(Result::unwrap).collect();
+/// assert_eq!(frames.len(), 2);
+/// ```
+pub struct ConFrameReader<R> {
+    reader: R,
+    /// Text of the frame being assembled.
+    buffer: String,
+    /// A line read past the end of the previous frame, or empty.
+    pending: String,
+    /// Set once an error has been returned; later calls return `None`.
+    finished: bool,
+}
+
+impl ConFrameReader<Box<dyn std::io::BufRead>> {
+    /// Opens `path` for streaming. With the `gzip` feature, gzip input
+    /// (detected as in [`read_all_frames`]) is decompressed on the fly.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        #[cfg(feature = "gzip")]
+        {
+            use std::io::BufRead;
+            let mut reader = std::io::BufReader::new(file);
+            if is_gzip(path, reader.fill_buf()?) {
+                let decoder = flate2::bufread::MultiGzDecoder::new(reader);
+                return Ok(Self::new(Box::new(std::io::BufReader::new(decoder))));
+            }
+            Ok(Self::new(Box::new(reader)))
+        }
+        #[cfg(not(feature = "gzip"))]
+        Ok(Self::new(Box::new(std::io::BufReader::new(file))))
+    }
+}
+
+impl<R: std::io::BufRead> ConFrameReader<R> {
+    /// Creates a reader over `reader`, which should start at a frame.
+    pub fn new(reader: R) -> Self {
+        ConFrameReader {
+            reader,
+            buffer: String::new(),
+            pending: String::new(),
+            finished: false,
+        }
+    }
+
+    /// Appends the next line to the frame text, returning `false` at the end
+    /// of the input.
+    fn read_line(&mut self) -> std::io::Result<bool> {
+        if !self.pending.is_empty() {
+            self.buffer.push_str(&self.pending);
+            self.pending.clear();
+            return Ok(true);
+        }
+        Ok(self.reader.read_line(&mut self.buffer)? > 0)
+    }
+
+    /// Appends up to `n` lines, returning `false` if the input ended first.
+    fn read_lines(&mut self, n: usize) -> std::io::Result<bool> {
+        for _ in 0..n {
+            if !self.read_line()? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Collects the text of the next frame into `buffer`, returning `false`
+    /// if no input is left. A frame cut short by the end of the input or by
+    /// a malformed header is kept as is, for the parser to report.
+    fn read_frame_text(&mut self) -> std::io::Result<bool> {
+        self.buffer.clear();
+        if !self.read_line()? {
+            return Ok(false);
+        }
+        if !self.read_lines(8)? {
+            return Ok(true);
+        }
+        let counts: Option<Vec<usize>> = self
+            .buffer
+            .lines()
+            .nth(7)
+            .and_then(|line| line.split_whitespace().map(|n| n.parse().ok()).collect());
+        let Some(counts) = counts else {
+            return Ok(true);
+        };
+        // Each block is a symbol line and a component line, then its atoms.
+        let block_lines = counts
+            .iter()
+            .fold(0usize, |sum, &n| sum.saturating_add(n.saturating_add(2)));
+        if !self.read_lines(block_lines)? {
+            return Ok(true);
+        }
+        if self.reader.read_line(&mut self.pending)? > 0 && self.pending.trim().is_empty() {
+            self.read_line()?;
+            self.read_lines(block_lines)?;
+        }
+        Ok(true)
+    }
+}
+
+impl<R: std::io::BufRead> Iterator for ConFrameReader<R> {
+    type Item = Result<types::ConFrame, Box<dyn std::error::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
         }
+        let item = match self.read_frame_text() {
+            Ok(false) => None,
+            Ok(true) => ConFrameIterator::new(strip_bom(&self.buffer))
+                .next()
+                .map(|frame| frame.map_err(Into::into)),
+            Err(e) => Some(Err(e.into())),
+        };
+        self.finished = !matches!(item, Some(Ok(_)));
+        item
     }
 }
 
@@ -185,7 +827,8 @@ impl FileContents {
 /// For files smaller than 64 KiB, uses a simple `read_to_string` to avoid
 /// the fixed overhead of mmap (VMA creation, page fault, munmap). For larger
 /// trajectory files, uses memory-mapped I/O to let the OS page cache handle
-/// the data.
+/// the data. With the `gzip` feature, gzip-compressed files are detected and
+/// decompressed into memory first.
 pub fn read_all_frames(path: &Path) -> Result<Vec<types::ConFrame>, Box<dyn std::error::Error>> {
     let contents = read_file_contents(path)?;
     let text = contents.as_str()?;
@@ -194,6 +837,207 @@ pub fn read_all_frames(path: &Path) -> Result<Vec<types::ConFrame>, Box<dyn std:
     Ok(frames?)
 }
 
+/// Reads all frames from a file without blocking the async runtime on disk
+/// I/O, for use inside tokio tasks such as the RPC server's.
+///
+/// The file is read with `tokio::fs`, which moves the blocking reads to
+/// tokio's blocking pool, so a slow disk or network mount stalls only the
+/// awaiting task. Parsing runs on the calling task rather than on
+/// `spawn_blocking`: frames share their symbols through `Rc` and cannot be
+/// sent back from another thread. Gzip input is handled as in
+/// [`read_all_frames`].
+///
+/// Requires the `async` feature.
+#[cfg(feature = "async")]
+pub async fn read_all_frames_async(
+    path: &Path,
+) -> Result<Vec<types::ConFrame>, Box<dyn std::error::Error>> {
+    let bytes = tokio::fs::read(path).await?;
+    #[cfg(feature = "gzip")]
+    let bytes = if is_gzip(path, &bytes) {
+        use std::io::Read;
+        let mut decoded = Vec::new();
+        flate2::read::MultiGzDecoder::new(bytes.as_slice()).read_to_end(&mut decoded)?;
+        decoded
+    } else {
+        bytes
+    };
+    let text = strip_bom(std::str::from_utf8(&bytes)?);
+    let frames: Result<Vec<_>, _> = ConFrameIterator::new(text).collect();
+    Ok(frames?)
+}
+
+/// Reads at most `n` frames from the start of a file.
+///
+/// Parsing stops after the `n`th frame, so previewing a large trajectory does
+/// not pay for the rest of it; when the file is memory-mapped the tail pages
+/// are never touched. Fewer than `n` frames are returned if the file is
+/// shorter.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or any of the first `n` frames
+/// is malformed.
+pub fn read_n_frames(
+    path: &Path,
+    n: usize,
+) -> Result<Vec<types::ConFrame>, Box<dyn std::error::Error>> {
+    let contents = read_file_contents(path)?;
+    let text = contents.as_str()?;
+    let frames: Result<Vec<_>, _> = ConFrameIterator::new(text).take(n).collect();
+    Ok(frames?)
+}
+
+/// Reads frames from a file until the first parse error.
+///
+/// Returns every frame that parsed cleanly before the error, together with the
+/// error that stopped parsing (`None` if the whole file parsed). Unlike a
+/// recovering reader, no attempt is made to resynchronise after the error,
+/// which makes this suited to truncated or partially-downloaded files.
+///
+/// # Errors
+///
+/// Only I/O and UTF-8 errors are returned as `Err`; parse errors are reported
+/// in the second tuple element.
+pub fn read_valid_prefix(
+    path: &Path,
+) -> Result<(Vec<types::ConFrame>, Option<error::ParseError>), Box<dyn std::error::Error>> {
+    let contents = read_file_contents(path)?;
+    let text = contents.as_str()?;
+    let mut frames = Vec::new();
+    for result in ConFrameIterator::new(text) {
+        match result {
+            Ok(frame) => frames.push(frame),
+            Err(e) => return Ok((frames, Some(e))),
+        }
+    }
+    Ok((frames, None))
+}
+
+/// Parses every frame in `file_contents` and reports all the errors found.
+///
+/// This is a linting pass: the iterator runs with [`ParseOptions::recover`]
+/// set, so a malformed frame does not hide errors in the frames after it.
+/// Each error is paired with the index of the frame attempt that produced it,
+/// counting the frames that parsed. An empty result means the contents parse
+/// cleanly.
+///
+/// [`ParseOptions::recover`]: crate::parser::ParseOptions::recover
+pub fn validate(file_contents: &str) -> Vec<(usize, error::ParseError)> {
+    let options = ParseOptions {
+        recover: true,
+        ..ParseOptions::default()
+    };
+    ConFrameIterator::with_options(file_contents, options)
+        .enumerate()
+        .filter_map(|(index, result)| result.err().map(|e| (index, e)))
+        .collect()
+}
+
+/// Counts the frames in a file without constructing them.
+///
+/// Walks the file with [`ConFrameIterator::forward`], which reads each header
+/// but skips the atom lines, and goes through the same mmap threshold as
+/// [`read_all_frames`]. Coordinates are not parsed, so malformed numbers are
+/// not detected; a truncated or malformed frame structure is.
+///
+/// # Errors
+///
+/// Returns the first error, whether I/O, UTF-8 or parsing. Use
+/// [`count_valid_prefix`] to keep the count of frames seen before a parse
+/// error instead.
+pub fn count_frames(path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let (count, err) = count_valid_prefix(path)?;
+    match err {
+        Some(e) => Err(Box::new(e)),
+        None => Ok(count),
+    }
+}
+
+/// Counts the frames in a file until the first parse error.
+///
+/// The counting counterpart of [`read_valid_prefix`]: returns the number of
+/// frames skipped cleanly, together with the parse error that stopped the
+/// walk (`None` if the whole file was read).
+pub fn count_valid_prefix(
+    path: &Path,
+) -> Result<(usize, Option<error::ParseError>), Box<dyn std::error::Error>> {
+    let contents = read_file_contents(path)?;
+    let text = contents.as_str()?;
+    let mut iter = ConFrameIterator::new(text);
+    let mut count = 0;
+    while let Some(result) = iter.forward() {
+        match result {
+            Ok(()) => count += 1,
+            Err(e) => return Ok((count, Some(e))),
+        }
+    }
+    Ok((count, None))
+}
+
+/// What [`ConFrameIterator::skip_frame`] learned about a frame it skipped.
+struct SkippedFrame {
+    total_atoms: usize,
+    has_velocities: bool,
+}
+
+/// Summary of a trajectory file, as returned by [`stats`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrajectoryStats {
+    /// Number of frames in the file.
+    pub frames: usize,
+    /// Fewest atoms in any frame (0 for a file without frames).
+    pub min_atoms: usize,
+    /// Most atoms in any frame (0 for a file without frames).
+    pub max_atoms: usize,
+    /// Mean number of atoms per frame (0.0 for a file without frames).
+    pub mean_atoms: f64,
+    /// Every chemical symbol that appears in some frame, in sorted order.
+    pub symbols: BTreeSet<String>,
+    /// Whether at least one frame carries a velocity section.
+    pub has_velocities: bool,
+}
+
+/// Summarises a trajectory file without parsing any atom lines.
+///
+/// Like [`count_frames`], this walks the file with the skipping logic of
+/// [`ConFrameIterator::forward`]: atom counts come from each frame's header
+/// and symbols from the block symbol lines, so coordinates are never parsed
+/// and malformed numbers go undetected.
+///
+/// # Errors
+///
+/// Returns the first error, whether I/O, UTF-8 or parsing.
+pub fn stats(path: &Path) -> Result<TrajectoryStats, Box<dyn std::error::Error>> {
+    let contents = read_file_contents(path)?;
+    let text = contents.as_str()?;
+    let mut iter = ConFrameIterator::new(text);
+    let mut stats = TrajectoryStats {
+        min_atoms: usize::MAX,
+        ..TrajectoryStats::default()
+    };
+    let mut atoms_sum = 0.0;
+    let mut record_symbol = |symbol: &str| {
+        if !stats.symbols.contains(symbol) {
+            stats.symbols.insert(symbol.to_string());
+        }
+    };
+    while let Some(result) = iter.skip_frame(&mut record_symbol) {
+        let frame = result?;
+        stats.frames += 1;
+        stats.min_atoms = stats.min_atoms.min(frame.total_atoms);
+        stats.max_atoms = stats.max_atoms.max(frame.total_atoms);
+        atoms_sum += frame.total_atoms as f64;
+        stats.has_velocities |= frame.has_velocities;
+    }
+    if stats.frames == 0 {
+        stats.min_atoms = 0;
+    } else {
+        stats.mean_atoms = atoms_sum / stats.frames as f64;
+    }
+    Ok(stats)
+}
+
 /// Reads only the first frame from a file.
 ///
 /// More efficient than `read_all_frames` for single-frame access because it
@@ -205,15 +1049,20 @@ pub fn read_first_frame(path: &Path) -> Result<types::ConFrame, Box<dyn std::err
     match iter.next() {
         Some(Ok(frame)) => Ok(frame),
         Some(Err(e)) => Err(Box::new(e)),
-        None => Err("No frames found in file".into()),
+        None => Err(Box::new(error::ParseError::NoFrames)),
     }
 }
 
 /// Parses frames in parallel using rayon, splitting on frame boundaries.
 ///
-/// Phase 1: sequential scan to find byte offsets of each frame's start.
+/// Phase 1: sequential scan of the frame headers to find each frame's byte
+/// span, as [`FrameIndex::build`] does.
 /// Phase 2: parallel parse of each frame slice using rayon.
 ///
+/// The result holds one entry per frame in file order. A frame whose header
+/// is malformed yields an `Err` at its position, and scanning resumes at the
+/// next frame that parses.
+///
 /// Requires the `parallel` feature.
 #[cfg(feature = "parallel")]
 pub fn parse_frames_parallel(
@@ -221,100 +1070,103 @@ pub fn parse_frames_parallel(
 ) -> Vec<Result<types::ConFrame, error::ParseError>> {
     use rayon::prelude::*;
 
-    // Phase 1: find frame byte boundaries by scanning for header patterns.
-    // Each frame starts with a header: 2 comment lines, then a line with 3 floats (box).
-    // We identify boundaries by walking through the file with a ConFrameIterator
-    // and recording byte positions.
-    let mut boundaries: Vec<usize> = Vec::new();
-    let mut offset = 0;
-    boundaries.push(0);
+    let parsed: Vec<Result<DetachedFrame, error::ParseError>> = frame_spans(file_contents)
+        .into_par_iter()
+        .map(|span| {
+            let mut iter = ConFrameIterator::new(&file_contents[span?]);
+            match iter.next() {
+                Some(result) => result.map(DetachedFrame::detach),
+                None => Err(error::ParseError::IncompleteFrame),
+            }
+        })
+        .collect();
+    // Symbols get their `Rc`s here, shared across frames as with
+    // `ConFrameIterator`.
+    let mut symbols = SymbolInterner::default();
+    parsed
+        .into_iter()
+        .map(|result| result.map(|frame| frame.attach(&mut symbols)))
+        .collect()
+}
+
+/// A parsed frame with its `Rc` symbols replaced by owned strings, so it can
+/// be sent back from the rayon worker that parsed it.
+#[cfg(feature = "parallel")]
+struct DetachedFrame {
+    header: types::FrameHeader,
+    /// Distinct symbols of the frame.
+    symbols: Vec<String>,
+    /// The atoms, each with the index of its symbol in `symbols`.
+    atoms: Vec<(usize, DetachedAtom)>,
+}
 
-    // Walk through the file using the forward() method to find frame boundaries
-    let mut scanner = ConFrameIterator::new(file_contents);
-    while scanner.forward().is_some() {
-        // After forward(), the internal iterator is positioned right after the frame.
-        // We need to figure out the byte offset of the next frame start.
-        // Since Peekable<Lines> doesn't expose byte offsets, we use a different approach:
-        // count lines consumed per frame and convert to byte offsets.
-    }
-
-    // Simpler approach: split into frame text chunks by parsing sequentially,
-    // recording where each frame starts and ends in the string.
-    boundaries.clear();
-    let lines: Vec<&str> = file_contents.lines().collect();
-    let mut line_idx = 0;
-    let total_lines = lines.len();
-
-    while line_idx < total_lines {
-        // Record the byte offset of this frame's start
-        let byte_offset: usize = lines[..line_idx]
-            .iter()
-            .map(|l| l.len() + 1) // +1 for newline
-            .sum();
-        boundaries.push(byte_offset);
+/// The fields of an [`types::AtomDatum`] other than its symbol.
+#[cfg(feature = "parallel")]
+struct DetachedAtom {
+    xyz: [f64; 3],
+    vel: [Option<f64>; 3],
+    is_fixed: bool,
+    atom_id: u64,
+    extra_columns: Vec<f64>,
+}
 
-        // Skip 6 header lines (prebox1, prebox2, boxl, angles, postbox1, postbox2)
-        if line_idx + 6 >= total_lines {
-            break;
+#[cfg(feature = "parallel")]
+impl DetachedFrame {
+    fn detach(frame: types::ConFrame) -> Self {
+        let mut symbols: Vec<String> = Vec::new();
+        let atoms = frame
+            .atom_data
+            .into_iter()
+            .map(|atom| {
+                let symbol = match symbols.iter().rposition(|s| s == atom.symbol_str()) {
+                    Some(k) => k,
+                    None => {
+                        symbols.push(atom.symbol_str().to_string());
+                        symbols.len() - 1
+                    }
+                };
+                let detached = DetachedAtom {
+                    xyz: [atom.x, atom.y, atom.z],
+                    vel: [atom.vx, atom.vy, atom.vz],
+                    is_fixed: atom.is_fixed,
+                    atom_id: atom.atom_id,
+                    extra_columns: atom.extra_columns,
+                };
+                (symbol, detached)
+            })
+            .collect();
+        DetachedFrame {
+            header: frame.header,
+            symbols,
+            atoms,
         }
-        line_idx += 6;
-
-        // Line 7: natm_types
-        let natm_types: usize = match lines.get(line_idx) {
-            Some(l) => match crate::parser::parse_line_of_n::<usize>(l, 1) {
-                Ok(v) => v[0],
-                Err(_) => break,
-            },
-            None => break,
-        };
-        line_idx += 1;
+    }
 
-        // Line 8: natms_per_type
-        let natms_per_type: Vec<usize> = match lines.get(line_idx) {
-            Some(l) => match crate::parser::parse_line_of_n(l, natm_types) {
-                Ok(v) => v,
-                Err(_) => break,
-            },
-            None => break,
-        };
-        line_idx += 1;
-
-        // Line 9: masses (just skip)
-        line_idx += 1;
-
-        // Skip coordinate blocks
-        let total_atoms: usize = natms_per_type.iter().sum();
-        let coord_lines = total_atoms + natm_types * 2;
-        line_idx += coord_lines;
-
-        // Check for velocity section (blank separator)
-        if line_idx < total_lines {
-            if let Some(l) = lines.get(line_idx) {
-                if l.trim().is_empty() {
-                    line_idx += 1; // blank separator
-                    line_idx += coord_lines; // velocity blocks same size
+    fn attach(self, interner: &mut SymbolInterner) -> types::ConFrame {
+        let symbols: Vec<_> = self.symbols.iter().map(|s| interner.intern(s)).collect();
+        let atom_data = self
+            .atoms
+            .into_iter()
+            .map(|(symbol, atom)| {
+                let [x, y, z] = atom.xyz;
+                let [vx, vy, vz] = atom.vel;
+                types::AtomDatum {
+                    symbol: std::rc::Rc::clone(&symbols[symbol]),
+                    x,
+                    y,
+                    z,
+                    is_fixed: atom.is_fixed,
+                    atom_id: atom.atom_id,
+                    vx,
+                    vy,
+                    vz,
+                    extra_columns: atom.extra_columns,
                 }
-            }
+            })
+            .collect();
+        types::ConFrame {
+            header: self.header,
+            atom_data,
         }
     }
-
-    // Phase 2: parallel parse each frame chunk
-    let num_frames = boundaries.len();
-    (0..num_frames)
-        .into_par_iter()
-        .map(|i| {
-            let start = boundaries[i];
-            let end = if i + 1 < num_frames {
-                boundaries[i + 1]
-            } else {
-                file_contents.len()
-            };
-            let chunk = &file_contents[start..end];
-            let mut iter = ConFrameIterator::new(chunk);
-            match iter.next() {
-                Some(result) => result,
-                None => Err(error::ParseError::IncompleteFrame),
-            }
-        })
-        .collect()
 }
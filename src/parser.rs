@@ -1,8 +1,22 @@
 use crate::error::ParseError;
-use crate::types::{AtomDatum, ConFrame, FrameHeader};
+use crate::helpers;
+use crate::types::{AtomDatum, ConFrame, ConFrameBuilder, FrameHeader};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::iter::Peekable;
 use std::rc::Rc;
 
+/// The number of standard columns on an atom line: `x y z fixed atom_id`.
+const ATOM_LINE_COLUMNS: usize = 5;
+
+/// The largest atom type count `parse_frame_header` accepts. Real systems
+/// have at most a few dozen types; anything near this is a corrupt header.
+pub const MAX_ATOM_TYPES: usize = 1024;
+
+/// Upper bound on the atoms preallocated for a frame, so a corrupt atom count
+/// cannot trigger a huge allocation before the atom lines are read.
+const MAX_PREALLOCATED_ATOMS: usize = 1 << 20;
+
 /// Parses a line of whitespace-separated f64 values using fast-float2.
 ///
 /// This is the hot-path parser for coordinate and velocity lines. It uses
@@ -16,9 +30,7 @@ use std::rc::Rc;
 pub fn parse_line_of_n_f64(line: &str, n: usize) -> Result<Vec<f64>, ParseError> {
     let mut values = Vec::with_capacity(n);
     for token in line.split_ascii_whitespace() {
-        let val: f64 = fast_float2::parse(token)
-            .map_err(|_| ParseError::InvalidNumberFormat(format!("invalid float: {token}")))?;
-        values.push(val);
+        values.push(parse_f64_token(token)?);
     }
     if values.len() == n {
         Ok(values)
@@ -30,6 +42,64 @@ pub fn parse_line_of_n_f64(line: &str, n: usize) -> Result<Vec<f64>, ParseError>
     }
 }
 
+/// Parses a single float token with fast-float2.
+fn parse_f64_token(token: &str) -> Result<f64, ParseError> {
+    fast_float2::parse(token)
+        .map_err(|_| ParseError::InvalidNumberFormat(format!("invalid float: {token}")))
+}
+
+/// Position, fixed flag and atom ID from the standard columns of an atom line.
+type AtomFields = ([f64; 3], bool, u64);
+
+/// Parses the five standard columns of an atom line, returning them together
+/// with the tokens that follow.
+fn parse_atom_fields(
+    line: &str,
+) -> Result<(AtomFields, std::str::SplitAsciiWhitespace<'_>), ParseError> {
+    let mut tokens = line.split_ascii_whitespace();
+    let mut values = [0.0; ATOM_LINE_COLUMNS];
+    for (found, value) in values.iter_mut().enumerate() {
+        let token = tokens.next().ok_or(ParseError::InvalidVectorLength {
+            expected: ATOM_LINE_COLUMNS,
+            found,
+        })?;
+        *value = parse_f64_token(token)?;
+    }
+    let [x, y, z, fixed, atom_id] = values;
+    Ok((([x, y, z], fixed != 0.0, atom_id as u64), tokens))
+}
+
+/// Parses an atom line of exactly five columns, `x y z fixed atom_id`.
+///
+/// Unlike [`parse_line_of_n_f64`], this fills a fixed-size result without any
+/// heap allocation. As in the frame parser, the fixed flag is any non-zero
+/// value and the atom ID may be written as a float.
+///
+/// # Errors
+///
+/// * `ParseError::InvalidVectorLength` if the line does not have exactly five values.
+/// * `ParseError::InvalidNumberFormat` if a value is not a number.
+///
+/// # Example
+///
+/// ```
+/// use readcon_core::parser::parse_atom_line;
+///
+/// let (pos, fixed, id) = parse_atom_line("1.0 2.0 3.0 1 7").unwrap();
+/// assert_eq!((pos, fixed, id), ([1.0, 2.0, 3.0], true, 7));
+/// assert!(parse_atom_line("1.0 2.0 3.0 1 7 0.5").is_err());
+/// ```
+pub fn parse_atom_line(line: &str) -> Result<([f64; 3], bool, u64), ParseError> {
+    let (fields, rest) = parse_atom_fields(line)?;
+    match rest.count() {
+        0 => Ok(fields),
+        extra => Err(ParseError::InvalidVectorLength {
+            expected: ATOM_LINE_COLUMNS,
+            found: ATOM_LINE_COLUMNS + extra,
+        }),
+    }
+}
+
 /// Parses a line of whitespace-separated values into a vector of a specific type.
 ///
 /// This generic helper function takes a string slice, splits it by whitespace,
@@ -76,6 +146,22 @@ where
     }
 }
 
+/// Copies a free-text header line, dropping trailing whitespace such as the
+/// `\r` left behind by CRLF line endings. Numeric lines need no such care, as
+/// they are split on ASCII whitespace, which includes `\r`.
+fn header_text_line(line: &str) -> String {
+    line.trim_end().to_string()
+}
+
+/// Parses the per-type atom count line after checking `natm_types` is sane.
+pub(crate) fn parse_type_counts(line: &str, natm_types: usize) -> Result<Vec<usize>, ParseError> {
+    // Zero types is only valid for an empty frame, whose count line is blank.
+    if natm_types > MAX_ATOM_TYPES || (natm_types == 0 && !line.trim().is_empty()) {
+        return Err(ParseError::InvalidAtomTypeCount { found: natm_types });
+    }
+    parse_line_of_n(line, natm_types)
+}
+
 /// Parses the 9-line header of a `.con` file frame from an iterator.
 ///
 /// This function consumes the next 9 lines from the given line iterator to
@@ -88,6 +174,10 @@ where
 /// # Errors
 ///
 /// * `ParseError::IncompleteHeader` if the iterator has fewer than 9 lines remaining.
+/// * `ParseError::InvalidAtomTypeCount` if `natm_types` exceeds
+///   [`MAX_ATOM_TYPES`], or is zero while the atom count line is not empty.
+/// * `ParseError::MassCountMismatch` if the mass line does not hold exactly
+///   `natm_types` values.
 /// * Propagates any errors from `parse_line_of_n` if the numeric data within
 ///   the header is malformed.
 ///
@@ -99,34 +189,28 @@ where
 pub fn parse_frame_header<'a>(
     lines: &mut impl Iterator<Item = &'a str>,
 ) -> Result<FrameHeader, ParseError> {
-    let prebox1 = lines
-        .next()
-        .ok_or(ParseError::IncompleteHeader)?
-        .to_string();
-    let prebox2 = lines
-        .next()
-        .ok_or(ParseError::IncompleteHeader)?
-        .to_string();
+    let prebox1 = header_text_line(lines.next().ok_or(ParseError::IncompleteHeader)?);
+    let prebox2 = header_text_line(lines.next().ok_or(ParseError::IncompleteHeader)?);
     let boxl_vec = parse_line_of_n_f64(lines.next().ok_or(ParseError::IncompleteHeader)?, 3)?;
     let angles_vec = parse_line_of_n_f64(lines.next().ok_or(ParseError::IncompleteHeader)?, 3)?;
-    let postbox1 = lines
-        .next()
-        .ok_or(ParseError::IncompleteHeader)?
-        .to_string();
-    let postbox2 = lines
-        .next()
-        .ok_or(ParseError::IncompleteHeader)?
-        .to_string();
+    let postbox1 = header_text_line(lines.next().ok_or(ParseError::IncompleteHeader)?);
+    let postbox2 = header_text_line(lines.next().ok_or(ParseError::IncompleteHeader)?);
     let natm_types =
         parse_line_of_n::<usize>(lines.next().ok_or(ParseError::IncompleteHeader)?, 1)?[0];
-    let natms_per_type = parse_line_of_n::<usize>(
+    let natms_per_type = parse_type_counts(
         lines.next().ok_or(ParseError::IncompleteHeader)?,
         natm_types,
     )?;
     let masses_per_type = parse_line_of_n_f64(
         lines.next().ok_or(ParseError::IncompleteHeader)?,
         natm_types,
-    )?;
+    )
+    .map_err(|e| match e {
+        ParseError::InvalidVectorLength { expected, found } => {
+            ParseError::MassCountMismatch { expected, found }
+        }
+        other => other,
+    })?;
     Ok(FrameHeader {
         prebox_header: [prebox1, prebox2],
         boxl: boxl_vec.try_into().unwrap(),
@@ -144,6 +228,11 @@ pub fn parse_frame_header<'a>(
 /// (specifically the number of atom types and atoms per type) to parse the subsequent
 /// atom coordinate blocks.
 ///
+/// Atom lines normally hold exactly `x y z fixed atom_id`. Any further numeric
+/// columns (e.g. a per-atom charge) are kept in `AtomDatum::extra_columns`; the
+/// column count is taken from the first atom line of each component block and
+/// must stay the same for the rest of that block.
+///
 /// # Arguments
 ///
 /// * `lines` - A mutable reference to an iterator that yields string slices for the frame.
@@ -183,47 +272,218 @@ pub fn parse_frame_header<'a>(
 ///
 /// assert_eq!(con_frame.header.natm_types, 2);
 /// assert_eq!(con_frame.atom_data.len(), 2);
-/// assert_eq!(&*con_frame.atom_data[0].symbol, "C");
+/// assert_eq!(con_frame.atom_data[0].symbol_str(), "C");
 /// assert_eq!(con_frame.atom_data[1].atom_id, 2);
 /// ```
 pub fn parse_single_frame<'a>(
     lines: &mut impl Iterator<Item = &'a str>,
+) -> Result<ConFrame, ParseError> {
+    // Without optional labels nothing is peeked, so the adapter never holds
+    // back a line.
+    parse_single_frame_interned(&mut lines.peekable(), &mut SymbolInterner::default(), false)
+}
+
+/// Like [`parse_single_frame`], but also parses a velocity section that
+/// follows and returns the number of lines the frame took up.
+///
+/// Exactly that many lines are consumed from `lines`, velocity section
+/// included, so the count gives the frame's span within a larger buffer
+/// without re-deriving it from the header. The layout is that of
+/// [`ParseOptions::default`], as read by `ConFrameIterator::new`.
+///
+/// # Errors
+///
+/// The same as [`parse_single_frame`] and [`parse_velocity_section`].
+///
+/// # Example
+///
+/// ```
+/// use readcon_core::parser::parse_single_frame_counted;
+///
+/// let text = "PREBOX 1\nPREBOX 2\n10 10 10\n90 90 90\nPOSTBOX 1\nPOSTBOX 2\n\
+///             1\n1\n1.008\nH\nCoordinates of Component 1\n0 0 0 0 1\nnext frame...";
+/// let mut lines = text.lines().peekable();
+/// let (frame, consumed) = parse_single_frame_counted(&mut lines).unwrap();
+/// assert_eq!((frame.atom_data.len(), consumed), (1, 12));
+/// assert_eq!(lines.next(), Some("next frame..."));
+/// ```
+pub fn parse_single_frame_counted<'a, I: Iterator<Item = &'a str>>(
+    lines: &mut Peekable<I>,
+) -> Result<(ConFrame, usize), ParseError> {
+    let mut consumed = 0;
+    let mut frame = parse_single_frame(&mut lines.by_ref().inspect(|_| consumed += 1))?;
+    if lines.peek().is_some_and(|line| line.trim().is_empty()) {
+        // With the default options every line the velocity parser peeks at
+        // is also consumed, so the adapter below never holds one back.
+        let mut counted = lines.by_ref().inspect(|_| consumed += 1).peekable();
+        parse_velocity_section_with_options(
+            &mut counted,
+            &frame.header,
+            &mut frame.atom_data,
+            &ParseOptions::default(),
+        )?;
+    }
+    Ok((frame, consumed))
+}
+
+/// Hands out one shared `Rc<String>` per distinct chemical symbol, so frames
+/// parsed with the same interner reuse the same symbol allocations.
+#[derive(Debug, Default)]
+pub(crate) struct SymbolInterner {
+    symbols: HashMap<String, Rc<String>>,
+}
+
+impl SymbolInterner {
+    pub(crate) fn intern(&mut self, symbol: &str) -> Rc<String> {
+        if let Some(shared) = self.symbols.get(symbol) {
+            return Rc::clone(shared);
+        }
+        let shared = Rc::new(symbol.to_string());
+        self.symbols.insert(symbol.to_string(), Rc::clone(&shared));
+        shared
+    }
+}
+
+/// Like [`parse_single_frame`], but takes symbols from `interner`.
+pub(crate) fn parse_single_frame_interned<'a, I: Iterator<Item = &'a str>>(
+    lines: &mut Peekable<I>,
+    interner: &mut SymbolInterner,
+    optional_labels: bool,
 ) -> Result<ConFrame, ParseError> {
     let header = parse_frame_header(lines)?;
-    let total_atoms: usize = header.natms_per_type.iter().sum();
-    let mut atom_data = Vec::with_capacity(total_atoms);
+    let mut atom_data = Vec::with_capacity(header.total_atoms().min(MAX_PREALLOCATED_ATOMS));
 
     for num_atoms in &header.natms_per_type {
-        // Create a reference-counted string for the symbol once per component.
-        let symbol = Rc::new(
-            lines
-                .next()
-                .ok_or(ParseError::IncompleteFrame)?
-                .trim()
-                .to_string(),
-        );
-        // Consume and discard the "Coordinates of Component X" line.
-        lines.next().ok_or(ParseError::IncompleteFrame)?;
+        // Look up the reference-counted symbol once per component.
+        let symbol = interner.intern(lines.next().ok_or(ParseError::IncompleteFrame)?.trim());
+        // Consume and discard the "Coordinates of Component X" line, unless
+        // the options allow it to be missing and it is.
+        let has_label = !optional_labels
+            || lines
+                .peek()
+                .is_some_and(|line| is_coordinate_label(line, *num_atoms));
+        if has_label {
+            lines.next().ok_or(ParseError::IncompleteFrame)?;
+        }
+        // The column count is taken from the first atom line of the block and
+        // every following line in the block must match it.
+        let mut n_columns = None;
         for _ in 0..*num_atoms {
             let coord_line = lines.next().ok_or(ParseError::IncompleteFrame)?;
-            let vals = parse_line_of_n_f64(coord_line, 5)?;
+            let (([x, y, z], is_fixed, atom_id), rest) =
+                parse_atom_fields(coord_line).map_err(|e| match (e, n_columns) {
+                    (ParseError::InvalidVectorLength { found, .. }, Some(expected)) => {
+                        ParseError::InvalidVectorLength { expected, found }
+                    }
+                    (e, _) => e,
+                })?;
+            // Collecting an empty iterator does not allocate, so lines with
+            // only the standard columns stay allocation-free.
+            let extra_columns = rest.map(parse_f64_token).collect::<Result<Vec<_>, _>>()?;
+            let found = ATOM_LINE_COLUMNS + extra_columns.len();
+            let expected = *n_columns.get_or_insert(found);
+            if found != expected {
+                return Err(ParseError::InvalidVectorLength { expected, found });
+            }
             atom_data.push(AtomDatum {
                 // This is now a cheap reference-count increment, not a full string clone.
                 symbol: Rc::clone(&symbol),
-                x: vals[0],
-                y: vals[1],
-                z: vals[2],
-                is_fixed: vals[3] != 0.0,
-                atom_id: vals[4] as u64,
+                x,
+                y,
+                z,
+                is_fixed,
+                atom_id,
                 vx: None,
                 vy: None,
                 vz: None,
+                extra_columns,
             });
         }
     }
     Ok(ConFrame { header, atom_data })
 }
 
+/// Callback invoked with the frame index and error whenever a frame fails to
+/// parse. Clones of a `ParseOptions` share the same callback.
+pub type ErrorCallback = Rc<RefCell<dyn FnMut(usize, &ParseError)>>;
+
+/// Options controlling how lenient or strict the parser is about layout
+/// variations between `.con`/`.convel` writers.
+#[derive(Clone)]
+pub struct ParseOptions {
+    /// Whether each velocity block starts with a symbol line before the
+    /// "Velocities of Component N" line. Defaults to `true`, matching the
+    /// files written by eOn and by this crate.
+    pub velocity_symbol_line: bool,
+    /// Whether blank lines between frames are skipped. When set, a blank line
+    /// after the coordinate blocks only starts a velocity section if a
+    /// "Velocities of Component" line (or, with `optional_velocity_labels`,
+    /// a velocity line) follows it. Defaults to `false`.
+    pub skip_blank_lines: bool,
+    /// Whether the iterator keeps going after a malformed frame. When set, it
+    /// skips ahead to the next line where a complete frame parses; otherwise
+    /// (the default) it yields the first error and then returns `None`, where
+    /// earlier releases went on trying to parse from the failed line.
+    pub recover: bool,
+    /// Called with the frame index and error for every frame that fails to
+    /// parse, before the iterator stops or recovers. Defaults to `None`.
+    pub on_error: Option<ErrorCallback>,
+    /// Whether each velocity line's atom ID is checked against the atom at
+    /// the same position in the coordinate blocks. Defaults to `false`, which
+    /// matches velocities to atoms purely by position; enable it for untrusted
+    /// input where a reordered velocity section would otherwise be accepted.
+    pub check_velocity_ids: bool,
+    /// Whether the "Coordinates of Component N" line after each symbol line
+    /// may be missing, as in files from some minimal writers. When set, a line
+    /// that parses as an atom line is taken as the first atom of the block
+    /// rather than as its label. Defaults to `false`, so strict files with an
+    /// unusual label are never misread.
+    pub optional_coordinate_labels: bool,
+    /// Lines starting with this prefix (after leading whitespace) are skipped
+    /// where a frame may start, such as `#` comments some toolchains put
+    /// between frames. Comments inside a frame are not recognised. Defaults
+    /// to `None`.
+    pub comment_prefix: Option<String>,
+    /// Whether the "Velocities of Component N" line of each velocity block may
+    /// be missing, as in files from some writers. When set, a block whose
+    /// first velocity line follows the symbol line directly is accepted.
+    /// Defaults to `false`, like `optional_coordinate_labels`.
+    pub optional_velocity_labels: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            velocity_symbol_line: true,
+            skip_blank_lines: false,
+            recover: false,
+            on_error: None,
+            check_velocity_ids: false,
+            optional_coordinate_labels: false,
+            comment_prefix: None,
+            optional_velocity_labels: false,
+        }
+    }
+}
+
+impl std::fmt::Debug for ParseOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParseOptions")
+            .field("velocity_symbol_line", &self.velocity_symbol_line)
+            .field("skip_blank_lines", &self.skip_blank_lines)
+            .field("recover", &self.recover)
+            .field("on_error", &self.on_error.as_ref().map(|_| "<callback>"))
+            .field("check_velocity_ids", &self.check_velocity_ids)
+            .field(
+                "optional_coordinate_labels",
+                &self.optional_coordinate_labels,
+            )
+            .field("comment_prefix", &self.comment_prefix)
+            .field("optional_velocity_labels", &self.optional_velocity_labels)
+            .finish()
+    }
+}
+
 /// Attempts to parse an optional velocity section following coordinate blocks.
 ///
 /// In `.convel` files, after all coordinate blocks there is a blank separator line
@@ -241,6 +501,33 @@ pub fn parse_velocity_section<'a, I>(
     header: &FrameHeader,
     atom_data: &mut [AtomDatum],
 ) -> Result<bool, ParseError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    parse_velocity_section_with_options(lines, header, atom_data, &ParseOptions::default())
+}
+
+/// Like [`parse_velocity_section`], but honours the layout settings in `options`.
+///
+/// With `options.velocity_symbol_line == false`, each velocity block is expected
+/// to start directly with its "Velocities of Component N" line. With
+/// `options.optional_velocity_labels`, a block may leave that line out if its
+/// first velocity line takes its place.
+///
+/// # Errors
+///
+/// * `ParseError::VelocityBlockMismatch` if a block does not start the way
+///   `options` says it should, which usually means the toggle is set wrongly
+///   for this file.
+/// * `ParseError::IncompleteVelocitySection` if the input ends mid-section.
+/// * `ParseError::VelocityAtomMismatch` if `options.check_velocity_ids` is set
+///   and a velocity line's atom ID differs from the corresponding atom's.
+pub fn parse_velocity_section_with_options<'a, I>(
+    lines: &mut Peekable<I>,
+    header: &FrameHeader,
+    atom_data: &mut [AtomDatum],
+    options: &ParseOptions,
+) -> Result<bool, ParseError>
 where
     I: Iterator<Item = &'a str>,
 {
@@ -255,32 +542,45 @@ where
 
     let mut atom_idx = 0;
     for (type_idx, &num_atoms) in header.natms_per_type.iter().enumerate() {
-        // Symbol line
-        let _symbol = lines
-            .next()
-            .ok_or(ParseError::IncompleteVelocitySection)?
-            .trim();
+        if options.velocity_symbol_line {
+            let symbol_line = lines.next().ok_or(ParseError::IncompleteVelocitySection)?;
+            if is_velocity_component_line(symbol_line) {
+                return Err(ParseError::VelocityBlockMismatch {
+                    component: type_idx + 1,
+                    expected_symbol_line: true,
+                    found: symbol_line.trim().to_string(),
+                });
+            }
+        }
 
-        // "Velocities of Component N" line
+        // "Velocities of Component N" line, unless the writer left it out
         let comp_line = lines
-            .next()
+            .peek()
+            .copied()
             .ok_or(ParseError::IncompleteVelocitySection)?;
-        // Validate it looks like a velocity header (optional strictness)
-        if !comp_line.contains("Velocities of Component") {
-            return Err(ParseError::IncompleteVelocitySection);
+        if is_velocity_component_line(comp_line) {
+            lines.next();
+        } else if !options.optional_velocity_labels || !is_velocity_data_line(comp_line) {
+            return Err(ParseError::VelocityBlockMismatch {
+                component: type_idx + 1,
+                expected_symbol_line: options.velocity_symbol_line,
+                found: comp_line.trim().to_string(),
+            });
         }
-        let _ = type_idx; // suppress unused warning
 
         for _ in 0..num_atoms {
-            let vel_line = lines
-                .next()
-                .ok_or(ParseError::IncompleteVelocitySection)?;
+            let vel_line = lines.next().ok_or(ParseError::IncompleteVelocitySection)?;
             let vals = parse_line_of_n_f64(vel_line, 5)?;
             if atom_idx < atom_data.len() {
+                // vals[3] is the fixed flag, vals[4] the atom_id
+                if options.check_velocity_ids && vals[4] as u64 != atom_data[atom_idx].atom_id {
+                    return Err(ParseError::VelocityAtomMismatch {
+                        atom_id: vals[4] as u64,
+                    });
+                }
                 atom_data[atom_idx].vx = Some(vals[0]);
                 atom_data[atom_idx].vy = Some(vals[1]);
                 atom_data[atom_idx].vz = Some(vals[2]);
-                // vals[3] is fixed flag, vals[4] is atom_id (redundant with coords)
             }
             atom_idx += 1;
         }
@@ -289,6 +589,385 @@ where
     Ok(true)
 }
 
+/// With `ParseOptions::optional_coordinate_labels`, decides whether `line`,
+/// which follows the symbol line of a type with `num_atoms` atoms, is its
+/// "Coordinates of Component N" label. For a type with atoms, any line that
+/// does not parse as an atom line is the label; an empty type has no atom line
+/// to compare with, so only a line starting with `Coordinates` counts.
+pub(crate) fn is_coordinate_label(line: &str, num_atoms: usize) -> bool {
+    if num_atoms == 0 {
+        line.trim_start().starts_with("Coordinates")
+    } else {
+        parse_atom_fields(line).is_err()
+    }
+}
+
+pub(crate) fn is_velocity_component_line(line: &str) -> bool {
+    line.contains("Velocities of Component")
+}
+
+/// Whether `line` is a velocity line: three components, the fixed flag and
+/// the atom ID.
+pub(crate) fn is_velocity_data_line(line: &str) -> bool {
+    parse_line_of_n_f64(line, 5).is_ok()
+}
+
+/// A lattice from another format, mapped onto the fixed orientation of a
+/// `.con` cell (see [`helpers::cell_matrix`]).
+struct LatticeMap {
+    boxl: [f64; 3],
+    angles: [f64; 3],
+    /// Takes Cartesian rows in the original lattice's frame to fractional.
+    inverse: [[f64; 3]; 3],
+    /// Takes fractional rows to Cartesian in the `.con` frame.
+    con_cell: [[f64; 3]; 3],
+}
+
+impl LatticeMap {
+    /// Maps `lattice`, whose rows are the lattice vectors. `format` names the
+    /// input format in the error for a degenerate lattice.
+    fn new(lattice: &[[f64; 3]; 3], format: &str) -> Result<Self, ParseError> {
+        let inverse = helpers::invert_3x3(lattice).ok_or_else(|| {
+            let msg = format!("{format} lattice vectors are linearly dependent");
+            ParseError::UnsupportedFormat(msg)
+        })?;
+        let (boxl, angles) = helpers::cell_parameters(lattice);
+        Ok(LatticeMap {
+            boxl,
+            angles,
+            inverse,
+            con_cell: helpers::cell_matrix(boxl, angles),
+        })
+    }
+
+    fn fractional_to_con(&self, fractional: [f64; 3]) -> [f64; 3] {
+        helpers::row_times_matrix(fractional, &self.con_cell)
+    }
+
+    /// Rotates a Cartesian vector (a position or a velocity) into the `.con`
+    /// frame.
+    fn cartesian_to_con(&self, v: [f64; 3]) -> [f64; 3] {
+        self.fractional_to_con(helpers::row_times_matrix(v, &self.inverse))
+    }
+}
+
+/// Parses a VASP POSCAR file into a frame, the counterpart of
+/// [`crate::writer::write_poscar`].
+///
+/// Only the VASP 5 layout, with an element symbol line above the counts, is
+/// read. The lattice is scaled (a negative scaling factor is the target cell
+/// volume) and converted to `boxl` and `angles`. Because a `.con` cell has a
+/// fixed orientation, coordinates go through their fractional form, so
+/// Cartesian input is rotated along with the lattice. With `Selective
+/// dynamics`, an atom is fixed if any of its three flags is `F`.
+///
+/// Atom IDs are assigned sequentially from 0, masses come from
+/// [`helpers::standard_atomic_mass`], and the comment line becomes the first
+/// pre-box header line. Velocity blocks after the coordinates are ignored.
+///
+/// # Errors
+///
+/// * `ParseError::UnsupportedFormat` for a VASP 4 file (counts without a
+///   symbol line) or linearly dependent lattice vectors.
+/// * `ParseError::IncompleteHeader` or `ParseError::IncompleteFrame` if the
+///   input ends early, and the usual number and line length errors.
+pub fn parse_poscar(contents: &str) -> Result<ConFrame, ParseError> {
+    fn next_header<'a>(lines: &mut std::str::Lines<'a>) -> Result<&'a str, ParseError> {
+        lines.next().ok_or(ParseError::IncompleteHeader)
+    }
+
+    let mut lines = contents.lines();
+    let comment = header_text_line(next_header(&mut lines)?);
+    let scale = parse_line_of_n_f64(next_header(&mut lines)?, 1)?[0];
+    let mut lattice = [[0.0; 3]; 3];
+    for row in &mut lattice {
+        let v = parse_line_of_n_f64(next_header(&mut lines)?, 3)?;
+        *row = [v[0], v[1], v[2]];
+    }
+    // A negative scaling factor is the volume the lattice should be scaled to.
+    let scale = if scale < 0.0 {
+        let [a, b, c] = lattice;
+        let volume = a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+            + a[2] * (b[0] * c[1] - b[1] * c[0]);
+        (-scale / volume.abs()).cbrt()
+    } else {
+        scale
+    };
+    let map = LatticeMap::new(&lattice.map(|row| row.map(|x| x * scale)), "POSCAR")?;
+
+    let symbol_line = next_header(&mut lines)?;
+    if symbol_line
+        .split_ascii_whitespace()
+        .next()
+        .is_some_and(|t| t.parse::<usize>().is_ok())
+    {
+        return Err(ParseError::UnsupportedFormat(
+            "VASP 4 POSCAR without an element symbol line".to_string(),
+        ));
+    }
+    // VASP 6 may append a POTCAR hash to each symbol, as in `Cu/a1b2c3`.
+    let symbols: Vec<&str> = symbol_line
+        .split_ascii_whitespace()
+        .map(|t| t.split('/').next().unwrap_or(t))
+        .collect();
+    let counts: Vec<usize> = parse_line_of_n(next_header(&mut lines)?, symbols.len())?;
+
+    let mut mode_line = next_header(&mut lines)?;
+    let selective = mode_line.trim_start().starts_with(['S', 's']);
+    if selective {
+        mode_line = next_header(&mut lines)?;
+    }
+    let cartesian = mode_line.trim_start().starts_with(['C', 'c', 'K', 'k']);
+
+    let mut builder =
+        ConFrameBuilder::new(map.boxl, map.angles).prebox_header([comment, String::new()]);
+    let mut atom_id = 0;
+    for (symbol, &count) in symbols.iter().zip(&counts) {
+        for _ in 0..count {
+            let line = lines.next().ok_or(ParseError::IncompleteFrame)?;
+            let mut tokens = line.split_ascii_whitespace();
+            let mut pos = [0.0; 3];
+            for (found, value) in pos.iter_mut().enumerate() {
+                let token = tokens
+                    .next()
+                    .ok_or(ParseError::InvalidVectorLength { expected: 3, found })?;
+                *value = parse_f64_token(token)?;
+            }
+            let [x, y, z] = if cartesian {
+                map.cartesian_to_con(pos.map(|x| x * scale))
+            } else {
+                map.fractional_to_con(pos)
+            };
+            let is_fixed = selective && tokens.take(3).any(|flag| flag.starts_with(['F', 'f']));
+            builder.add_atom_auto_mass(symbol, x, y, z, is_fixed, atom_id);
+            atom_id += 1;
+        }
+    }
+    Ok(builder.build())
+}
+
+/// Parses a multi-frame XYZ or extended XYZ file.
+///
+/// Each frame is an atom count line, a comment line and one `symbol x y z`
+/// line per atom. The cell comes from an extended XYZ `Lattice="..."` entry on
+/// the comment line, which this function requires; use
+/// [`parse_xyz_with_cell`] for plain XYZ files. A `Properties=` entry locates
+/// the `species` and `pos` columns, and a `velo` column, if listed, fills the
+/// atom velocities. Positions and velocities are rotated along with the
+/// lattice into the `.con` cell orientation.
+///
+/// As in [`parse_poscar`], atom IDs count from 0 in file order, masses come
+/// from [`helpers::standard_atomic_mass`], and the comment line becomes the
+/// first pre-box header line. Atoms are grouped by symbol in order of first
+/// appearance, so interleaved symbols are reordered; their IDs keep the file
+/// order.
+///
+/// # Errors
+///
+/// * `ParseError::UnsupportedFormat` for a frame without a `Lattice` entry, a
+///   degenerate lattice, or a `Properties` entry this reader cannot use.
+/// * `ParseError::IncompleteHeader` or `ParseError::IncompleteFrame` if a
+///   frame ends early, and the usual number and line length errors.
+pub fn parse_xyz(contents: &str) -> Result<Vec<ConFrame>, ParseError> {
+    parse_xyz_frames(contents, None)
+}
+
+/// Like [`parse_xyz`], but frames without a `Lattice` entry get the cell with
+/// lengths `cell` and `angles` (degrees), and their positions are kept as
+/// written.
+pub fn parse_xyz_with_cell(
+    contents: &str,
+    cell: [f64; 3],
+    angles: [f64; 3],
+) -> Result<Vec<ConFrame>, ParseError> {
+    parse_xyz_frames(contents, Some((cell, angles)))
+}
+
+fn parse_xyz_frames(
+    contents: &str,
+    default_cell: Option<([f64; 3], [f64; 3])>,
+) -> Result<Vec<ConFrame>, ParseError> {
+    let mut frames = Vec::new();
+    let mut lines = contents.lines().peekable();
+    loop {
+        while lines.peek().is_some_and(|line| line.trim().is_empty()) {
+            lines.next();
+        }
+        let Some(count_line) = lines.next() else {
+            return Ok(frames);
+        };
+        let natoms: usize = parse_line_of_n(count_line, 1)?[0];
+        let comment = lines.next().ok_or(ParseError::IncompleteHeader)?;
+        frames.push(parse_xyz_frame(natoms, comment, &mut lines, default_cell)?);
+    }
+}
+
+fn parse_xyz_frame<'a>(
+    natoms: usize,
+    comment: &str,
+    lines: &mut impl Iterator<Item = &'a str>,
+    default_cell: Option<([f64; 3], [f64; 3])>,
+) -> Result<ConFrame, ParseError> {
+    let map = match extxyz_value(comment, "Lattice") {
+        Some(value) => {
+            let v = parse_line_of_n_f64(value, 9)?;
+            let lattice = [[v[0], v[1], v[2]], [v[3], v[4], v[5]], [v[6], v[7], v[8]]];
+            Some(LatticeMap::new(&lattice, "XYZ")?)
+        }
+        None => None,
+    };
+    let (boxl, angles) = match (&map, default_cell) {
+        (Some(map), _) => (map.boxl, map.angles),
+        (None, Some(cell)) => cell,
+        (None, None) => {
+            return Err(ParseError::UnsupportedFormat(
+                "XYZ frame without a Lattice entry; pass a cell with parse_xyz_with_cell"
+                    .to_string(),
+            ));
+        }
+    };
+    let rotate = |v: [f64; 3]| map.as_ref().map_or(v, |map| map.cartesian_to_con(v));
+    let columns = XyzColumns::parse(extxyz_value(comment, "Properties"))?;
+
+    let header = [header_text_line(comment), String::new()];
+    let mut builder = ConFrameBuilder::new(boxl, angles).prebox_header(header);
+    for atom_id in 0..natoms as u64 {
+        let line = lines.next().ok_or(ParseError::IncompleteFrame)?;
+        let tokens: Vec<&str> = line.split_ascii_whitespace().collect();
+        if tokens.len() < columns.width {
+            return Err(ParseError::InvalidVectorLength {
+                expected: columns.width,
+                found: tokens.len(),
+            });
+        }
+        let vector = |start: usize| -> Result<[f64; 3], ParseError> {
+            Ok([
+                parse_f64_token(tokens[start])?,
+                parse_f64_token(tokens[start + 1])?,
+                parse_f64_token(tokens[start + 2])?,
+            ])
+        };
+        let symbol = tokens[columns.species];
+        let mass = helpers::standard_atomic_mass(symbol).unwrap_or(0.0);
+        let [x, y, z] = rotate(vector(columns.pos)?);
+        match columns.velo {
+            Some(start) => {
+                let [vx, vy, vz] = rotate(vector(start)?);
+                builder.add_atom_with_velocity(symbol, x, y, z, false, atom_id, mass, vx, vy, vz);
+            }
+            None => builder.add_atom(symbol, x, y, z, false, atom_id, mass),
+        }
+    }
+    Ok(builder.build())
+}
+
+/// Returns the value of `key` in an extended XYZ comment line, without its
+/// surrounding quotes. Keys are matched case-insensitively, and only in
+/// `key=value` tokens, so free text such as `Lattice relaxation step 3` in a
+/// plain XYZ comment never matches.
+fn extxyz_value<'a>(comment: &'a str, key: &str) -> Option<&'a str> {
+    let mut rest = comment.trim_start();
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '=' || c.is_ascii_whitespace())
+            .unwrap_or(rest.len());
+        let (name, tail) = rest.split_at(name_end);
+        let Some(tail) = tail.strip_prefix('=') else {
+            rest = tail.trim_start();
+            continue;
+        };
+        let (value, tail) = match tail.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                (&quoted[..end], quoted.get(end + 1..).unwrap_or(""))
+            }
+            None => {
+                let end = tail.find(|c: char| c.is_ascii_whitespace());
+                tail.split_at(end.unwrap_or(tail.len()))
+            }
+        };
+        if name.eq_ignore_ascii_case(key) {
+            return Some(value);
+        }
+        rest = tail.trim_start();
+    }
+    None
+}
+
+/// Column offsets of the fields read from an XYZ atom line.
+struct XyzColumns {
+    species: usize,
+    pos: usize,
+    velo: Option<usize>,
+    /// The number of columns an atom line must have.
+    width: usize,
+}
+
+impl XyzColumns {
+    /// Reads an extended XYZ `Properties` value such as
+    /// `species:S:1:pos:R:3:velo:R:3`. Without one, the columns are those of
+    /// plain XYZ, `species:S:1:pos:R:3`.
+    fn parse(properties: Option<&str>) -> Result<Self, ParseError> {
+        let Some(properties) = properties else {
+            return Ok(XyzColumns {
+                species: 0,
+                pos: 1,
+                velo: None,
+                width: 4,
+            });
+        };
+        let unsupported =
+            || ParseError::UnsupportedFormat(format!("XYZ Properties entry {properties:?}"));
+        let fields: Vec<&str> = properties.split(':').collect();
+        if !fields.len().is_multiple_of(3) {
+            return Err(unsupported());
+        }
+        let (mut species, mut pos, mut velo, mut width) = (None, None, None, 0);
+        for field in fields.chunks(3) {
+            let columns: usize = field[2].parse()?;
+            let name = field[0];
+            if name.eq_ignore_ascii_case("species") {
+                species = Some(width);
+            } else if name.eq_ignore_ascii_case("pos") || name.eq_ignore_ascii_case("velo") {
+                if columns != 3 {
+                    return Err(unsupported());
+                }
+                if name.eq_ignore_ascii_case("pos") {
+                    pos = Some(width);
+                } else {
+                    velo = Some(width);
+                }
+            }
+            width += columns;
+        }
+        match (species, pos) {
+            (Some(species), Some(pos)) => Ok(XyzColumns {
+                species,
+                pos,
+                velo,
+                width,
+            }),
+            _ => Err(unsupported()),
+        }
+    }
+}
+
+/// Reads frames from the JSON layout written by [`crate::writer::write_json`].
+///
+/// Each frame is rebuilt with [`ConFrameBuilder`], so atoms are grouped by
+/// symbol in order of first appearance and each type takes the mass of its
+/// first atom.
+///
+/// # Errors
+///
+/// Returns the `serde_json` error for malformed JSON or input that does not
+/// follow the layout, such as a position without three components.
+#[cfg(feature = "serde")]
+pub fn parse_json(contents: &str) -> Result<Vec<ConFrame>, serde_json::Error> {
+    let frames: Vec<crate::types::json::Frame> = serde_json::from_str(contents)?;
+    Ok(frames.into_iter().map(ConFrame::from).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -416,6 +1095,60 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_frame_header_checks_type_count() {
+        let header = |natm_types: &'static str, counts: &'static str, masses: &'static str| {
+            let lines = [
+                "PREBOX1",
+                "PREBOX2",
+                "10.0 20.0 30.0",
+                "90.0 90.0 90.0",
+                "POSTBOX1",
+                "POSTBOX2",
+                natm_types,
+                counts,
+                masses,
+            ];
+            parse_frame_header(&mut lines.iter().copied())
+        };
+        assert!(matches!(
+            header("0", "4", "12.011"),
+            Err(ParseError::InvalidAtomTypeCount { found: 0 })
+        ));
+        assert!(matches!(
+            header("1000000000", "1", "1.0"),
+            Err(ParseError::InvalidAtomTypeCount {
+                found: 1_000_000_000
+            })
+        ));
+        // An empty frame legitimately has no types.
+        assert_eq!(header("0", "", "").unwrap().total_atoms(), 0);
+    }
+
+    #[test]
+    fn test_parse_frame_header_too_few_masses() {
+        let lines = vec![
+            "PREBOX1",
+            "PREBOX2",
+            "10.0 20.0 30.0",
+            "90.0 90.0 90.0",
+            "POSTBOX1",
+            "POSTBOX2",
+            "2",
+            "1 1",
+            "12.011", // 1 mass, but natm_types is 2
+        ];
+        let mut line_it = lines.iter().copied();
+        let result = parse_frame_header(&mut line_it);
+        assert!(matches!(
+            result.unwrap_err(),
+            ParseError::MassCountMismatch {
+                expected: 2,
+                found: 1
+            }
+        ));
+    }
+
     #[test]
     fn test_parse_single_frame_success() {
         let lines = vec![
@@ -446,9 +1179,9 @@ mod tests {
         assert_eq!(frame.header.natms_per_type, vec![3, 3]);
         assert_eq!(frame.header.masses_per_type, vec![12.011, 1.008]);
         assert_eq!(frame.atom_data.len(), 6);
-        assert_eq!(&*frame.atom_data[0].symbol, "1");
+        assert_eq!(frame.atom_data[0].symbol_str(), "1");
         assert_eq!(frame.atom_data[0].atom_id, 1);
-        assert_eq!(&*frame.atom_data[5].symbol, "2");
+        assert_eq!(frame.atom_data[5].symbol_str(), "2");
         assert_eq!(frame.atom_data[5].atom_id, 6);
     }
 
@@ -512,6 +1245,64 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_single_frame_extra_columns() {
+        let lines = vec![
+            "PREBOX1",
+            "PREBOX2",
+            "10.0 20.0 30.0",
+            "90.0 90.0 90.0",
+            "POSTBOX1",
+            "POSTBOX2",
+            "2",
+            "2 1",
+            "63.546 1.008",
+            "Cu",
+            "Coordinates of Component 1",
+            "0.0 0.0 0.0 1 0 -0.25",
+            "1.0 0.0 0.0 1 1 -0.5",
+            "H",
+            "Coordinates of Component 2",
+            "2.0 3.0 4.0 0 2",
+        ];
+        let mut line_it = lines.iter().copied();
+        let frame = parse_single_frame(&mut line_it).unwrap();
+
+        assert_eq!(frame.atom_data[0].extra_columns, vec![-0.25]);
+        assert_eq!(frame.atom_data[1].extra_columns, vec![-0.5]);
+        assert_eq!(frame.atom_data[1].atom_id, 1);
+        // Standard five-column blocks carry no extras.
+        assert!(frame.atom_data[2].extra_columns.is_empty());
+    }
+
+    #[test]
+    fn test_parse_single_frame_inconsistent_extra_columns() {
+        let lines = vec![
+            "PREBOX1",
+            "PREBOX2",
+            "10.0 20.0 30.0",
+            "90.0 90.0 90.0",
+            "POSTBOX1",
+            "POSTBOX2",
+            "1",
+            "2",
+            "63.546",
+            "Cu",
+            "Coordinates of Component 1",
+            "0.0 0.0 0.0 1 0 -0.25",
+            "1.0 0.0 0.0 1 1",
+        ];
+        let mut line_it = lines.iter().copied();
+        let result = parse_single_frame(&mut line_it);
+        assert!(matches!(
+            result.unwrap_err(),
+            ParseError::InvalidVectorLength {
+                expected: 6,
+                found: 5
+            }
+        ));
+    }
+
     #[test]
     fn test_parse_velocity_section_present() {
         let lines = vec![
@@ -545,9 +1336,8 @@ mod tests {
         assert!(!frame.has_velocities());
 
         // Now parse the velocity section
-        let has_vel =
-            parse_velocity_section(&mut line_it, &frame.header, &mut frame.atom_data)
-                .expect("velocity parsing should succeed");
+        let has_vel = parse_velocity_section(&mut line_it, &frame.header, &mut frame.atom_data)
+            .expect("velocity parsing should succeed");
         assert!(has_vel);
         assert_eq!(frame.atom_data[0].vx, Some(0.1));
         assert_eq!(frame.atom_data[0].vy, Some(0.2));
@@ -557,6 +1347,152 @@ mod tests {
         assert_eq!(frame.atom_data[1].vz, Some(0.6));
     }
 
+    const VELOCITY_FRAME_HEAD: [&str; 15] = [
+        "PREBOX1",
+        "PREBOX2",
+        "10.0 20.0 30.0",
+        "90.0 90.0 90.0",
+        "POSTBOX1",
+        "POSTBOX2",
+        "2",
+        "1 1",
+        "63.546 1.008",
+        "Cu",
+        "Coordinates of Component 1",
+        "0.0 0.0 0.0 1.0 0",
+        "H",
+        "Coordinates of Component 2",
+        "1.0 2.0 3.0 0.0 1",
+    ];
+
+    const VELOCITIES_WITHOUT_SYMBOLS: [&str; 5] = [
+        "",
+        "Velocities of Component 1",
+        "0.1 0.2 0.3 1.0 0",
+        "Velocities of Component 2",
+        "0.4 0.5 0.6 0.0 1",
+    ];
+
+    #[test]
+    fn test_parse_velocity_section_without_symbol_lines() {
+        let lines: Vec<&str> = VELOCITY_FRAME_HEAD
+            .iter()
+            .chain(VELOCITIES_WITHOUT_SYMBOLS.iter())
+            .copied()
+            .collect();
+        let mut line_it = lines.into_iter().peekable();
+        let mut frame = parse_single_frame(&mut line_it).unwrap();
+        let options = ParseOptions {
+            velocity_symbol_line: false,
+            ..ParseOptions::default()
+        };
+        let has_vel = parse_velocity_section_with_options(
+            &mut line_it,
+            &frame.header,
+            &mut frame.atom_data,
+            &options,
+        )
+        .expect("velocity parsing without symbol lines should succeed");
+        assert!(has_vel);
+        assert_eq!(frame.atom_data[0].vx, Some(0.1));
+        assert_eq!(frame.atom_data[1].vz, Some(0.6));
+        assert!(line_it.next().is_none());
+    }
+
+    #[test]
+    fn test_parse_velocity_section_checks_atom_ids() {
+        // Velocity lines list atom 1 before atom 0.
+        let swapped = [
+            "",
+            "Velocities of Component 1",
+            "0.4 0.5 0.6 0.0 1",
+            "Velocities of Component 2",
+            "0.1 0.2 0.3 1.0 0",
+        ];
+        let options = ParseOptions {
+            velocity_symbol_line: false,
+            check_velocity_ids: true,
+            ..ParseOptions::default()
+        };
+
+        let parse = |velocities: &[&'static str], options: &ParseOptions| {
+            let lines: Vec<&str> = VELOCITY_FRAME_HEAD
+                .iter()
+                .chain(velocities.iter())
+                .copied()
+                .collect();
+            let mut line_it = lines.into_iter().peekable();
+            let mut frame = parse_single_frame(&mut line_it).unwrap();
+            parse_velocity_section_with_options(
+                &mut line_it,
+                &frame.header,
+                &mut frame.atom_data,
+                options,
+            )
+        };
+
+        assert!(parse(&VELOCITIES_WITHOUT_SYMBOLS, &options).unwrap());
+        assert!(matches!(
+            parse(&swapped, &options),
+            Err(ParseError::VelocityAtomMismatch { atom_id: 1 })
+        ));
+
+        // Positional matching (the default) accepts the same lines.
+        let positional = ParseOptions {
+            velocity_symbol_line: false,
+            ..ParseOptions::default()
+        };
+        assert!(parse(&swapped, &positional).unwrap());
+    }
+
+    #[test]
+    fn test_parse_velocity_section_symbol_line_misconfigured() {
+        // Default options expect a symbol line, but the file has none.
+        let lines: Vec<&str> = VELOCITY_FRAME_HEAD
+            .iter()
+            .chain(VELOCITIES_WITHOUT_SYMBOLS.iter())
+            .copied()
+            .collect();
+        let mut line_it = lines.into_iter().peekable();
+        let mut frame = parse_single_frame(&mut line_it).unwrap();
+        let result = parse_velocity_section(&mut line_it, &frame.header, &mut frame.atom_data);
+        assert!(matches!(
+            result,
+            Err(ParseError::VelocityBlockMismatch {
+                component: 1,
+                expected_symbol_line: true,
+                ..
+            })
+        ));
+
+        // The file has symbol lines, but the options say it does not.
+        let lines: Vec<&str> = VELOCITY_FRAME_HEAD
+            .iter()
+            .copied()
+            .chain(["", "Cu", "Velocities of Component 1", "0.1 0.2 0.3 1.0 0"])
+            .collect();
+        let mut line_it = lines.into_iter().peekable();
+        let mut frame = parse_single_frame(&mut line_it).unwrap();
+        let options = ParseOptions {
+            velocity_symbol_line: false,
+            ..ParseOptions::default()
+        };
+        let result = parse_velocity_section_with_options(
+            &mut line_it,
+            &frame.header,
+            &mut frame.atom_data,
+            &options,
+        );
+        assert!(matches!(
+            result,
+            Err(ParseError::VelocityBlockMismatch {
+                component: 1,
+                expected_symbol_line: false,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_parse_velocity_section_absent() {
         let lines = vec![
@@ -575,10 +1511,112 @@ mod tests {
         ];
         let mut line_it = lines.iter().copied().peekable();
         let mut frame = parse_single_frame(&mut line_it).expect("parse should succeed");
-        let has_vel =
-            parse_velocity_section(&mut line_it, &frame.header, &mut frame.atom_data)
-                .expect("should succeed with no velocities");
+        let has_vel = parse_velocity_section(&mut line_it, &frame.header, &mut frame.atom_data)
+            .expect("should succeed with no velocities");
         assert!(!has_vel);
         assert_eq!(frame.atom_data[0].vx, None);
     }
+
+    #[test]
+    fn test_parse_poscar() {
+        // A rotated 2x3x4 lattice, scaled by 2, with Cartesian coordinates.
+        let poscar = "rotated\n2.0\n0 1 0\n-1.5 0 0\n0 0 2\nCu H\n1 1\n\
+                      Selective dynamics\nCartesian\n-0.75 0.5 1.0 T F T\n0 0 0 T T T\n";
+        let frame = parse_poscar(poscar).unwrap();
+        assert_eq!(frame.header.prebox_header[0], "rotated");
+        assert_eq!(frame.header.boxl, [2.0, 3.0, 4.0]);
+        assert_eq!(frame.header.angles, [90.0, 90.0, 90.0]);
+        assert_eq!(frame.header.natms_per_type, vec![1, 1]);
+        let cu = &frame.atom_data[0];
+        assert_eq!((cu.symbol_str(), cu.atom_id, cu.is_fixed), ("Cu", 0, true));
+        // Fractional (0.5, 0.5, 0.5) in the POSCAR lattice.
+        for (got, want) in [cu.x, cu.y, cu.z].iter().zip([1.0, 1.5, 2.0]) {
+            assert!((got - want).abs() < 1e-12, "{got} != {want}");
+        }
+        assert!(!frame.atom_data[1].is_fixed);
+        assert_eq!(frame.atom_data[1].atom_id, 1);
+    }
+
+    #[test]
+    fn test_parse_poscar_rejects_vasp4() {
+        let poscar = "old\n1.0\n1 0 0\n0 1 0\n0 0 1\n2\nDirect\n0 0 0\n0.5 0.5 0.5\n";
+        assert!(matches!(
+            parse_poscar(poscar),
+            Err(ParseError::UnsupportedFormat(_))
+        ));
+        let degenerate = "flat\n1.0\n1 0 0\n2 0 0\n0 0 1\nH\n1\nDirect\n0 0 0\n";
+        assert!(matches!(
+            parse_poscar(degenerate),
+            Err(ParseError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_xyz() {
+        // The second frame's lattice is rotated: a along y and b along -x.
+        let xyz = "2\nLattice=\"2 0 0 0 3 0 0 0 4\" \
+                   Properties=species:S:1:pos:R:3:velo:R:3 pbc=\"T T T\"\n\
+                   H 1 1 1 0.1 0.2 0.3\nCu 0 0 0 0 0 0\n\n\
+                   1\nlattice=\"0 2 0 -3 0 0 0 0 4\" properties=species:S:1:pos:R:3:velo:R:3\n\
+                   H -1.5 1 2 0 0.5 0\n";
+        let frames = parse_xyz(xyz).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].header.boxl, [2.0, 3.0, 4.0]);
+        assert_eq!(frames[0].header.angles, [90.0, 90.0, 90.0]);
+        let h = &frames[0].atom_data[0];
+        assert_eq!(
+            (h.symbol_str(), h.atom_id, h.x, h.y, h.z),
+            ("H", 0, 1.0, 1.0, 1.0)
+        );
+        assert_eq!((h.vx, h.vy, h.vz), (Some(0.1), Some(0.2), Some(0.3)));
+        assert_eq!(frames[0].atom_data[1].atom_id, 1);
+
+        let h = &frames[1].atom_data[0];
+        let rotated = [h.x, h.y, h.z, h.vx.unwrap(), h.vy.unwrap(), h.vz.unwrap()];
+        for (got, want) in rotated.iter().zip([1.0, 1.5, 2.0, 0.5, 0.0, 0.0]) {
+            assert!((got - want).abs() < 1e-12, "{got} != {want}");
+        }
+    }
+
+    #[test]
+    fn test_parse_xyz_plain_needs_cell() {
+        let xyz = "2\nwater-ish\nO 0 0 0\nH 0.96 0 0\n";
+        assert!(matches!(
+            parse_xyz(xyz),
+            Err(ParseError::UnsupportedFormat(_))
+        ));
+        let frames = parse_xyz_with_cell(xyz, [10.0; 3], [90.0; 3]).unwrap();
+        assert_eq!(frames[0].header.boxl, [10.0; 3]);
+        assert_eq!(frames[0].header.prebox_header[0], "water-ish");
+        assert_eq!(frames[0].atom_data[1].x, 0.96);
+        assert!(!frames[0].has_velocities());
+
+        assert!(matches!(
+            parse_xyz_with_cell("3\nshort\nH 0 0 0\n", [10.0; 3], [90.0; 3]),
+            Err(ParseError::IncompleteFrame)
+        ));
+    }
+
+    #[test]
+    fn test_parse_xyz_free_text_comment() {
+        // Key names in ordinary prose are not extended XYZ keys.
+        for comment in [
+            "Lattice relaxation step 3",
+            "Properties of water",
+            "pbc T T T",
+        ] {
+            let xyz = format!("1\n{comment}\nO 0 0 0\n");
+            let frames = parse_xyz_with_cell(&xyz, [10.0; 3], [90.0; 3]).unwrap();
+            assert_eq!(frames[0].header.prebox_header[0], comment);
+            assert!(matches!(
+                parse_xyz(&xyz),
+                Err(ParseError::UnsupportedFormat(_))
+            ));
+        }
+        assert_eq!(
+            extxyz_value("Lattice relaxation Lattice=\"1 0 0\"", "lattice"),
+            Some("1 0 0")
+        );
+        assert_eq!(extxyz_value("energy = -3.2", "energy"), None);
+    }
 }
@@ -1,14 +1,24 @@
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+
+#[cfg(feature = "numpy")]
+use numpy::{
+    IntoPyArray, PyArray1, PyArray2, PyArrayMethods, PyReadonlyArray1, PyReadonlyArray2,
+    PyUntypedArrayMethods,
+};
+use pyo3::IntoPyObjectExt;
+use pyo3::exceptions::{PyIOError, PyIndexError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::exceptions::PyIOError;
-use pyo3::types::IntoPyDict;
+use pyo3::types::{IntoPyDict, PySlice, PyTuple, PyType};
 
-use crate::iterators::ConFrameIterator;
+use crate::helpers;
+use crate::iterators::{self, ConFrameIterator, FileContents, FrameIndex};
 use crate::types::{AtomDatum, ConFrame, ConFrameBuilder};
-use crate::writer::ConFrameWriter;
+use crate::writer::{ConFrameWriter, FormatOptions, Notation, write_con_string_with_options};
 
 /// Python-visible atom data.
-#[pyclass(name = "Atom", from_py_object)]
-#[derive(Clone)]
+#[pyclass(name = "Atom", module = "readcon", from_py_object, eq, hash, frozen)]
+#[derive(Clone, PartialEq)]
 pub struct PyAtomDatum {
     #[pyo3(get)]
     pub symbol: String,
@@ -67,6 +77,26 @@ impl PyAtomDatum {
         self.vx.is_some() && self.vy.is_some() && self.vz.is_some()
     }
 
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
+        let a = slf.get();
+        let args = (
+            a.symbol.clone(),
+            a.x,
+            a.y,
+            a.z,
+            a.is_fixed,
+            a.atom_id,
+            a.mass,
+            a.vx,
+            a.vy,
+            a.vz,
+        )
+            .into_pyobject(slf.py())?;
+        Ok((slf.get_type(), args))
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "Atom(symbol='{}', x={}, y={}, z={}, atom_id={})",
@@ -75,10 +105,58 @@ impl PyAtomDatum {
     }
 }
 
+/// Hashes a float by value, so that `0.0` and `-0.0` (which compare equal)
+/// hash alike.
+fn hash_f64<H: Hasher>(value: f64, state: &mut H) {
+    let value = if value == 0.0 { 0.0 } else { value };
+    value.to_bits().hash(state);
+}
+
+fn hash_opt_f64<H: Hasher>(value: Option<f64>, state: &mut H) {
+    value.is_some().hash(state);
+    if let Some(v) = value {
+        hash_f64(v, state);
+    }
+}
+
+impl Hash for PyAtomDatum {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.symbol.hash(state);
+        for v in [self.x, self.y, self.z] {
+            hash_f64(v, state);
+        }
+        self.is_fixed.hash(state);
+        self.atom_id.hash(state);
+        for v in [self.mass, self.vx, self.vy, self.vz] {
+            hash_opt_f64(v, state);
+        }
+    }
+}
+
+impl Hash for PyConFrame {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for v in self.cell.iter().chain(&self.angles) {
+            hash_f64(*v, state);
+        }
+        self.prebox_header.hash(state);
+        self.postbox_header.hash(state);
+        self.atoms_inner.hash(state);
+        self.has_velocities.hash(state);
+    }
+}
+
 impl PyAtomDatum {
+    /// The atom's mass, falling back to the standard mass of its element and
+    /// then to 0.0.
+    fn mass_or_standard(&self) -> f64 {
+        self.mass
+            .or_else(|| helpers::standard_atomic_mass(&self.symbol))
+            .unwrap_or(0.0)
+    }
+
     fn from_atom_with_mass(atom: &AtomDatum, mass: f64) -> Self {
         PyAtomDatum {
-            symbol: (*atom.symbol).clone(),
+            symbol: atom.symbol_str().to_string(),
             x: atom.x,
             y: atom.y,
             z: atom.z,
@@ -93,8 +171,15 @@ impl PyAtomDatum {
 }
 
 /// Python-visible simulation frame.
-#[pyclass(name = "ConFrame", from_py_object)]
-#[derive(Clone)]
+#[pyclass(
+    name = "ConFrame",
+    module = "readcon",
+    from_py_object,
+    eq,
+    hash,
+    frozen
+)]
+#[derive(Clone, PartialEq)]
 pub struct PyConFrame {
     #[pyo3(get)]
     pub cell: [f64; 3],
@@ -136,6 +221,21 @@ impl PyConFrame {
         self.atoms_inner.clone()
     }
 
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
+        let f = slf.get();
+        let args = (
+            f.cell,
+            f.angles,
+            f.atoms_inner.clone(),
+            f.prebox_header.clone(),
+            f.postbox_header.clone(),
+        )
+            .into_pyobject(slf.py())?;
+        Ok((slf.get_type(), args))
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "ConFrame(cell={:?}, angles={:?}, natoms={}, has_velocities={})",
@@ -150,33 +250,146 @@ impl PyConFrame {
         self.atoms_inner.len()
     }
 
+    /// Chemical symbols of all atoms, in order.
+    #[getter]
+    fn symbols(&self) -> Vec<String> {
+        self.atoms_inner.iter().map(|a| a.symbol.clone()).collect()
+    }
+
+    /// Positions as an `(N, 3)` float64 array (requires the `numpy` feature).
+    #[cfg(feature = "numpy")]
+    #[getter]
+    fn positions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+        let flat: Vec<f64> = self
+            .atoms_inner
+            .iter()
+            .flat_map(|a| [a.x, a.y, a.z])
+            .collect();
+        flat.into_pyarray(py).reshape([self.atoms_inner.len(), 3])
+    }
+
+    /// Velocities as an `(N, 3)` float64 array, or `None` if any atom lacks
+    /// them (requires the `numpy` feature).
+    #[cfg(feature = "numpy")]
+    #[getter]
+    fn velocities<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyArray2<f64>>>> {
+        let flat: Option<Vec<f64>> = self
+            .atoms_inner
+            .iter()
+            .map(|a| Some([a.vx?, a.vy?, a.vz?]))
+            .collect::<Option<Vec<_>>>()
+            .map(|rows| rows.into_iter().flatten().collect());
+        flat.map(|flat| flat.into_pyarray(py).reshape([self.atoms_inner.len(), 3]))
+            .transpose()
+    }
+
+    /// Masses as an `(N,)` float64 array (requires the `numpy` feature).
+    /// Atoms without a mass get the standard mass of their element, or 0.0.
+    #[cfg(feature = "numpy")]
+    #[getter]
+    fn masses<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        let masses: Vec<f64> = self
+            .atoms_inner
+            .iter()
+            .map(|a| a.mass_or_standard())
+            .collect();
+        masses.into_pyarray(py)
+    }
+
+    /// Build a frame from NumPy arrays (requires the `numpy` feature).
+    ///
+    /// `positions` and `velocities` are `(N, 3)`, `fixed` and `masses` are
+    /// `(N,)`, and `symbols` has length `N`. Atom ids are assigned `0..N`.
+    /// Atoms without a mass get the standard mass of their element, or 0.0.
+    /// As with every frame, atoms are grouped by type in encounter order.
+    /// Raises `ValueError` if the shapes disagree.
+    #[cfg(feature = "numpy")]
+    #[staticmethod]
+    #[pyo3(signature = (
+        symbols, positions, cell, angles, velocities=None, fixed=None, masses=None
+    ))]
+    fn from_arrays(
+        symbols: Vec<String>,
+        positions: PyReadonlyArray2<'_, f64>,
+        cell: [f64; 3],
+        angles: [f64; 3],
+        velocities: Option<PyReadonlyArray2<'_, f64>>,
+        fixed: Option<PyReadonlyArray1<'_, bool>>,
+        masses: Option<PyReadonlyArray1<'_, f64>>,
+    ) -> PyResult<Self> {
+        let n = symbols.len();
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        let xyz = xyz_rows("positions", &positions)?;
+        let fixed = match fixed {
+            Some(f) => f.as_array().to_vec(),
+            None => vec![false; n],
+        };
+        let ids: Vec<u64> = (0..n as u64).collect();
+        let masses = match masses {
+            Some(m) => m.as_array().to_vec(),
+            None => symbols
+                .iter()
+                .map(|s| helpers::standard_atomic_mass(s).unwrap_or(0.0))
+                .collect(),
+        };
+
+        let mut builder = ConFrameBuilder::new(cell, angles);
+        let added = match velocities {
+            Some(v) => {
+                let velocities = xyz_rows("velocities", &v)?;
+                builder.add_atoms_soa_with_velocity(
+                    &symbols,
+                    &xyz,
+                    &fixed,
+                    &ids,
+                    &masses,
+                    &velocities,
+                )
+            }
+            None => builder.add_atoms_soa(&symbols, &xyz, &fixed, &ids, &masses),
+        };
+        added.map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyConFrame::from(&builder.build()))
+    }
+
     /// Convert this frame to an ASE Atoms object (requires ase package).
+    ///
+    /// Velocities are passed through unchanged: eOn and ASE share the same
+    /// time unit (Å·sqrt(amu/eV), about 10.18 fs), so no conversion is needed.
     fn to_ase(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         ase_from_pyconframe(py, self)
     }
 
     /// Create a ConFrame from an ASE Atoms object.
+    ///
+    /// Velocities are read when the Atoms object carries momenta.
     #[staticmethod]
     fn from_ase(py: Python<'_>, ase_atoms: &Bound<'_, PyAny>) -> PyResult<Self> {
         pyconframe_from_ase(py, ase_atoms)
     }
 }
 
+/// Reads an `(N, 3)` array argument into rows, raising `ValueError` if it
+/// does not have three columns. The row count is checked by the builder.
+#[cfg(feature = "numpy")]
+fn xyz_rows(name: &str, array: &PyReadonlyArray2<'_, f64>) -> PyResult<Vec<[f64; 3]>> {
+    let shape = array.shape();
+    if shape[1] != 3 {
+        return Err(PyValueError::new_err(format!(
+            "{name} has shape {shape:?}, expected (N, 3)"
+        )));
+    }
+    Ok(array
+        .as_array()
+        .rows()
+        .into_iter()
+        .map(|row| [row[0], row[1], row[2]])
+        .collect())
+}
+
 impl From<&ConFrame> for PyConFrame {
     fn from(frame: &ConFrame) -> Self {
-        // Build per-atom mass lookup from per-type header data
-        let mut per_atom_mass: Vec<f64> = Vec::with_capacity(frame.atom_data.len());
-        for (type_idx, &count) in frame.header.natms_per_type.iter().enumerate() {
-            let mass = frame
-                .header
-                .masses_per_type
-                .get(type_idx)
-                .copied()
-                .unwrap_or(0.0);
-            for _ in 0..count {
-                per_atom_mass.push(mass);
-            }
-        }
+        let per_atom_mass = frame.header.per_atom_masses();
 
         let atoms: Vec<PyAtomDatum> = frame
             .atom_data
@@ -212,7 +425,7 @@ impl PyConFrame {
             ]);
 
         for py_atom in &self.atoms_inner {
-            let mass = py_atom.mass.unwrap_or(0.0);
+            let mass = py_atom.mass_or_standard();
             if py_atom.has_velocity() {
                 builder.add_atom_with_velocity(
                     &py_atom.symbol,
@@ -251,6 +464,220 @@ fn read_con(path: &str) -> PyResult<Vec<PyConFrame>> {
     read_con_string(&contents)
 }
 
+/// Validates `file_contents` as UTF-8 and extends the borrow to `'static`.
+///
+/// # Safety
+///
+/// The returned slice must not outlive the boxed contents. Callers store both
+/// in one struct, with the borrowing field declared (and so dropped) first.
+unsafe fn static_text(file_contents: &FileContents) -> PyResult<&'static str> {
+    let text = file_contents
+        .as_str()
+        .map_err(|e| PyIOError::new_err(format!("utf8 error: {e}")))?;
+    // SAFETY: a boxed `FileContents` never moves, and its heap or mapped
+    // storage stays put for as long as the box is alive.
+    Ok(unsafe { &*(text as *const str) })
+}
+
+/// Streaming writer for .con and .convel files.
+///
+/// Frames are written one at a time, so a trajectory never has to be held in
+/// memory. Use as a context manager to flush and close the file on exit:
+/// `with ConWriter(path) as w: w.write(frame)`.
+#[pyclass(name = "ConWriter", module = "readcon")]
+struct PyConWriter {
+    /// `None` once the writer has been closed.
+    writer: Option<ConFrameWriter<File>>,
+}
+
+impl PyConWriter {
+    fn writer(&mut self) -> PyResult<&mut ConFrameWriter<File>> {
+        self.writer
+            .as_mut()
+            .ok_or_else(|| PyValueError::new_err("write to closed ConWriter"))
+    }
+}
+
+#[pymethods]
+impl PyConWriter {
+    #[new]
+    #[pyo3(signature = (path, precision=6))]
+    fn new(path: &str, precision: usize) -> PyResult<Self> {
+        let writer = ConFrameWriter::from_path_with_precision(path, precision)
+            .map_err(|e| PyIOError::new_err(format!("failed to create writer: {e}")))?;
+        Ok(PyConWriter {
+            writer: Some(writer),
+        })
+    }
+
+    /// Write a single frame.
+    fn write(&mut self, frame: &PyConFrame) -> PyResult<()> {
+        self.writer()?
+            .write_frame(&frame.to_con_frame())
+            .map_err(|e| PyIOError::new_err(format!("write error: {e}")))
+    }
+
+    /// Flush buffered output to disk.
+    fn flush(&mut self) -> PyResult<()> {
+        self.writer()?
+            .flush()
+            .map_err(|e| PyIOError::new_err(format!("write error: {e}")))
+    }
+
+    /// Flush and close the file. Closing twice is a no-op.
+    fn close(&mut self) -> PyResult<()> {
+        match self.writer.take() {
+            Some(writer) => writer
+                .finish()
+                .map(drop)
+                .map_err(|e| PyIOError::new_err(format!("write error: {e}"))),
+            None => Ok(()),
+        }
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+}
+
+/// Lazy iterator over the frames of a .con or .convel file.
+///
+/// Frames are parsed one at a time on `__next__`; a parse error raises on the
+/// call that reaches the offending frame. The iterator is bound to the thread
+/// that created it, since the parser options are not `Send`.
+#[pyclass(name = "ConFrameIterator", module = "readcon", unsendable)]
+struct PyConFrameIterator {
+    /// Borrows from `file_contents`; declared first so it is dropped first.
+    iterator: ConFrameIterator<'static>,
+    file_contents: Box<FileContents>,
+}
+
+impl PyConFrameIterator {
+    fn new(file_contents: FileContents) -> PyResult<Self> {
+        let file_contents = Box::new(file_contents);
+        // SAFETY: the iterator is dropped before `file_contents`.
+        let text = unsafe { static_text(&file_contents)? };
+        Ok(Self {
+            iterator: ConFrameIterator::new(text),
+            file_contents,
+        })
+    }
+}
+
+#[pymethods]
+impl PyConFrameIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<PyConFrame>> {
+        match self.iterator.next() {
+            Some(Ok(frame)) => Ok(Some(PyConFrame::from(&frame))),
+            Some(Err(e)) => Err(PyIOError::new_err(format!("parse error: {e}"))),
+            None => Ok(None),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        let kind = match *self.file_contents {
+            FileContents::Owned(_) => "owned",
+            FileContents::Mapped(_) => "mmap",
+        };
+        format!("ConFrameIterator(contents={kind})")
+    }
+}
+
+/// Random-access view of a trajectory file.
+///
+/// Only frame offsets are computed up front; each `traj[i]` parses just the
+/// requested frame. Supports `len()`, negative indices and slicing.
+#[pyclass(name = "Trajectory", module = "readcon", frozen)]
+struct PyTrajectory {
+    /// Borrows from `file_contents`; declared first so it is dropped first.
+    text: &'static str,
+    index: FrameIndex,
+    /// Owns the storage behind `text`.
+    _file_contents: Box<FileContents>,
+}
+
+impl PyTrajectory {
+    fn frame(&self, i: usize) -> PyResult<PyConFrame> {
+        let frame = self
+            .index
+            .frame(self.text, i)
+            .map_err(|e| PyIOError::new_err(format!("parse error: {e}")))?;
+        Ok(PyConFrame::from(&frame))
+    }
+}
+
+#[pymethods]
+impl PyTrajectory {
+    fn __len__(&self) -> usize {
+        self.index.len()
+    }
+
+    fn __getitem__(&self, py: Python<'_>, key: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let len = self.index.len();
+        if let Ok(slice) = key.cast::<PySlice>() {
+            let idx = slice.indices(len as isize)?;
+            let frames = (0..idx.slicelength as isize)
+                .map(|k| self.frame((idx.start + k * idx.step) as usize))
+                .collect::<PyResult<Vec<_>>>()?;
+            return frames.into_py_any(py);
+        }
+        let i: isize = key.extract()?;
+        let resolved = if i < 0 { i + len as isize } else { i };
+        if resolved < 0 || resolved as usize >= len {
+            return Err(PyIndexError::new_err("trajectory index out of range"));
+        }
+        self.frame(resolved as usize)?.into_py_any(py)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Trajectory(n_frames={})", self.index.len())
+    }
+}
+
+/// Open a .con or .convel file for random access by frame index.
+///
+/// Only frame headers are read here, so a malformed atom line raises
+/// `IOError` when its frame is indexed, not when the file is loaded.
+#[pyfunction]
+fn load(path: &str) -> PyResult<PyTrajectory> {
+    let file_contents = iterators::read_file_contents(std::path::Path::new(path))
+        .map(Box::new)
+        .map_err(|e| PyIOError::new_err(format!("failed to read file: {e}")))?;
+    // SAFETY: `text` is dropped before `file_contents`.
+    let text = unsafe { static_text(&file_contents)? };
+    let index =
+        FrameIndex::build(text).map_err(|e| PyIOError::new_err(format!("parse error: {e}")))?;
+    Ok(PyTrajectory {
+        text,
+        index,
+        _file_contents: file_contents,
+    })
+}
+
+/// Lazily iterate over the frames of a .con or .convel file path.
+///
+/// Large files are memory-mapped rather than read into memory.
+#[pyfunction]
+fn read_con_iter(path: &str) -> PyResult<PyConFrameIterator> {
+    let contents = iterators::read_file_contents(std::path::Path::new(path))
+        .map_err(|e| PyIOError::new_err(format!("failed to read file: {e}")))?;
+    PyConFrameIterator::new(contents)
+}
+
 /// Read frames from a string containing .con or .convel data.
 #[pyfunction]
 fn read_con_string(contents: &str) -> PyResult<Vec<PyConFrame>> {
@@ -263,6 +690,17 @@ fn read_con_string(contents: &str) -> PyResult<Vec<PyConFrame>> {
     Ok(frames)
 }
 
+/// Parse every frame in a string and return all errors as (frame index, message)
+/// pairs, resynchronising after each malformed frame. An empty list means the
+/// contents are valid.
+#[pyfunction]
+fn validate_con_string(contents: &str) -> Vec<(usize, String)> {
+    iterators::validate(contents)
+        .into_iter()
+        .map(|(index, e)| (index, e.to_string()))
+        .collect()
+}
+
 /// Write frames to a .con or .convel file path.
 #[pyfunction]
 #[pyo3(signature = (path, frames, precision=6))]
@@ -277,18 +715,29 @@ fn write_con(path: &str, frames: Vec<PyConFrame>, precision: usize) -> PyResult<
 }
 
 /// Write frames to a string in .con format.
+///
+/// `column_width` right-aligns coordinate and velocity fields to at least that
+/// many characters, and `scientific` renders them as e.g. `1.000000e-6`. The
+/// defaults reproduce the standard output.
 #[pyfunction]
-#[pyo3(signature = (frames, precision=6))]
-fn write_con_string(frames: Vec<PyConFrame>, precision: usize) -> PyResult<String> {
+#[pyo3(signature = (frames, precision=6, column_width=None, scientific=false))]
+fn write_con_string(
+    frames: Vec<PyConFrame>,
+    precision: usize,
+    column_width: Option<usize>,
+    scientific: bool,
+) -> String {
     let rust_frames: Vec<ConFrame> = frames.iter().map(|f| f.to_con_frame()).collect();
-    let mut buffer: Vec<u8> = Vec::new();
-    {
-        let mut writer = ConFrameWriter::with_precision(&mut buffer, precision);
-        writer
-            .extend(rust_frames.iter())
-            .map_err(|e| PyIOError::new_err(format!("write error: {e}")))?;
-    }
-    String::from_utf8(buffer).map_err(|e| PyIOError::new_err(format!("utf8 error: {e}")))
+    let notation = if scientific {
+        Notation::Scientific
+    } else {
+        Notation::Fixed
+    };
+    let format = FormatOptions::default()
+        .precision(precision)
+        .coord_width(column_width.unwrap_or(0))
+        .notation(notation);
+    write_con_string_with_options(&rust_frames, format)
 }
 
 /// Read a .con file and return a list of ASE Atoms objects.
@@ -296,10 +745,7 @@ fn write_con_string(frames: Vec<PyConFrame>, precision: usize) -> PyResult<Strin
 #[pyfunction]
 fn read_con_as_ase(py: Python<'_>, path: &str) -> PyResult<Vec<Py<PyAny>>> {
     let frames = read_con(path)?;
-    frames
-        .iter()
-        .map(|f| ase_from_pyconframe(py, f))
-        .collect()
+    frames.iter().map(|f| ase_from_pyconframe(py, f)).collect()
 }
 
 // --- ASE conversion helpers (runtime import, no compile-time dep) ---
@@ -309,25 +755,15 @@ fn ase_from_pyconframe(py: Python<'_>, frame: &PyConFrame) -> PyResult<Py<PyAny>
     let ase_atoms_cls = ase.getattr("Atoms")?;
 
     // Build symbols list and positions array
-    let symbols: Vec<&str> = frame.atoms_inner.iter().map(|a| a.symbol.as_str()).collect();
-    let positions: Vec<[f64; 3]> = frame
+    let symbols: Vec<&str> = frame
         .atoms_inner
         .iter()
-        .map(|a| [a.x, a.y, a.z])
+        .map(|a| a.symbol.as_str())
         .collect();
+    let positions: Vec<[f64; 3]> = frame.atoms_inner.iter().map(|a| [a.x, a.y, a.z]).collect();
 
-    // Build cell from lengths + angles using ASE's cellpar_to_cell
-    let cellpar: Vec<f64> = frame
-        .cell
-        .iter()
-        .chain(frame.angles.iter())
-        .copied()
-        .collect();
-
-    let ase_cell_mod = py.import("ase.geometry.cell")?;
-    let cell = ase_cell_mod
-        .getattr("cellpar_to_cell")?
-        .call1((cellpar,))?;
+    // Build cell from lengths + angles (same convention as ASE's cellpar_to_cell)
+    let cell: Vec<[f64; 3]> = helpers::cell_matrix(frame.cell, frame.angles).to_vec();
 
     let atoms = ase_atoms_cls.call(
         (),
@@ -335,13 +771,30 @@ fn ase_from_pyconframe(py: Python<'_>, frame: &PyConFrame) -> PyResult<Py<PyAny>
             &[
                 ("symbols", symbols.into_pyobject(py)?.into_any()),
                 ("positions", positions.into_pyobject(py)?.into_any()),
-                ("cell", cell.into_any()),
+                ("cell", cell.into_pyobject(py)?.into_any()),
                 ("pbc", true.into_pyobject(py)?.to_owned().into_any()),
             ]
             .into_py_dict(py)?,
         ),
     )?;
 
+    // Velocities must be set before the constraint, since ASE zeroes the
+    // momenta of fixed atoms when applying FixAtoms.
+    if frame.has_velocities {
+        let velocities: Vec<[f64; 3]> = frame
+            .atoms_inner
+            .iter()
+            .map(|a| {
+                [
+                    a.vx.unwrap_or(0.0),
+                    a.vy.unwrap_or(0.0),
+                    a.vz.unwrap_or(0.0),
+                ]
+            })
+            .collect();
+        atoms.call_method1("set_velocities", (velocities,))?;
+    }
+
     // Set FixAtoms constraint for fixed atoms
     let fixed_indices: Vec<usize> = frame
         .atoms_inner
@@ -353,15 +806,10 @@ fn ase_from_pyconframe(py: Python<'_>, frame: &PyConFrame) -> PyResult<Py<PyAny>
 
     if !fixed_indices.is_empty() {
         let ase_constraints = py.import("ase.constraints")?;
-        let fix_atoms = ase_constraints
-            .getattr("FixAtoms")?
-            .call(
-                (),
-                Some(
-                    &[("indices", fixed_indices.into_pyobject(py)?.into_any())]
-                        .into_py_dict(py)?,
-                ),
-            )?;
+        let fix_atoms = ase_constraints.getattr("FixAtoms")?.call(
+            (),
+            Some(&[("indices", fixed_indices.into_pyobject(py)?.into_any())].into_py_dict(py)?),
+        )?;
         atoms.call_method1("set_constraint", (vec![fix_atoms],))?;
     }
 
@@ -370,9 +818,7 @@ fn ase_from_pyconframe(py: Python<'_>, frame: &PyConFrame) -> PyResult<Py<PyAny>
 
 fn pyconframe_from_ase(_py: Python<'_>, ase_atoms: &Bound<'_, PyAny>) -> PyResult<PyConFrame> {
     // Extract symbols
-    let symbols: Vec<String> = ase_atoms
-        .call_method0("get_chemical_symbols")?
-        .extract()?;
+    let symbols: Vec<String> = ase_atoms.call_method0("get_chemical_symbols")?.extract()?;
 
     // Extract positions
     let positions: Vec<Vec<f64>> = ase_atoms
@@ -416,6 +862,21 @@ fn pyconframe_from_ase(_py: Python<'_>, ase_atoms: &Bound<'_, PyAny>) -> PyResul
         .and_then(|m| m.call_method0("tolist").ok())
         .and_then(|m| m.extract().ok());
 
+    // Extract velocities. `get_velocities` returns zeros rather than None when
+    // no momenta are set, so check for the momenta array explicitly.
+    let has_momenta: bool = ase_atoms.call_method1("has", ("momenta",))?.extract()?;
+    let velocities: Option<Vec<Vec<f64>>> = if has_momenta {
+        Some(
+            ase_atoms
+                .call_method0("get_velocities")?
+                .call_method0("tolist")?
+                .extract()?,
+        )
+    } else {
+        None
+    };
+    let velocity = |i: usize, axis: usize| velocities.as_ref().map(|v| v[i][axis]);
+
     // Build PyAtomDatum list
     let atoms: Vec<PyAtomDatum> = symbols
         .iter()
@@ -429,13 +890,13 @@ fn pyconframe_from_ase(_py: Python<'_>, ase_atoms: &Bound<'_, PyAny>) -> PyResul
             is_fixed: fixed_set.contains(&i),
             atom_id: i as u64,
             mass: masses.as_ref().map(|m| m[i]),
-            vx: None,
-            vy: None,
-            vz: None,
+            vx: velocity(i, 0),
+            vy: velocity(i, 1),
+            vz: velocity(i, 2),
         })
         .collect();
 
-    let has_velocities = false;
+    let has_velocities = velocities.is_some();
     Ok(PyConFrame {
         cell,
         angles,
@@ -451,8 +912,14 @@ fn pyconframe_from_ase(_py: Python<'_>, ase_atoms: &Bound<'_, PyAny>) -> PyResul
 fn readcon(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyAtomDatum>()?;
     m.add_class::<PyConFrame>()?;
+    m.add_class::<PyConFrameIterator>()?;
+    m.add_class::<PyTrajectory>()?;
+    m.add_class::<PyConWriter>()?;
     m.add_function(wrap_pyfunction!(read_con, m)?)?;
+    m.add_function(wrap_pyfunction!(read_con_iter, m)?)?;
+    m.add_function(wrap_pyfunction!(load, m)?)?;
     m.add_function(wrap_pyfunction!(read_con_string, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_con_string, m)?)?;
     m.add_function(wrap_pyfunction!(write_con, m)?)?;
     m.add_function(wrap_pyfunction!(write_con_string, m)?)?;
     m.add_function(wrap_pyfunction!(read_con_as_ase, m)?)?;
@@ -1,8 +1,16 @@
-use capnp_rpc::{RpcSystem, twoparty, rpc_twoparty_capnp};
+use capnp::capability::Promise;
+use capnp_rpc::{RpcSystem, pry, rpc_twoparty_capnp, twoparty};
+use futures::channel::mpsc;
+use futures::future::{self, Either};
+use futures::{AsyncReadExt, SinkExt, StreamExt};
 
-use crate::iterators::ConFrameIterator;
+use super::convert::{read_frame_data, write_frame_data};
+use super::read_con_capnp::{frame_sink, read_con_service};
 use crate::types::ConFrame;
-use super::read_con_capnp::read_con_service;
+
+/// Received frames a streaming parse may queue before the server is made to
+/// wait.
+pub const STREAM_BUFFER_FRAMES: usize = 256;
 
 /// A synchronous RPC client that wraps the Cap'n Proto async transport.
 pub struct RpcClient {
@@ -32,26 +40,13 @@ impl RpcClient {
     }
 
     /// Parses raw file bytes via the RPC server.
-    pub fn parse_bytes(
-        &self,
-        data: &[u8],
-    ) -> Result<Vec<ConFrame>, Box<dyn std::error::Error>> {
-        self.runtime.block_on(async {
-            let stream = tokio::net::TcpStream::connect(&self.addr).await?;
-            stream.set_nodelay(true)?;
-            let (reader, writer) =
-                tokio_util::compat::TokioAsyncReadCompatExt::compat(stream).split();
-            let network = twoparty::VatNetwork::new(
-                reader,
-                writer,
-                rpc_twoparty_capnp::Side::Client,
-                Default::default(),
-            );
-            let mut rpc_system = RpcSystem::new(Box::new(network), None);
-            let service: read_con_service::Client =
-                rpc_system.bootstrap(rpc_twoparty_capnp::Side::Server);
-
-            tokio::task::spawn_local(rpc_system);
+    ///
+    /// Frames are rebuilt from the server's response; a parse error on the
+    /// server is returned as an error.
+    pub fn parse_bytes(&self, data: &[u8]) -> Result<Vec<ConFrame>, Box<dyn std::error::Error>> {
+        let local = tokio::task::LocalSet::new();
+        local.block_on(&self.runtime, async {
+            let service = self.connect().await?;
 
             let mut request = service.parse_frames_request();
             request.get().init_req().set_file_contents(data);
@@ -59,37 +54,159 @@ impl RpcClient {
             let result = response.get()?.get_result()?;
             let frame_data_list = result.get_frames()?;
 
-            // Convert Cap'n Proto frames back to Rust ConFrame by serializing
-            // back through the writer/parser roundtrip (the schema carries
-            // enough data to reconstruct the text format).
-            //
-            // For a simpler approach, we just parse the returned data as UTF-8
-            // text and feed it through ConFrameIterator. This works because the
-            // server's writeFrames does exactly this serialization.
-            //
-            // Instead, reconstruct directly from the Cap'n Proto messages:
-            let _ = frame_data_list; // suppress warning
-            // The simplest path: ask the server to also return the serialized text
-            // For now, just parse the original data locally as fallback
-            let contents = std::str::from_utf8(data)?;
-            let iter = ConFrameIterator::new(contents);
-            let frames: Result<Vec<_>, _> = iter.collect();
-            Ok(frames?)
+            let mut frames = Vec::with_capacity(frame_data_list.len() as usize);
+            for fd in frame_data_list.iter() {
+                frames.push(read_frame_data(fd)?);
+            }
+            Ok(frames)
         })
     }
 
-    /// Writes frames by sending them to the RPC server, receiving serialized output.
-    pub fn write_frames(
+    /// Parses a file on the RPC server, streaming frames back in chunks.
+    ///
+    /// See [`RpcClient::parse_bytes_streaming`].
+    pub fn parse_file_streaming<F>(
         &self,
-        frames: &[ConFrame],
-    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-        use crate::writer::ConFrameWriter;
-        // Serialize locally and send to server for validation/processing
-        let mut buffer: Vec<u8> = Vec::new();
-        {
-            let mut writer = ConFrameWriter::new(&mut buffer);
-            writer.extend(frames.iter())?;
+        path: &std::path::Path,
+        on_frame: F,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: FnMut(ConFrame),
+    {
+        let data = std::fs::read(path)?;
+        self.parse_bytes_streaming(&data, on_frame)
+    }
+
+    /// Parses raw file bytes via the RPC server, handing each frame to
+    /// `on_frame` as it arrives instead of collecting the whole trajectory.
+    ///
+    /// At most [`STREAM_BUFFER_FRAMES`] received frames are queued locally; the
+    /// server waits for the client to catch up before parsing further. A parse
+    /// error on the server is returned after the frames preceding it have
+    /// been delivered.
+    pub fn parse_bytes_streaming<F>(
+        &self,
+        data: &[u8],
+        mut on_frame: F,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: FnMut(ConFrame),
+    {
+        let local = tokio::task::LocalSet::new();
+        local.block_on(&self.runtime, async {
+            let service = self.connect().await?;
+
+            let (tx, mut rx) = mpsc::channel(STREAM_BUFFER_FRAMES);
+            let sink: frame_sink::Client = capnp_rpc::new_client(FrameSinkImpl { tx });
+
+            let mut request = service.parse_frames_streaming_request();
+            {
+                let mut params = request.get();
+                params.reborrow().init_req().set_file_contents(data);
+                params.set_sink(sink);
+                params.set_chunk_size(0);
+            }
+            let mut call = request.send().promise;
+
+            let outcome = loop {
+                match future::select(rx.next(), call).await {
+                    Either::Left((Some(frame), pending)) => {
+                        on_frame(frame);
+                        call = pending;
+                    }
+                    Either::Left((None, pending)) => break pending.await,
+                    Either::Right((result, _)) => break result,
+                }
+            };
+            // Frames queued before the call completed, including those the
+            // server pushed ahead of a parse error.
+            while let Ok(Some(frame)) = rx.try_next() {
+                on_frame(frame);
+            }
+            outcome?;
+            Ok(())
+        })
+    }
+
+    /// Opens a connection and returns the bootstrap service capability.
+    ///
+    /// The RPC system is spawned onto the current `LocalSet`.
+    async fn connect(&self) -> Result<read_con_service::Client, Box<dyn std::error::Error>> {
+        let stream = tokio::net::TcpStream::connect(&self.addr).await?;
+        stream.set_nodelay(true)?;
+        let (reader, writer) = tokio_util::compat::TokioAsyncReadCompatExt::compat(stream).split();
+        let network = twoparty::VatNetwork::new(
+            reader,
+            writer,
+            rpc_twoparty_capnp::Side::Client,
+            Default::default(),
+        );
+        let mut rpc_system = RpcSystem::new(Box::new(network), None);
+        let service: read_con_service::Client =
+            rpc_system.bootstrap(rpc_twoparty_capnp::Side::Server);
+
+        tokio::task::spawn_local(rpc_system);
+        Ok(service)
+    }
+
+    /// Writes frames by sending them to the RPC server, receiving serialized output.
+    ///
+    /// Per-type masses travel with each frame, so the output keeps the masses
+    /// the frames were parsed with. This makes a network round-trip and fails
+    /// if the server is unreachable; use [`crate::writer::ConFrameWriter`] to
+    /// serialize without a server.
+    pub fn write_frames(&self, frames: &[ConFrame]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let local = tokio::task::LocalSet::new();
+        local.block_on(&self.runtime, async {
+            let service = self.connect().await?;
+
+            let mut request = service.write_frames_request();
+            let mut frames_builder = request.get().init_req().init_frames(frames.len() as u32);
+            for (i, frame) in frames.iter().enumerate() {
+                write_frame_data(frames_builder.reborrow().get(i as u32), frame);
+            }
+            let response = request.send().promise.await?;
+            let contents = response.get()?.get_result()?.get_file_contents()?;
+            Ok(contents.to_vec())
+        })
+    }
+}
+
+/// Receives frames pushed by the server during a streaming parse and forwards
+/// them into a bounded channel, acknowledging each chunk once it is queued.
+struct FrameSinkImpl {
+    tx: mpsc::Sender<ConFrame>,
+}
+
+impl frame_sink::Server for FrameSinkImpl {
+    fn push_frames(
+        &mut self,
+        params: frame_sink::PushFramesParams,
+        _results: frame_sink::PushFramesResults,
+    ) -> Promise<(), capnp::Error> {
+        let frame_data_list = pry!(pry!(params.get()).get_frames());
+        let mut frames = Vec::with_capacity(frame_data_list.len() as usize);
+        for fd in frame_data_list.iter() {
+            frames.push(pry!(read_frame_data(fd)));
         }
-        Ok(buffer)
+
+        let mut tx = self.tx.clone();
+        Promise::from_future(async move {
+            for frame in frames {
+                tx.send(frame)
+                    .await
+                    .map_err(|e| capnp::Error::failed(e.to_string()))?;
+            }
+            Ok(())
+        })
+    }
+
+    fn done(
+        &mut self,
+        _params: frame_sink::DoneParams,
+        _results: frame_sink::DoneResults,
+    ) -> Promise<(), capnp::Error> {
+        self.tx.close_channel();
+        Promise::ok(())
     }
 }
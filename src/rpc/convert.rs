@@ -0,0 +1,184 @@
+//! Conversions between [`ConFrame`] and the Cap'n Proto `ConFrameData` struct.
+
+use std::rc::Rc;
+
+use capnp::{primitive_list, text_list};
+
+use super::read_con_capnp::con_frame_data;
+use crate::helpers;
+use crate::types::{AtomDatum, ConFrame, FrameHeader};
+
+/// Fills a `ConFrameData` builder from a frame.
+pub(crate) fn write_frame_data(mut fb: con_frame_data::Builder<'_>, frame: &ConFrame) {
+    // Cell
+    let mut cell = fb.reborrow().init_cell(3);
+    for (j, &v) in frame.header.boxl.iter().enumerate() {
+        cell.set(j as u32, v);
+    }
+
+    // Angles
+    let mut angles = fb.reborrow().init_angles(3);
+    for (j, &v) in frame.header.angles.iter().enumerate() {
+        angles.set(j as u32, v);
+    }
+
+    // Headers
+    let mut prebox = fb.reborrow().init_prebox_header(2);
+    prebox.set(0, &frame.header.prebox_header[0]);
+    prebox.set(1, &frame.header.prebox_header[1]);
+
+    let mut postbox = fb.reborrow().init_postbox_header(2);
+    postbox.set(0, &frame.header.postbox_header[0]);
+    postbox.set(1, &frame.header.postbox_header[1]);
+
+    fb.set_has_velocities(frame.has_velocities());
+
+    // Types
+    let natms_per_type = &frame.header.natms_per_type;
+    let mut counts = fb
+        .reborrow()
+        .init_natms_per_type(natms_per_type.len() as u32);
+    for (j, &n) in natms_per_type.iter().enumerate() {
+        counts.set(j as u32, n as u64);
+    }
+
+    // Masses
+    let masses_per_type = &frame.header.masses_per_type;
+    let mut masses = fb
+        .reborrow()
+        .init_masses_per_type(masses_per_type.len() as u32);
+    for (j, &m) in masses_per_type.iter().enumerate() {
+        masses.set(j as u32, m);
+    }
+
+    // Atoms
+    let mut atoms_builder = fb.reborrow().init_atoms(frame.atom_data.len() as u32);
+    for (k, atom) in frame.atom_data.iter().enumerate() {
+        let mut ab = atoms_builder.reborrow().get(k as u32);
+        ab.set_symbol(atom.symbol_str());
+        ab.set_x(atom.x);
+        ab.set_y(atom.y);
+        ab.set_z(atom.z);
+        ab.set_is_fixed(atom.is_fixed);
+        ab.set_atom_id(atom.atom_id);
+        ab.set_vx(atom.vx.unwrap_or(0.0));
+        ab.set_vy(atom.vy.unwrap_or(0.0));
+        ab.set_vz(atom.vz.unwrap_or(0.0));
+        ab.set_has_velocity(atom.has_velocity());
+    }
+}
+
+/// Rebuilds a frame from a `ConFrameData` reader, reversing
+/// [`write_frame_data`].
+///
+/// Type blocks come from `natmsPerType`, which must add up to the number of
+/// atoms. For messages from peers that predate that field, each run of equal
+/// symbols becomes one type. Masses are taken from `massesPerType`; types
+/// without an entry get the standard atomic mass of their symbol (0.0 if
+/// unknown or if the type is empty).
+pub(crate) fn read_frame_data(fd: con_frame_data::Reader<'_>) -> capnp::Result<ConFrame> {
+    let boxl = read_triple(fd.get_cell()?, "cell")?;
+    let angles = read_triple(fd.get_angles()?, "angles")?;
+    let prebox_header = read_header_lines(fd.get_prebox_header()?, "preboxHeader")?;
+    let postbox_header = read_header_lines(fd.get_postbox_header()?, "postboxHeader")?;
+    let masses = fd.get_masses_per_type()?;
+    let atoms_list = fd.get_atoms()?;
+
+    let mut atom_data = Vec::with_capacity(atoms_list.len() as usize);
+    let mut symbol_runs: Vec<usize> = Vec::new();
+    let mut current_symbol: Option<Rc<String>> = None;
+
+    for a in atoms_list.iter() {
+        let sym = a.get_symbol()?.to_str()?;
+        let symbol = match &current_symbol {
+            Some(s) if s.as_str() == sym => {
+                *symbol_runs.last_mut().expect("run already started") += 1;
+                Rc::clone(s)
+            }
+            _ => {
+                symbol_runs.push(1);
+                let s = Rc::new(sym.to_string());
+                current_symbol = Some(Rc::clone(&s));
+                s
+            }
+        };
+
+        let has_vel = a.get_has_velocity();
+        atom_data.push(AtomDatum {
+            symbol,
+            x: a.get_x(),
+            y: a.get_y(),
+            z: a.get_z(),
+            is_fixed: a.get_is_fixed(),
+            atom_id: a.get_atom_id(),
+            vx: has_vel.then(|| a.get_vx()),
+            vy: has_vel.then(|| a.get_vy()),
+            vz: has_vel.then(|| a.get_vz()),
+            extra_columns: Vec::new(),
+        });
+    }
+
+    let natms_per_type: Vec<usize> = if fd.has_natms_per_type() {
+        let counts: Vec<usize> = fd
+            .get_natms_per_type()?
+            .iter()
+            .map(|n| n as usize)
+            .collect();
+        let total: usize = counts.iter().sum();
+        if total != atom_data.len() {
+            return Err(capnp::Error::failed(format!(
+                "natmsPerType adds up to {total} atoms, but {} were sent",
+                atom_data.len()
+            )));
+        }
+        counts
+    } else {
+        symbol_runs
+    };
+
+    let mut masses_per_type = Vec::with_capacity(natms_per_type.len());
+    let mut first_atom = 0;
+    for (type_index, &count) in natms_per_type.iter().enumerate() {
+        let type_index = type_index as u32;
+        let mass = if type_index < masses.len() {
+            masses.get(type_index)
+        } else if count > 0 {
+            helpers::standard_atomic_mass(atom_data[first_atom].symbol_str()).unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        masses_per_type.push(mass);
+        first_atom += count;
+    }
+
+    let header = FrameHeader {
+        prebox_header,
+        boxl,
+        angles,
+        postbox_header,
+        natm_types: natms_per_type.len(),
+        natms_per_type,
+        masses_per_type,
+    };
+    Ok(ConFrame { header, atom_data })
+}
+
+fn read_triple(list: primitive_list::Reader<'_, f64>, field: &str) -> capnp::Result<[f64; 3]> {
+    if list.len() != 3 {
+        return Err(capnp::Error::failed(format!(
+            "{field} must have 3 entries, got {}",
+            list.len()
+        )));
+    }
+    Ok([list.get(0), list.get(1), list.get(2)])
+}
+
+fn read_header_lines(list: text_list::Reader<'_>, field: &str) -> capnp::Result<[String; 2]> {
+    if list.len() != 2 {
+        return Err(capnp::Error::failed(format!(
+            "{field} must have 2 lines, got {}",
+            list.len()
+        )));
+    }
+    Ok([list.get(0)?.to_string()?, list.get(1)?.to_string()?])
+}
@@ -1,11 +1,20 @@
+use std::collections::VecDeque;
+
 use capnp::capability::Promise;
-use capnp_rpc::{RpcSystem, twoparty, rpc_twoparty_capnp};
+use capnp_rpc::{RpcSystem, pry, rpc_twoparty_capnp, twoparty};
 
 use crate::iterators::ConFrameIterator;
 use crate::writer::ConFrameWriter;
 
+use super::convert::{read_frame_data, write_frame_data};
 use super::read_con_capnp::read_con_service;
 
+/// Frames per `pushFrames` call when the client does not pick a chunk size.
+const DEFAULT_STREAM_CHUNK_SIZE: usize = 64;
+
+/// Upper bound on frames sent to a streaming client but not yet acknowledged.
+const MAX_IN_FLIGHT_FRAMES: usize = 1024;
+
 struct ReadConServiceImpl;
 
 impl read_con_service::Server for ReadConServiceImpl {
@@ -22,52 +31,15 @@ impl read_con_service::Server for ReadConServiceImpl {
         };
 
         let iter = ConFrameIterator::new(file_str);
-        let frames: Vec<_> = iter.filter_map(|r| r.ok()).collect();
+        let frames: Vec<_> = match iter.collect::<Result<_, _>>() {
+            Ok(frames) => frames,
+            Err(e) => return Promise::err(capnp::Error::failed(e.to_string())),
+        };
 
         let mut result_builder = results.get().init_result();
         let mut frames_builder = result_builder.reborrow().init_frames(frames.len() as u32);
-
         for (i, frame) in frames.iter().enumerate() {
-            let mut fb = frames_builder.reborrow().get(i as u32);
-
-            // Cell
-            let mut cell = fb.reborrow().init_cell(3);
-            for (j, &v) in frame.header.boxl.iter().enumerate() {
-                cell.set(j as u32, v);
-            }
-
-            // Angles
-            let mut angles = fb.reborrow().init_angles(3);
-            for (j, &v) in frame.header.angles.iter().enumerate() {
-                angles.set(j as u32, v);
-            }
-
-            // Headers
-            let mut prebox = fb.reborrow().init_prebox_header(2);
-            prebox.set(0, &frame.header.prebox_header[0]);
-            prebox.set(1, &frame.header.prebox_header[1]);
-
-            let mut postbox = fb.reborrow().init_postbox_header(2);
-            postbox.set(0, &frame.header.postbox_header[0]);
-            postbox.set(1, &frame.header.postbox_header[1]);
-
-            fb.set_has_velocities(frame.has_velocities());
-
-            // Atoms
-            let mut atoms_builder = fb.reborrow().init_atoms(frame.atom_data.len() as u32);
-            for (k, atom) in frame.atom_data.iter().enumerate() {
-                let mut ab = atoms_builder.reborrow().get(k as u32);
-                ab.set_symbol(&atom.symbol);
-                ab.set_x(atom.x);
-                ab.set_y(atom.y);
-                ab.set_z(atom.z);
-                ab.set_is_fixed(atom.is_fixed);
-                ab.set_atom_id(atom.atom_id);
-                ab.set_vx(atom.vx.unwrap_or(0.0));
-                ab.set_vy(atom.vy.unwrap_or(0.0));
-                ab.set_vz(atom.vz.unwrap_or(0.0));
-                ab.set_has_velocity(atom.has_velocity());
-            }
+            write_frame_data(frames_builder.reborrow().get(i as u32), frame);
         }
 
         Promise::ok(())
@@ -78,91 +50,12 @@ impl read_con_service::Server for ReadConServiceImpl {
         params: read_con_service::WriteFramesParams,
         mut results: read_con_service::WriteFramesResults,
     ) -> Promise<(), capnp::Error> {
-        use crate::types::{AtomDatum, ConFrame, FrameHeader};
-        use std::rc::Rc;
-
         let req = pry!(params.get());
         let frame_data_list = pry!(pry!(req.get_req()).get_frames());
 
-        let mut frames = Vec::new();
-        for i in 0..frame_data_list.len() {
-            let fd = pry!(frame_data_list.get(i));
-
-            let cell_list = pry!(fd.get_cell());
-            let angles_list = pry!(fd.get_angles());
-            let prebox_list = pry!(fd.get_prebox_header());
-            let postbox_list = pry!(fd.get_postbox_header());
-            let atoms_list = pry!(fd.get_atoms());
-
-            let boxl = [
-                cell_list.get(0),
-                cell_list.get(1),
-                cell_list.get(2),
-            ];
-            let angles = [
-                angles_list.get(0),
-                angles_list.get(1),
-                angles_list.get(2),
-            ];
-
-            let prebox_header = [
-                pry!(prebox_list.get(0)).to_string(),
-                pry!(prebox_list.get(1)).to_string(),
-            ];
-            let postbox_header = [
-                pry!(postbox_list.get(0)).to_string(),
-                pry!(postbox_list.get(1)).to_string(),
-            ];
-
-            // Reconstruct atom data
-            let mut atom_data = Vec::with_capacity(atoms_list.len() as usize);
-            let mut natms_per_type: Vec<usize> = Vec::new();
-            let mut masses_per_type: Vec<f64> = Vec::new();
-            let mut current_symbol = String::new();
-            let mut current_count: usize = 0;
-
-            for j in 0..atoms_list.len() {
-                let a = pry!(atoms_list.get(j));
-                let sym = pry!(a.get_symbol()).to_string();
-
-                if sym != current_symbol {
-                    if current_count > 0 {
-                        natms_per_type.push(current_count);
-                    }
-                    current_symbol = sym.clone();
-                    current_count = 0;
-                    masses_per_type.push(0.0); // mass not in schema atoms
-                }
-                current_count += 1;
-
-                let has_vel = a.get_has_velocity();
-                atom_data.push(AtomDatum {
-                    symbol: Rc::new(sym),
-                    x: a.get_x(),
-                    y: a.get_y(),
-                    z: a.get_z(),
-                    is_fixed: a.get_is_fixed(),
-                    atom_id: a.get_atom_id(),
-                    vx: if has_vel { Some(a.get_vx()) } else { None },
-                    vy: if has_vel { Some(a.get_vy()) } else { None },
-                    vz: if has_vel { Some(a.get_vz()) } else { None },
-                });
-            }
-            if current_count > 0 {
-                natms_per_type.push(current_count);
-            }
-
-            let header = FrameHeader {
-                prebox_header,
-                boxl,
-                angles,
-                postbox_header,
-                natm_types: natms_per_type.len(),
-                natms_per_type,
-                masses_per_type,
-            };
-
-            frames.push(ConFrame { header, atom_data });
+        let mut frames = Vec::with_capacity(frame_data_list.len() as usize);
+        for fd in frame_data_list.iter() {
+            frames.push(pry!(read_frame_data(fd)));
         }
 
         let mut buffer: Vec<u8> = Vec::new();
@@ -173,22 +66,100 @@ impl read_con_service::Server for ReadConServiceImpl {
             }
         }
 
-        results
-            .get()
-            .init_result()
-            .set_file_contents(&buffer);
+        results.get().init_result().set_file_contents(&buffer);
 
         Promise::ok(())
     }
+
+    fn parse_frames_streaming(
+        &mut self,
+        params: read_con_service::ParseFramesStreamingParams,
+        _results: read_con_service::ParseFramesStreamingResults,
+    ) -> Promise<(), capnp::Error> {
+        let params = pry!(params.get());
+        let file_bytes = pry!(pry!(params.get_req()).get_file_contents()).to_vec();
+        let sink = pry!(params.get_sink());
+        // The chunk size comes from the client, so cap it at the in-flight
+        // bound: a huge value would otherwise parse the whole file before
+        // the first push.
+        let chunk_size = match params.get_chunk_size() {
+            0 => DEFAULT_STREAM_CHUNK_SIZE,
+            n => (n as usize).min(MAX_IN_FLIGHT_FRAMES),
+        };
+        let max_in_flight = (MAX_IN_FLIGHT_FRAMES / chunk_size).max(1);
+
+        Promise::from_future(async move {
+            let file_str =
+                String::from_utf8(file_bytes).map_err(|e| capnp::Error::failed(e.to_string()))?;
+            let mut iter = ConFrameIterator::new(&file_str);
+            let mut in_flight = VecDeque::new();
+
+            loop {
+                let mut chunk = Vec::new();
+                let mut parse_error = None;
+                for result in iter.by_ref().take(chunk_size) {
+                    match result {
+                        Ok(frame) => chunk.push(frame),
+                        Err(e) => {
+                            parse_error = Some(e);
+                            break;
+                        }
+                    }
+                }
+                if chunk.is_empty() && parse_error.is_none() {
+                    break;
+                }
+
+                if !chunk.is_empty() {
+                    let mut request = sink.push_frames_request();
+                    let mut frames_builder = request.get().init_frames(chunk.len() as u32);
+                    for (i, frame) in chunk.iter().enumerate() {
+                        write_frame_data(frames_builder.reborrow().get(i as u32), frame);
+                    }
+                    in_flight.push_back(request.send().promise);
+                }
+
+                if let Some(e) = parse_error {
+                    // Deliver the frames before the bad one, then fail.
+                    for pending in in_flight {
+                        pending.await?;
+                    }
+                    return Err(capnp::Error::failed(e.to_string()));
+                }
+
+                // Wait for the client to take the oldest chunk before parsing
+                // more, so at most `max_in_flight` chunks are buffered.
+                if in_flight.len() >= max_in_flight
+                    && let Some(oldest) = in_flight.pop_front()
+                {
+                    oldest.await?;
+                }
+            }
+
+            for pending in in_flight {
+                pending.await?;
+            }
+            sink.done_request().send().promise.await?;
+            Ok(())
+        })
+    }
 }
 
 /// Starts an RPC server on the given address.
 ///
 /// This function blocks until the server is shut down.
 pub async fn start_server(addr: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    let service = read_con_service::ToClient::new(ReadConServiceImpl)
-        .into_client::<capnp_rpc::Server>();
+    serve(tokio::net::TcpListener::bind(addr).await?).await
+}
+
+/// Serves RPC connections accepted on an already bound listener, e.g. one
+/// bound to port 0 to let the OS pick a free port.
+///
+/// Connections are spawned with `spawn_local`, so this must run inside a
+/// `tokio::task::LocalSet`. It returns only if accepting a connection fails.
+pub async fn serve(listener: tokio::net::TcpListener) -> Result<(), Box<dyn std::error::Error>> {
+    let service =
+        read_con_service::ToClient::new(ReadConServiceImpl).into_client::<capnp_rpc::Server>();
 
     loop {
         let (stream, _) = listener.accept().await?;
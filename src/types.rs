@@ -2,10 +2,14 @@
 // Data Structures - The shape of our parsed data
 //=============================================================================
 
+use crate::error::{BuildError, ConsistencyError, FrameError, ValidationIssue};
+use crate::helpers;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 /// Holds all metadata from the 9-line header of a simulation frame.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FrameHeader {
     /// The two text lines preceding the box dimension data.
     pub prebox_header: [String; 2],
@@ -23,11 +27,112 @@ pub struct FrameHeader {
     pub masses_per_type: Vec<f64>,
 }
 
+impl FrameHeader {
+    /// Returns the 3x3 cell matrix (rows are the lattice vectors a, b, c)
+    /// built from `boxl` and `angles`.
+    ///
+    /// See [`crate::helpers::cell_matrix`] for the convention used.
+    pub fn cell_matrix(&self) -> [[f64; 3]; 3] {
+        helpers::cell_matrix(self.boxl, self.angles)
+    }
+
+    /// Total number of atoms across all type blocks.
+    ///
+    /// Saturates at `usize::MAX` rather than overflowing on corrupt counts.
+    pub fn total_atoms(&self) -> usize {
+        self.natms_per_type
+            .iter()
+            .fold(0, |sum, &n| sum.saturating_add(n))
+    }
+
+    /// Index of the first atom of each type block, in type order.
+    ///
+    /// Block `i` spans `offsets[i]..offsets[i] + natms_per_type[i]`.
+    pub fn type_offsets(&self) -> Vec<usize> {
+        self.natms_per_type
+            .iter()
+            .scan(0, |offset, &count| {
+                let start = *offset;
+                *offset += count;
+                Some(start)
+            })
+            .collect()
+    }
+
+    /// Expands `masses_per_type` into one mass per atom, in atom order.
+    ///
+    /// Types without a mass entry get `0.0`.
+    pub fn per_atom_masses(&self) -> Vec<f64> {
+        self.natms_per_type
+            .iter()
+            .enumerate()
+            .flat_map(|(type_idx, &count)| {
+                let mass = self.masses_per_type.get(type_idx).copied().unwrap_or(0.0);
+                std::iter::repeat_n(mass, count)
+            })
+            .collect()
+    }
+
+    /// Returns `true` if at least one atom type has a nonzero mass.
+    pub fn has_masses(&self) -> bool {
+        self.masses_per_type.iter().any(|&m| m != 0.0)
+    }
+
+    /// Returns the inverse of [`FrameHeader::cell_matrix`], used to convert
+    /// Cartesian coordinates to fractional ones (`frac = cart * inv`).
+    ///
+    /// Returns `None` for a degenerate (zero-volume) cell.
+    pub fn cell_matrix_inverse(&self) -> Option<[[f64; 3]; 3]> {
+        if self.angles == [90.0, 90.0, 90.0] && self.boxl.iter().all(|&l| l != 0.0) {
+            let [a, b, c] = self.boxl;
+            return Some([
+                [1.0 / a, 0.0, 0.0],
+                [0.0, 1.0 / b, 0.0],
+                [0.0, 0.0, 1.0 / c],
+            ]);
+        }
+        helpers::invert_3x3(&self.cell_matrix())
+    }
+
+    /// Simulation time recorded in the second prebox line, read from the
+    /// number next to a `Time` token (`Time 1234.5`, or `0.0000 TIME` as eOn
+    /// writes it).
+    ///
+    /// This is a best-effort convention: `None` if the line has no such
+    /// token or no number beside it.
+    pub fn time(&self) -> Option<f64> {
+        labelled_value(&self.prebox_header[1], "time")
+    }
+
+    /// Random number seed recorded in the first prebox line, read from the
+    /// integer next to a `Seed` token (`Random Number Seed 42`).
+    ///
+    /// Like [`FrameHeader::time`], returns `None` if the convention does not
+    /// match.
+    pub fn random_seed(&self) -> Option<u64> {
+        labelled_value(&self.prebox_header[0], "seed")
+    }
+}
+
+/// Parses the token after `keyword` (case-insensitive, optionally followed by
+/// `:` or `=`) in `line`, falling back to the token before it.
+fn labelled_value<T: std::str::FromStr>(line: &str, keyword: &str) -> Option<T> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let at = tokens
+        .iter()
+        .position(|t| t.trim_end_matches([':', '=']).eq_ignore_ascii_case(keyword))?;
+    let after = tokens.get(at + 1).and_then(|t| t.parse().ok());
+    after.or_else(|| at.checked_sub(1).and_then(|i| tokens[i].parse().ok()))
+}
+
 /// Represents the data for a single atom in a frame.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AtomDatum {
     /// The chemical symbol of the atom (e.g., "C", "H", "O").
-    /// Using Rc<String> to avoid expensive clones for each atom of the same type.
+    /// Using `Rc<String>` to avoid expensive clones for each atom of the same type;
+    /// [`AtomDatum::symbol_str`] reads it as a plain `&str`.
+    #[cfg_attr(feature = "serde", serde(with = "rc_string"))]
     pub symbol: Rc<String>,
     /// The Cartesian x-coordinate.
     pub x: f64,
@@ -45,9 +150,17 @@ pub struct AtomDatum {
     pub vy: Option<f64>,
     /// The z-component of velocity (present only in `.convel` files).
     pub vz: Option<f64>,
+    /// Any numeric columns after the standard `x y z fixed atom_id` five
+    /// (e.g. a per-atom charge). Empty for standard files.
+    pub extra_columns: Vec<f64>,
 }
 
 impl AtomDatum {
+    /// Returns the atom's chemical symbol.
+    pub fn symbol_str(&self) -> &str {
+        &self.symbol
+    }
+
     /// Returns `true` if this atom has velocity data.
     pub fn has_velocity(&self) -> bool {
         self.vx.is_some() && self.vy.is_some() && self.vz.is_some()
@@ -58,7 +171,7 @@ impl AtomDatum {
 impl PartialEq for AtomDatum {
     fn eq(&self, other: &Self) -> bool {
         // Compare the string values, not the pointers.
-        *self.symbol == *other.symbol
+        self.symbol_str() == other.symbol_str()
             && self.x == other.x
             && self.y == other.y
             && self.z == other.z
@@ -67,11 +180,119 @@ impl PartialEq for AtomDatum {
             && self.vx == other.vx
             && self.vy == other.vy
             && self.vz == other.vz
+            && self.extra_columns == other.extra_columns
+    }
+}
+
+/// (De)serializes the shared `Rc<String>` symbol as a plain string.
+#[cfg(feature = "serde")]
+mod rc_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::rc::Rc;
+
+    pub fn serialize<S: Serializer>(symbol: &Rc<String>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(symbol)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rc<String>, D::Error> {
+        String::deserialize(deserializer).map(Rc::new)
+    }
+}
+
+/// The JSON interchange layout of [`crate::writer::write_json`] and
+/// [`crate::parser::parse_json`]. Unlike the derived impls on [`ConFrame`],
+/// it lists each atom with its mass and leaves out the type bookkeeping.
+#[cfg(feature = "serde")]
+pub(crate) mod json {
+    use super::{BuilderAtom, ConFrame, ConFrameBuilder};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    pub(crate) struct Frame {
+        cell: [f64; 3],
+        angles: [f64; 3],
+        prebox_header: [String; 2],
+        postbox_header: [String; 2],
+        atoms: Vec<Atom>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Atom {
+        symbol: String,
+        id: u64,
+        pos: [f64; 3],
+        fixed: bool,
+        mass: f64,
+        /// Missing components are `null`, so partial velocities survive.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        vel: Option<[Option<f64>; 3]>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        extra: Vec<f64>,
+    }
+
+    impl From<&ConFrame> for Frame {
+        fn from(frame: &ConFrame) -> Self {
+            let header = &frame.header;
+            // Every atom is written, even if the type counts or masses in the
+            // header fall short; atoms without a mass get 0.0.
+            let masses = header.per_atom_masses();
+            let atoms = frame
+                .atom_data
+                .iter()
+                .enumerate()
+                .map(|(i, atom)| {
+                    let vel = [atom.vx, atom.vy, atom.vz];
+                    Atom {
+                        symbol: atom.symbol_str().to_string(),
+                        id: atom.atom_id,
+                        pos: [atom.x, atom.y, atom.z],
+                        fixed: atom.is_fixed,
+                        mass: masses.get(i).copied().unwrap_or(0.0),
+                        vel: vel.iter().any(Option::is_some).then_some(vel),
+                        extra: atom.extra_columns.clone(),
+                    }
+                })
+                .collect();
+            Frame {
+                cell: header.boxl,
+                angles: header.angles,
+                prebox_header: header.prebox_header.clone(),
+                postbox_header: header.postbox_header.clone(),
+                atoms,
+            }
+        }
+    }
+
+    impl From<Frame> for ConFrame {
+        fn from(frame: Frame) -> Self {
+            let mut builder = ConFrameBuilder::new(frame.cell, frame.angles)
+                .prebox_header(frame.prebox_header)
+                .postbox_header(frame.postbox_header);
+            for a in frame.atoms {
+                let [x, y, z] = a.pos;
+                let [vx, vy, vz] = a.vel.unwrap_or_default();
+                builder.atoms.push(BuilderAtom {
+                    symbol: a.symbol,
+                    x,
+                    y,
+                    z,
+                    is_fixed: a.fixed,
+                    atom_id: a.id,
+                    mass: a.mass,
+                    vx,
+                    vy,
+                    vz,
+                    extra_columns: a.extra,
+                });
+            }
+            builder.build()
+        }
     }
 }
 
 /// Represents a single, complete simulation frame, including header and all atomic data.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConFrame {
     /// The `FrameHeader` containing the frame's metadata.
     pub header: FrameHeader,
@@ -80,233 +301,1945 @@ pub struct ConFrame {
 }
 
 impl ConFrame {
-    /// Returns `true` if any atom in this frame has velocity data.
-    pub fn has_velocities(&self) -> bool {
-        self.atom_data.first().is_some_and(|a| a.has_velocity())
+    /// Returns the cell lengths `[a, b, c]` in file order (`header.boxl`).
+    pub fn cell_lengths(&self) -> [f64; 3] {
+        self.header.boxl
     }
-}
 
-// Manual implementation of PartialEq because of the change to AtomDatum.
-impl PartialEq for ConFrame {
-    fn eq(&self, other: &Self) -> bool {
-        self.header == other.header && self.atom_data == other.atom_data
+    /// Returns the cell angles `[alpha, beta, gamma]` in degrees, in file
+    /// order (`header.angles`).
+    ///
+    /// `alpha` is the angle between b and c, `beta` between a and c, and
+    /// `gamma` between a and b.
+    pub fn cell_angles(&self) -> [f64; 3] {
+        self.header.angles
     }
-}
-
-/// A builder for constructing `ConFrame` objects from in-memory data.
-///
-/// Atoms are accumulated and grouped by symbol on `build()` to compute the
-/// header fields (`natm_types`, `natms_per_type`, `masses_per_type`).
-///
-/// # Example
-///
-/// ```
-/// use readcon_core::types::ConFrameBuilder;
-///
-/// let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
-/// builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
-/// builder.add_atom("H", 1.0, 2.0, 3.0, false, 1, 1.008);
-/// let frame = builder.build();
-/// assert_eq!(frame.header.natm_types, 2);
-/// assert_eq!(frame.atom_data.len(), 2);
-/// ```
-pub struct ConFrameBuilder {
-    prebox_header: [String; 2],
-    cell: [f64; 3],
-    angles: [f64; 3],
-    postbox_header: [String; 2],
-    atoms: Vec<BuilderAtom>,
-}
 
-struct BuilderAtom {
-    symbol: String,
-    x: f64,
-    y: f64,
-    z: f64,
-    is_fixed: bool,
-    atom_id: u64,
-    mass: f64,
-    vx: Option<f64>,
-    vy: Option<f64>,
-    vz: Option<f64>,
-}
+    /// Returns the reciprocal lattice vectors as rows, without the `2π`
+    /// factor, so that `a_i · b_j == δ_ij` for cell rows `a_i` (the
+    /// transpose of [`FrameHeader::cell_matrix_inverse`]).
+    ///
+    /// Returns `None` for a degenerate (zero-volume) cell.
+    pub fn reciprocal_cell(&self) -> Option<[[f64; 3]; 3]> {
+        let inv = self.header.cell_matrix_inverse()?;
+        Some(std::array::from_fn(|i| std::array::from_fn(|j| inv[j][i])))
+    }
 
-impl ConFrameBuilder {
-    /// Creates a new builder with the given cell dimensions and angles.
-    pub fn new(cell: [f64; 3], angles: [f64; 3]) -> Self {
-        Self {
-            prebox_header: [String::new(), String::new()],
-            cell,
-            angles,
-            postbox_header: [String::new(), String::new()],
-            atoms: Vec::new(),
-        }
+    /// Returns `true` if any atom in this frame has velocity data.
+    pub fn has_velocities(&self) -> bool {
+        self.atom_data.first().is_some_and(|a| a.has_velocity())
     }
 
-    /// Sets the two pre-box header lines.
-    pub fn prebox_header(mut self, h: [String; 2]) -> Self {
-        self.prebox_header = h;
-        self
+    /// Returns the chemical symbol of every atom, in atom order.
+    pub fn symbols(&self) -> Vec<&str> {
+        self.atom_data.iter().map(AtomDatum::symbol_str).collect()
     }
 
-    /// Sets the two post-box header lines.
-    pub fn postbox_header(mut self, h: [String; 2]) -> Self {
-        self.postbox_header = h;
-        self
+    /// Iterates over the atoms one type block at a time, yielding each block's
+    /// symbol together with its contiguous slice of atoms.
+    ///
+    /// Block boundaries come from `header.natms_per_type`; blocks with no atoms
+    /// are skipped.
+    pub fn atoms_by_type(&self) -> impl Iterator<Item = (&str, &[AtomDatum])> {
+        let n = self.atom_data.len();
+        let offsets = self.header.type_offsets();
+        offsets
+            .into_iter()
+            .zip(&self.header.natms_per_type)
+            .filter_map(move |(start, &count)| {
+                let block = &self.atom_data[start.min(n)..(start + count).min(n)];
+                block.first().map(|atom| (atom.symbol_str(), block))
+            })
     }
 
-    /// Adds an atom without velocity data.
-    pub fn add_atom(
-        &mut self,
-        symbol: &str,
-        x: f64,
-        y: f64,
-        z: f64,
-        is_fixed: bool,
-        atom_id: u64,
-        mass: f64,
-    ) {
-        self.atoms.push(BuilderAtom {
-            symbol: symbol.to_string(),
-            x,
-            y,
-            z,
-            is_fixed,
-            atom_id,
-            mass,
-            vx: None,
-            vy: None,
-            vz: None,
-        });
+    /// Returns the unweighted mean position of all atoms.
+    ///
+    /// Coordinates are used as stored, without periodic unwrapping. Returns the
+    /// origin for a frame without atoms.
+    pub fn geometric_center(&self) -> [f64; 3] {
+        if self.atom_data.is_empty() {
+            return [0.0; 3];
+        }
+        let mut sum = [0.0; 3];
+        for atom in &self.atom_data {
+            sum[0] += atom.x;
+            sum[1] += atom.y;
+            sum[2] += atom.z;
+        }
+        let n = self.atom_data.len() as f64;
+        [sum[0] / n, sum[1] / n, sum[2] / n]
     }
 
-    /// Adds an atom with velocity data (for .convel output).
-    pub fn add_atom_with_velocity(
-        &mut self,
-        symbol: &str,
-        x: f64,
-        y: f64,
-        z: f64,
-        is_fixed: bool,
-        atom_id: u64,
-        mass: f64,
-        vx: f64,
-        vy: f64,
-        vz: f64,
-    ) {
-        self.atoms.push(BuilderAtom {
-            symbol: symbol.to_string(),
-            x,
-            y,
-            z,
-            is_fixed,
-            atom_id,
-            mass,
-            vx: Some(vx),
-            vy: Some(vy),
-            vz: Some(vz),
-        });
+    /// Checks the frame's internal consistency and returns every problem found.
+    ///
+    /// Checks that the header's per-type vectors agree with `natm_types` and
+    /// with the number of atoms, that each type block holds a single symbol,
+    /// that velocities are present on all atoms or none, and that the cell and
+    /// atom values are finite. An empty result means the frame is consistent.
+    pub fn validation_issues(&self) -> Vec<ValidationIssue> {
+        issues_in(&self.header, self.atom_data.len(), |i| {
+            AtomView::of(&self.atom_data[i])
+        })
     }
 
-    /// Consumes the builder and produces a `ConFrame`.
+    /// Checks the frame's internal consistency, as
+    /// [`ConFrame::validation_issues`] does, for use before writing a frame
+    /// assembled by hand or rebuilt from RPC data.
     ///
-    /// Atoms are grouped by symbol (in encounter order) to compute
-    /// `natm_types`, `natms_per_type`, and `masses_per_type`.
-    pub fn build(self) -> ConFrame {
-        // Group atoms by symbol in encounter order
-        let mut type_order: Vec<String> = Vec::new();
-        let mut type_counts: Vec<usize> = Vec::new();
-        let mut type_masses: Vec<f64> = Vec::new();
+    /// Repeated blocks of one symbol are allowed, since
+    /// [`ConFrameBuilder::build_preserving_order`] produces them and the
+    /// format can represent them; each block must hold a single symbol.
+    ///
+    /// # Errors
+    ///
+    /// * `ConsistencyError` listing every issue found.
+    pub fn validate(&self) -> Result<(), ConsistencyError> {
+        let issues = self.validation_issues();
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(ConsistencyError { issues })
+        }
+    }
 
-        for atom in &self.atoms {
-            if let Some(idx) = type_order.iter().position(|s| s == &atom.symbol) {
-                type_counts[idx] += 1;
-            } else {
-                type_order.push(atom.symbol.clone());
-                type_counts.push(1);
-                type_masses.push(atom.mass);
-            }
+    /// Returns the mass-weighted mean position of all atoms, using the
+    /// per-type masses from the header.
+    ///
+    /// If every mass is zero (e.g. a frame built without mass information) this
+    /// falls back to [`ConFrame::geometric_center`]; use
+    /// [`FrameHeader::has_masses`] to tell the two cases apart.
+    pub fn center_of_mass(&self) -> [f64; 3] {
+        let masses = self.header.per_atom_masses();
+        let mut sum = [0.0; 3];
+        let mut total = 0.0;
+        for (atom, &m) in self.atom_data.iter().zip(&masses) {
+            sum[0] += m * atom.x;
+            sum[1] += m * atom.y;
+            sum[2] += m * atom.z;
+            total += m;
+        }
+        if total == 0.0 {
+            return self.geometric_center();
         }
+        [sum[0] / total, sum[1] / total, sum[2] / total]
+    }
 
-        // Sort atoms by type order (group same symbols together)
-        let mut sorted_atoms: Vec<&BuilderAtom> = Vec::with_capacity(self.atoms.len());
-        for symbol in &type_order {
-            for atom in &self.atoms {
-                if &atom.symbol == symbol {
-                    sorted_atoms.push(atom);
-                }
+    /// Returns the axis-aligned bounding box `(min, max)` of all atom positions.
+    ///
+    /// Coordinates are used as stored, without periodic wrapping. Returns
+    /// zeros for both corners for a frame without atoms.
+    pub fn bounding_box(&self) -> ([f64; 3], [f64; 3]) {
+        if self.atom_data.is_empty() {
+            return ([0.0; 3], [0.0; 3]);
+        }
+        let mut lo = [f64::INFINITY; 3];
+        let mut hi = [f64::NEG_INFINITY; 3];
+        for atom in &self.atom_data {
+            for (k, v) in [atom.x, atom.y, atom.z].into_iter().enumerate() {
+                lo[k] = lo[k].min(v);
+                hi[k] = hi[k].max(v);
             }
         }
+        (lo, hi)
+    }
+
+    /// Returns the per-axis span of [`ConFrame::bounding_box`].
+    pub fn extent(&self) -> [f64; 3] {
+        let (lo, hi) = self.bounding_box();
+        [hi[0] - lo[0], hi[1] - lo[1], hi[2] - lo[2]]
+    }
 
-        let atom_data: Vec<AtomDatum> = sorted_atoms
+    /// Returns a sphere `(center, radius)` enclosing every atom.
+    ///
+    /// The center is the [`ConFrame::geometric_center`] and the radius is the
+    /// distance to the farthest atom, so the sphere is enclosing but not
+    /// necessarily minimal. Periodic images are ignored.
+    pub fn bounding_sphere(&self) -> ([f64; 3], f64) {
+        let center = self.geometric_center();
+        let radius_sq = self
+            .atom_data
             .iter()
             .map(|a| {
-                let symbol = Rc::new(a.symbol.clone());
-                AtomDatum {
-                    symbol,
-                    x: a.x,
-                    y: a.y,
-                    z: a.z,
-                    is_fixed: a.is_fixed,
-                    atom_id: a.atom_id,
-                    vx: a.vx,
-                    vy: a.vy,
-                    vz: a.vz,
-                }
+                let dx = a.x - center[0];
+                let dy = a.y - center[1];
+                let dz = a.z - center[2];
+                dx * dx + dy * dy + dz * dz
             })
-            .collect();
-
-        let header = FrameHeader {
-            prebox_header: self.prebox_header,
-            boxl: self.cell,
-            angles: self.angles,
-            postbox_header: self.postbox_header,
-            natm_types: type_order.len(),
-            natms_per_type: type_counts,
-            masses_per_type: type_masses,
-        };
-
-        ConFrame { header, atom_data }
+            .fold(0.0, f64::max);
+        (center, radius_sq.sqrt())
     }
-}
 
-impl ConFrame {
-    /// Creates a new builder for constructing a `ConFrame`.
-    pub fn builder(cell: [f64; 3], angles: [f64; 3]) -> ConFrameBuilder {
-        ConFrameBuilder::new(cell, angles)
+    /// Returns the fractional coordinates of every atom, in atom order.
+    ///
+    /// # Errors
+    ///
+    /// * `FrameError::DegenerateCell` if the cell has zero volume.
+    pub fn to_fractional(&self) -> Result<Vec<[f64; 3]>, FrameError> {
+        let inv = self
+            .header
+            .cell_matrix_inverse()
+            .ok_or(FrameError::DegenerateCell)?;
+        Ok(self
+            .atom_data
+            .iter()
+            .map(|a| helpers::row_times_matrix([a.x, a.y, a.z], &inv))
+            .collect())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_builder_basic() {
-        let mut builder = ConFrameBuilder::new([10.0, 20.0, 30.0], [90.0, 90.0, 90.0]);
-        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
-        builder.add_atom("Cu", 1.0, 0.0, 0.0, true, 1, 63.546);
-        builder.add_atom("H", 2.0, 3.0, 4.0, false, 2, 1.008);
-        let frame = builder.build();
-
-        assert_eq!(frame.header.natm_types, 2);
-        assert_eq!(frame.header.natms_per_type, vec![2, 1]);
-        assert_eq!(frame.header.masses_per_type, vec![63.546, 1.008]);
-        assert_eq!(frame.atom_data.len(), 3);
-        assert_eq!(&*frame.atom_data[0].symbol, "Cu");
-        assert_eq!(&*frame.atom_data[2].symbol, "H");
+    /// Sets every atom's Cartesian position from fractional coordinates.
+    ///
+    /// # Errors
+    ///
+    /// * `FrameError::AtomCountMismatch` if `fracs` does not hold one entry per atom.
+    pub fn from_fractional(&mut self, fracs: &[[f64; 3]]) -> Result<(), FrameError> {
+        if fracs.len() != self.atom_data.len() {
+            return Err(FrameError::AtomCountMismatch {
+                expected: self.atom_data.len(),
+                found: fracs.len(),
+            });
+        }
+        let cell = self.header.cell_matrix();
+        for (atom, &frac) in self.atom_data.iter_mut().zip(fracs) {
+            let [x, y, z] = helpers::row_times_matrix(frac, &cell);
+            atom.x = x;
+            atom.y = y;
+            atom.z = z;
+        }
+        Ok(())
     }
 
-    #[test]
-    fn test_builder_with_velocities() {
-        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
-        builder.add_atom_with_velocity("Cu", 0.0, 0.0, 0.0, true, 0, 63.546, 0.1, 0.2, 0.3);
-        let frame = builder.build();
+    /// Lists atom pairs `(i, j, distance)` with `i < j` that are closer than
+    /// `min_dist` under the minimum-image convention.
+    ///
+    /// Useful as a pre-flight check for overlapping atoms. A degenerate cell
+    /// (e.g. zero box lengths) is treated as non-periodic.
+    pub fn close_contacts(&self, min_dist: f64) -> Vec<(usize, usize, f64)> {
+        self.neighbor_list(min_dist)
+    }
 
-        assert!(frame.has_velocities());
-        assert_eq!(frame.atom_data[0].vx, Some(0.1));
-        assert_eq!(frame.atom_data[0].vy, Some(0.2));
-        assert_eq!(frame.atom_data[0].vz, Some(0.3));
+    /// Lists atom pairs `(i, j, distance)` with `i < j` that are closer than
+    /// `cutoff` under the minimum-image convention, sorted by `(i, j)`.
+    ///
+    /// Atoms are binned on a grid over the fractional coordinates, so only
+    /// pairs in neighbouring bins are measured and large frames take roughly
+    /// linear time. Each pair is reported once, at the distance given by
+    /// [`ConFrame::distance_mic`]. A degenerate cell is treated as
+    /// non-periodic and searched pair by pair.
+    pub fn neighbor_list(&self, cutoff: f64) -> Vec<(usize, usize, f64)> {
+        self.pairs_within(cutoff, |_, _, dist| dist < cutoff)
+    }
+
+    /// Lists bonded atom pairs `(i, j, distance)` with `i < j`, sorted by
+    /// `(i, j)`.
+    ///
+    /// Two atoms are bonded when their minimum-image distance is below the sum
+    /// of their covalent radii (see [`helpers::element_info`]) times `scale`;
+    /// a `scale` of about 1.2 is a common choice. Atoms whose symbol is not in
+    /// the element table have no bonds.
+    pub fn bonds(&self, scale: f64) -> Vec<(usize, usize, f64)> {
+        let radii: Vec<Option<f64>> = self
+            .atom_data
+            .iter()
+            .map(|a| helpers::element_info(a.symbol_str()).map(|e| e.covalent_radius))
+            .collect();
+        let max_radius = radii.iter().flatten().fold(0.0, |max: f64, &r| max.max(r));
+        self.pairs_within(2.0 * max_radius * scale, |i, j, dist| {
+            match (radii[i], radii[j]) {
+                (Some(ri), Some(rj)) => dist < (ri + rj) * scale,
+                _ => false,
+            }
+        })
+    }
+
+    /// Collects the pairs accepted by `accept` among those whose
+    /// minimum-image distance is below `cutoff`, sorted by `(i, j)`.
+    fn pairs_within(
+        &self,
+        cutoff: f64,
+        accept: impl Fn(usize, usize, f64) -> bool,
+    ) -> Vec<(usize, usize, f64)> {
+        let mic = MinimumImage::new(&self.header);
+        let positions: Vec<[f64; 3]> = self.atom_data.iter().map(|a| [a.x, a.y, a.z]).collect();
+        let mut pairs = Vec::new();
+        if cutoff.is_nan() || cutoff <= 0.0 {
+            return pairs;
+        }
+        let mut check = |i: usize, j: usize| {
+            let dist = norm(mic.delta(positions[i], positions[j]));
+            if dist < cutoff && accept(i, j, dist) {
+                pairs.push((i, j, dist));
+            }
+        };
+        match &mic.inverse {
+            Some(inverse) => {
+                let cells = CellList::new(&positions, inverse, cutoff);
+                for i in 0..positions.len() {
+                    for j in cells.candidates(i) {
+                        if j > i {
+                            check(i, j);
+                        }
+                    }
+                }
+            }
+            None => {
+                for i in 0..positions.len() {
+                    for j in i + 1..positions.len() {
+                        check(i, j);
+                    }
+                }
+            }
+        }
+        pairs.sort_unstable_by_key(|&(i, j, _)| (i, j));
+        pairs
+    }
+
+    /// Euclidean distance between atoms `i` and `j`, ignoring periodicity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is not a valid atom index.
+    pub fn distance(&self, i: usize, j: usize) -> f64 {
+        let (pi, pj) = self.position_pair(i, j);
+        norm([pj[0] - pi[0], pj[1] - pi[1], pj[2] - pi[2]])
+    }
+
+    /// Distance between atoms `i` and `j` under the minimum-image convention.
+    ///
+    /// The separation is wrapped in fractional coordinates, so triclinic cells
+    /// are handled as well as orthorhombic ones. A degenerate cell is treated
+    /// as non-periodic, giving the same result as [`ConFrame::distance`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is not a valid atom index.
+    pub fn distance_mic(&self, i: usize, j: usize) -> f64 {
+        let (pi, pj) = self.position_pair(i, j);
+        norm(MinimumImage::new(&self.header).delta(pi, pj))
+    }
+
+    /// Tiles the frame `nx * ny * nz` times along its lattice vectors.
+    ///
+    /// Each replica is shifted by `i*a + j*b + k*c`, with `a`, `b` and `c` the
+    /// rows of [`FrameHeader::cell_matrix`], so triclinic cells tile correctly.
+    /// The cell lengths are multiplied by the factors and the angles kept.
+    /// Atoms stay grouped by type, with the replicas of each type block
+    /// following one another, so every `natms_per_type` entry is multiplied by
+    /// the number of replicas. Velocities, fixed flags and extra columns are
+    /// copied to every replica, and atom IDs are renumbered from 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any factor is zero.
+    pub fn supercell(&self, nx: usize, ny: usize, nz: usize) -> ConFrame {
+        assert!(
+            nx > 0 && ny > 0 && nz > 0,
+            "supercell factors must be positive, got {nx}x{ny}x{nz}"
+        );
+        let [a, b, c] = self.header.cell_matrix();
+        let mut shifts = Vec::with_capacity(nx * ny * nz);
+        for i in 0..nx {
+            for j in 0..ny {
+                for k in 0..nz {
+                    let (i, j, k) = (i as f64, j as f64, k as f64);
+                    shifts.push([
+                        i * a[0] + j * b[0] + k * c[0],
+                        i * a[1] + j * b[1] + k * c[1],
+                        i * a[2] + j * b[2] + k * c[2],
+                    ]);
+                }
+            }
+        }
+
+        let mut atom_data = Vec::with_capacity(self.atom_data.len() * shifts.len());
+        for (_, block) in self.atoms_by_type() {
+            for shift in &shifts {
+                for atom in block {
+                    let mut replica = atom.clone();
+                    replica.x += shift[0];
+                    replica.y += shift[1];
+                    replica.z += shift[2];
+                    replica.atom_id = atom_data.len() as u64;
+                    atom_data.push(replica);
+                }
+            }
+        }
+
+        let mut header = self.header.clone();
+        let [lx, ly, lz] = header.boxl;
+        header.boxl = [lx * nx as f64, ly * ny as f64, lz * nz as f64];
+        for count in &mut header.natms_per_type {
+            *count *= shifts.len();
+        }
+        ConFrame { header, atom_data }
+    }
+
+    fn position_pair(&self, i: usize, j: usize) -> ([f64; 3], [f64; 3]) {
+        let n = self.atom_data.len();
+        let position = |k: usize| {
+            let a = self
+                .atom_data
+                .get(k)
+                .unwrap_or_else(|| panic!("atom index {k} out of range for {n} atoms"));
+            [a.x, a.y, a.z]
+        };
+        (position(i), position(j))
+    }
+
+    /// Wraps every atom back into the primary cell under periodic boundary
+    /// conditions. Equivalent to [`ConFrame::wrap_into_cell_opts`] with
+    /// `WrapOptions::default()`.
+    ///
+    /// # Errors
+    ///
+    /// * `FrameError::DegenerateCell` if the cell has zero volume.
+    pub fn wrap_into_cell(&mut self) -> Result<(), FrameError> {
+        self.wrap_into_cell_opts(&WrapOptions::default())
+    }
+
+    /// Wraps atom positions back into the primary cell under periodic boundary
+    /// conditions.
+    ///
+    /// Orthorhombic cells are wrapped into `[0, L)` per dimension directly;
+    /// triclinic cells are wrapped into `[0, 1)` in fractional coordinates and
+    /// converted back to Cartesian. Velocities are left untouched.
+    ///
+    /// # Errors
+    ///
+    /// * `FrameError::DegenerateCell` if the cell has zero volume.
+    pub fn wrap_into_cell_opts(&mut self, opts: &WrapOptions) -> Result<(), FrameError> {
+        let include = |atom: &AtomDatum| opts.include_fixed || !atom.is_fixed;
+
+        if self.header.angles == [90.0, 90.0, 90.0] {
+            let [lx, ly, lz] = self.header.boxl;
+            if lx * ly * lz == 0.0 {
+                return Err(FrameError::DegenerateCell);
+            }
+            for atom in self.atom_data.iter_mut().filter(|a| include(a)) {
+                atom.x = wrap_periodic(atom.x, lx);
+                atom.y = wrap_periodic(atom.y, ly);
+                atom.z = wrap_periodic(atom.z, lz);
+            }
+            return Ok(());
+        }
+
+        let fracs = self.to_fractional()?;
+        let cell = self.header.cell_matrix();
+        for (atom, frac) in self.atom_data.iter_mut().zip(fracs) {
+            if !include(atom) {
+                continue;
+            }
+            let wrapped = frac.map(|f| wrap_periodic(f, 1.0));
+            let [x, y, z] = helpers::row_times_matrix(wrapped, &cell);
+            atom.x = x;
+            atom.y = y;
+            atom.z = z;
+        }
+        Ok(())
+    }
+
+    /// Sorts atoms by `atom_id` within each type block and returns the
+    /// permutation that was applied.
+    ///
+    /// The returned vector maps each new index to the old index, i.e.
+    /// `perm[new] == old`, so per-atom data kept outside the frame can be
+    /// reordered with `perm.iter().map(|&old| data[old])`. Atoms never move
+    /// between type blocks, so the header stays valid.
+    pub fn sort_by_id_with_remap(&mut self) -> Vec<usize> {
+        let mut perm: Vec<usize> = (0..self.atom_data.len()).collect();
+        let mut offset = 0;
+        for &count in &self.header.natms_per_type {
+            let end = (offset + count).min(perm.len());
+            perm[offset..end].sort_by_key(|&i| self.atom_data[i].atom_id);
+            offset = end;
+        }
+
+        let mut old_atoms: Vec<Option<AtomDatum>> = std::mem::take(&mut self.atom_data)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.atom_data = perm
+            .iter()
+            .map(|&old| {
+                old_atoms[old]
+                    .take()
+                    .expect("permutation indices are unique")
+            })
+            .collect();
+        perm
+    }
+
+    /// Sorts atoms by `atom_id` within each type block, as
+    /// [`ConFrame::sort_by_id_with_remap`] does, without returning the
+    /// permutation.
+    ///
+    /// Type blocks keep their order, so the atoms are in global `atom_id`
+    /// order only if the IDs were already grouped by type.
+    pub fn sort_by_atom_id(&mut self) {
+        self.sort_by_id_with_remap();
+    }
+
+    /// Reorders atoms so that the atom at new index `i` is the one that was
+    /// at `perm[i]`.
+    ///
+    /// The type blocks in the header are re-derived from the new order: each
+    /// run of one symbol becomes a block, carrying that type's mass.
+    ///
+    /// # Errors
+    ///
+    /// * `FrameError::InvalidPermutation` if `perm` is not a permutation of
+    ///   the atom indices.
+    /// * `FrameError::InterleavedTypes` if the new order would split the
+    ///   atoms of one symbol into several blocks.
+    ///
+    /// The frame is left unchanged on error.
+    pub fn reorder(&mut self, perm: &[usize]) -> Result<(), FrameError> {
+        let len = self.atom_data.len();
+        let mut seen = vec![false; len];
+        let is_permutation = perm.len() == len
+            && perm
+                .iter()
+                .all(|&old| old < len && !std::mem::replace(&mut seen[old], true));
+        if !is_permutation {
+            return Err(FrameError::InvalidPermutation { len });
+        }
+
+        let masses = self.header.per_atom_masses();
+        let mut natms_per_type: Vec<usize> = Vec::new();
+        let mut masses_per_type: Vec<f64> = Vec::new();
+        let mut block_symbols: Vec<&str> = Vec::new();
+        for &old in perm {
+            let symbol = self.atom_data[old].symbol_str();
+            if block_symbols.last() == Some(&symbol) {
+                *natms_per_type.last_mut().expect("block already started") += 1;
+                continue;
+            }
+            if block_symbols.contains(&symbol) {
+                return Err(FrameError::InterleavedTypes {
+                    symbol: symbol.to_string(),
+                });
+            }
+            block_symbols.push(symbol);
+            natms_per_type.push(1);
+            masses_per_type.push(masses.get(old).copied().unwrap_or(0.0));
+        }
+
+        let mut old_atoms: Vec<Option<AtomDatum>> = std::mem::take(&mut self.atom_data)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.atom_data = perm
+            .iter()
+            .map(|&old| {
+                old_atoms[old]
+                    .take()
+                    .expect("permutation indices are unique")
+            })
+            .collect();
+        self.header.natm_types = natms_per_type.len();
+        self.header.natms_per_type = natms_per_type;
+        self.header.masses_per_type = masses_per_type;
+        Ok(())
+    }
+
+    /// Keeps only the atoms for which `pred` returns `true`, e.g. to strip
+    /// solvent or cut out a region.
+    ///
+    /// Survivors are regrouped by symbol in order of first appearance, so the
+    /// frame stays grouped by type even if it held several blocks of one
+    /// symbol. The header is re-derived from the survivors: a type with no
+    /// atoms left is dropped, and each remaining type keeps its mass. Atom IDs
+    /// are kept as they are.
+    pub fn retain(&mut self, mut pred: impl FnMut(&AtomDatum) -> bool) {
+        let masses = self.header.per_atom_masses();
+        let mut blocks: Vec<(f64, Vec<AtomDatum>)> = Vec::new();
+        for (i, atom) in std::mem::take(&mut self.atom_data).into_iter().enumerate() {
+            if !pred(&atom) {
+                continue;
+            }
+            match blocks
+                .iter_mut()
+                .find(|(_, block)| block[0].symbol == atom.symbol)
+            {
+                Some((_, block)) => block.push(atom),
+                None => blocks.push((masses.get(i).copied().unwrap_or(0.0), vec![atom])),
+            }
+        }
+
+        self.header.natm_types = blocks.len();
+        self.header.natms_per_type = blocks.iter().map(|(_, block)| block.len()).collect();
+        self.header.masses_per_type = blocks.iter().map(|&(mass, _)| mass).collect();
+        self.atom_data = blocks.into_iter().flat_map(|(_, block)| block).collect();
+    }
+
+    /// Returns a copy of the frame holding only the atoms whose symbol is in
+    /// `symbols`. See [`ConFrame::retain`].
+    pub fn filter_by_symbol(&self, symbols: &[&str]) -> ConFrame {
+        let mut filtered = self.clone();
+        filtered.retain(|atom| symbols.contains(&atom.symbol_str()));
+        filtered
+    }
+
+    /// Replaces purely numeric symbols with the element they number, for files
+    /// that store atomic numbers in the symbol line (`"1"` becomes `"H"`,
+    /// `"29"` becomes `"Cu"`).
+    ///
+    /// Symbols that are not a plain integer, or that name no known element
+    /// (such as `"0"`), are left unchanged. The header is not touched, so a
+    /// resolved type keeps its block and mass even if another block already
+    /// uses the same element.
+    pub fn resolve_numeric_symbols(&mut self) {
+        let mut resolved: Vec<(Rc<String>, Rc<String>)> = Vec::new();
+        for atom in &mut self.atom_data {
+            if let Some((_, element)) = resolved.iter().find(|(from, _)| *from == atom.symbol) {
+                atom.symbol = Rc::clone(element);
+                continue;
+            }
+            let digits = atom.symbol.bytes().all(|b| b.is_ascii_digit());
+            let element = digits
+                .then(|| atom.symbol.parse().ok())
+                .flatten()
+                .and_then(helpers::atomic_number_to_symbol);
+            if let Some(element) = element {
+                let element = Rc::new(element.to_string());
+                resolved.push((Rc::clone(&atom.symbol), Rc::clone(&element)));
+                atom.symbol = element;
+            }
+        }
+    }
+
+    /// Returns `true` if `other` describes the same frame up to `tol`.
+    ///
+    /// Cell lengths, angles, positions and velocities may differ by at most
+    /// `tol` per component. Type counts, symbols, atom IDs, fixed flags and
+    /// the presence of velocities must match exactly; header text, masses and
+    /// extra columns are ignored. Useful for comparing a frame with its
+    /// round-trip through the writer at a finite precision.
+    pub fn approx_eq(&self, other: &ConFrame, tol: f64) -> bool {
+        let close = |a: f64, b: f64| (a - b).abs() <= tol;
+        let close_opt = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => close(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        self.header.natms_per_type == other.header.natms_per_type
+            && self.atom_data.len() == other.atom_data.len()
+            && cells_match(&self.header, &other.header, tol)
+            && self.atom_data.iter().zip(&other.atom_data).all(|(a, b)| {
+                a.symbol_str() == b.symbol_str()
+                    && a.atom_id == b.atom_id
+                    && a.is_fixed == b.is_fixed
+                    && close(a.x, b.x)
+                    && close(a.y, b.y)
+                    && close(a.z, b.z)
+                    && close_opt(a.vx, b.vx)
+                    && close_opt(a.vy, b.vy)
+                    && close_opt(a.vz, b.vz)
+            })
+    }
+}
+
+impl ConFrame {
+    /// Returns the frame as `.con` text with `precision` decimal places,
+    /// matching the output of [`crate::writer::ConFrameWriter`].
+    pub fn to_con_string(&self, precision: usize) -> String {
+        crate::writer::write_con_string([self], precision)
+    }
+}
+
+/// Formats the frame as `.con` text. The precision defaults to 6 decimal
+/// places and can be set with the usual syntax, e.g. `format!("{frame:.3}")`.
+impl std::fmt::Display for ConFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_con_string(f.precision().unwrap_or(6)))
+    }
+}
+
+// Manual implementation of PartialEq because of the change to AtomDatum.
+impl PartialEq for ConFrame {
+    fn eq(&self, other: &Self) -> bool {
+        self.header == other.header && self.atom_data == other.atom_data
+    }
+}
+
+/// Options for [`ConFrame::wrap_into_cell_opts`].
+#[derive(Debug, Clone)]
+pub struct WrapOptions {
+    /// Whether atoms with `is_fixed == true` are wrapped as well. Defaults to `true`.
+    pub include_fixed: bool,
+}
+
+impl Default for WrapOptions {
+    fn default() -> Self {
+        WrapOptions {
+            include_fixed: true,
+        }
+    }
+}
+
+/// Returns `true` if both headers' cell lengths and angles agree within `tolerance`.
+fn cells_match(a: &FrameHeader, b: &FrameHeader, tolerance: f64) -> bool {
+    let close =
+        |x: &[f64; 3], y: &[f64; 3]| x.iter().zip(y).all(|(p, q)| (p - q).abs() <= tolerance);
+    close(&a.boxl, &b.boxl) && close(&a.angles, &b.angles)
+}
+
+/// Minimum-image displacement for a frame's cell.
+///
+/// Falls back to plain Cartesian differences when the cell is degenerate.
+struct MinimumImage {
+    cell: [[f64; 3]; 3],
+    inverse: Option<[[f64; 3]; 3]>,
+}
+
+impl MinimumImage {
+    fn new(header: &FrameHeader) -> Self {
+        MinimumImage {
+            cell: header.cell_matrix(),
+            inverse: header.cell_matrix_inverse(),
+        }
+    }
+
+    /// Returns the displacement `b - a` reduced to its nearest periodic image.
+    fn delta(&self, a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+        let d = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let Some(inv) = &self.inverse else {
+            return d;
+        };
+        let frac = helpers::row_times_matrix(d, inv).map(|f| f - f.round());
+        helpers::row_times_matrix(frac, &self.cell)
+    }
+}
+
+/// Atoms binned on a grid over their wrapped fractional coordinates.
+///
+/// Bins are at least `cutoff` wide along each lattice direction, so a pair
+/// whose minimum-image separation is below `cutoff` always sits in the same
+/// or neighbouring bins, counting across the periodic boundary.
+struct CellList {
+    dims: [usize; 3],
+    bins: Vec<Vec<usize>>,
+    atom_bins: Vec<[usize; 3]>,
+}
+
+impl CellList {
+    fn new(positions: &[[f64; 3]], inverse: &[[f64; 3]; 3], cutoff: f64) -> Self {
+        // The cell's height along axis k is one over the length of the k-th
+        // reciprocal vector, the k-th column of the inverse. Capping the bins
+        // per axis keeps the grid to about one bin per atom for tiny cutoffs.
+        let cap = (positions.len() as f64).cbrt().ceil().max(1.0) as usize;
+        let dims: [usize; 3] = std::array::from_fn(|k| {
+            let height = 1.0 / norm([inverse[0][k], inverse[1][k], inverse[2][k]]);
+            ((height / cutoff) as usize).clamp(1, cap)
+        });
+        let atom_bins: Vec<[usize; 3]> = positions
+            .iter()
+            .map(|&p| {
+                let frac = helpers::row_times_matrix(p, inverse);
+                std::array::from_fn(|k| {
+                    let bin = (frac[k].rem_euclid(1.0) * dims[k] as f64) as usize;
+                    bin.min(dims[k] - 1)
+                })
+            })
+            .collect();
+        let mut bins = vec![Vec::new(); dims[0] * dims[1] * dims[2]];
+        for (i, &bin) in atom_bins.iter().enumerate() {
+            bins[Self::flat(dims, bin)].push(i);
+        }
+        CellList {
+            dims,
+            bins,
+            atom_bins,
+        }
+    }
+
+    fn flat(dims: [usize; 3], [a, b, c]: [usize; 3]) -> usize {
+        (a * dims[1] + b) * dims[2] + c
+    }
+
+    /// Atoms in the bins around atom `i`'s, including `i` itself. With fewer
+    /// than three bins along an axis the periodic neighbours coincide, so
+    /// each bin is visited once.
+    fn candidates(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        let around: [Vec<usize>; 3] = std::array::from_fn(|k| {
+            let (n, b) = (self.dims[k], self.atom_bins[i][k]);
+            let mut bins = vec![(b + n - 1) % n, b, (b + 1) % n];
+            bins.sort_unstable();
+            bins.dedup();
+            bins
+        });
+        let mut bins = Vec::with_capacity(27);
+        for &a in &around[0] {
+            for &b in &around[1] {
+                for &c in &around[2] {
+                    bins.push(Self::flat(self.dims, [a, b, c]));
+                }
+            }
+        }
+        bins.into_iter()
+            .flat_map(move |bin| self.bins[bin].iter().copied())
+    }
+}
+
+fn norm([x, y, z]: [f64; 3]) -> f64 {
+    (x * x + y * y + z * z).sqrt()
+}
+
+/// Maps `value` into `[0, period)`.
+fn wrap_periodic(value: f64, period: f64) -> f64 {
+    let wrapped = value.rem_euclid(period);
+    // rem_euclid can round up to `period` for tiny negative inputs.
+    if wrapped >= period { 0.0 } else { wrapped }
+}
+
+/// A builder for constructing `ConFrame` objects from in-memory data.
+///
+/// Atoms are accumulated and grouped by symbol on `build()` to compute the
+/// header fields (`natm_types`, `natms_per_type`, `masses_per_type`).
+///
+/// # Example
+///
+/// ```
+/// use readcon_core::types::ConFrameBuilder;
+///
+/// let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+/// builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
+/// builder.add_atom("H", 1.0, 2.0, 3.0, false, 1, 1.008);
+/// let frame = builder.build();
+/// assert_eq!(frame.header.natm_types, 2);
+/// assert_eq!(frame.atom_data.len(), 2);
+/// ```
+pub struct ConFrameBuilder {
+    prebox_header: [String; 2],
+    cell: [f64; 3],
+    angles: [f64; 3],
+    postbox_header: [String; 2],
+    atoms: Vec<BuilderAtom>,
+    /// Per-symbol masses that take precedence over the per-atom ones.
+    type_masses: HashMap<String, f64>,
+}
+
+struct BuilderAtom {
+    symbol: String,
+    x: f64,
+    y: f64,
+    z: f64,
+    is_fixed: bool,
+    atom_id: u64,
+    mass: f64,
+    vx: Option<f64>,
+    vy: Option<f64>,
+    vz: Option<f64>,
+    extra_columns: Vec<f64>,
+}
+
+impl ConFrameBuilder {
+    /// Creates a new builder with the given cell dimensions and angles.
+    pub fn new(cell: [f64; 3], angles: [f64; 3]) -> Self {
+        Self {
+            prebox_header: [String::new(), String::new()],
+            cell,
+            angles,
+            postbox_header: [String::new(), String::new()],
+            atoms: Vec::new(),
+            type_masses: HashMap::new(),
+        }
+    }
+
+    /// Sets the two pre-box header lines.
+    pub fn prebox_header(mut self, h: [String; 2]) -> Self {
+        self.prebox_header = h;
+        self
+    }
+
+    /// Sets the two post-box header lines.
+    pub fn postbox_header(mut self, h: [String; 2]) -> Self {
+        self.postbox_header = h;
+        self
+    }
+
+    /// Sets the mass used for every atom of `symbol`, overriding the masses
+    /// passed when the atoms were added. Applies at build time, so it may be
+    /// called before or after the atoms are added.
+    pub fn set_type_mass(&mut self, symbol: &str, mass: f64) {
+        self.type_masses.insert(symbol.to_string(), mass);
+    }
+
+    /// Overrides the masses of several types at once, as repeated calls to
+    /// [`ConFrameBuilder::set_type_mass`] would. Symbols without atoms are
+    /// ignored.
+    pub fn with_masses(mut self, masses: HashMap<String, f64>) -> Self {
+        self.type_masses.extend(masses);
+        self
+    }
+
+    /// Adds an atom without velocity data.
+    pub fn add_atom(
+        &mut self,
+        symbol: &str,
+        x: f64,
+        y: f64,
+        z: f64,
+        is_fixed: bool,
+        atom_id: u64,
+        mass: f64,
+    ) {
+        self.atoms.push(BuilderAtom {
+            symbol: symbol.to_string(),
+            x,
+            y,
+            z,
+            is_fixed,
+            atom_id,
+            mass,
+            vx: None,
+            vy: None,
+            vz: None,
+            extra_columns: Vec::new(),
+        });
+    }
+
+    /// Adds an atom without velocity data, taking its mass from
+    /// [`helpers::standard_atomic_mass`].
+    ///
+    /// Unknown symbols get a mass of `0.0`; check them with
+    /// `standard_atomic_mass` first if that matters.
+    pub fn add_atom_auto_mass(
+        &mut self,
+        symbol: &str,
+        x: f64,
+        y: f64,
+        z: f64,
+        is_fixed: bool,
+        atom_id: u64,
+    ) {
+        let mass = helpers::standard_atomic_mass(symbol).unwrap_or(0.0);
+        self.add_atom(symbol, x, y, z, is_fixed, atom_id, mass);
+    }
+
+    /// Adds an atom with velocity data (for .convel output).
+    pub fn add_atom_with_velocity(
+        &mut self,
+        symbol: &str,
+        x: f64,
+        y: f64,
+        z: f64,
+        is_fixed: bool,
+        atom_id: u64,
+        mass: f64,
+        vx: f64,
+        vy: f64,
+        vz: f64,
+    ) {
+        self.atoms.push(BuilderAtom {
+            symbol: symbol.to_string(),
+            x,
+            y,
+            z,
+            is_fixed,
+            atom_id,
+            mass,
+            vx: Some(vx),
+            vy: Some(vy),
+            vz: Some(vz),
+            extra_columns: Vec::new(),
+        });
+    }
+
+    /// Adds many atoms from per-atom columns in one call, as calling
+    /// [`ConFrameBuilder::add_atom`] for each index in turn would. Suits
+    /// columnar data such as NumPy arrays.
+    ///
+    /// # Errors
+    ///
+    /// * `BuildError::LengthMismatch` if a column does not have one entry per
+    ///   symbol. No atoms are added in that case.
+    pub fn add_atoms_soa(
+        &mut self,
+        symbols: &[&str],
+        xyz: &[[f64; 3]],
+        fixed: &[bool],
+        ids: &[u64],
+        masses: &[f64],
+    ) -> Result<(), BuildError> {
+        check_column_lengths(
+            symbols.len(),
+            &[
+                ("xyz", xyz.len()),
+                ("fixed", fixed.len()),
+                ("ids", ids.len()),
+                ("masses", masses.len()),
+            ],
+        )?;
+        self.atoms.reserve(symbols.len());
+        let columns = symbols.iter().zip(xyz).zip(fixed).zip(ids).zip(masses);
+        for ((((&symbol, &[x, y, z]), &is_fixed), &atom_id), &mass) in columns {
+            self.add_atom(symbol, x, y, z, is_fixed, atom_id, mass);
+        }
+        Ok(())
+    }
+
+    /// Like [`ConFrameBuilder::add_atoms_soa`], with one velocity per atom as
+    /// [`ConFrameBuilder::add_atom_with_velocity`] takes.
+    ///
+    /// # Errors
+    ///
+    /// * `BuildError::LengthMismatch` if a column, including `velocities`,
+    ///   does not have one entry per symbol. No atoms are added in that case.
+    pub fn add_atoms_soa_with_velocity(
+        &mut self,
+        symbols: &[&str],
+        xyz: &[[f64; 3]],
+        fixed: &[bool],
+        ids: &[u64],
+        masses: &[f64],
+        velocities: &[[f64; 3]],
+    ) -> Result<(), BuildError> {
+        check_column_lengths(symbols.len(), &[("velocities", velocities.len())])?;
+        let start = self.atoms.len();
+        self.add_atoms_soa(symbols, xyz, fixed, ids, masses)?;
+        for (atom, &[vx, vy, vz]) in self.atoms[start..].iter_mut().zip(velocities) {
+            atom.vx = Some(vx);
+            atom.vy = Some(vy);
+            atom.vz = Some(vz);
+        }
+        Ok(())
+    }
+
+    /// Adds a copy of an existing atom, keeping its velocities and extra
+    /// columns, with the given mass.
+    pub(crate) fn push_atom_datum(&mut self, atom: &AtomDatum, mass: f64) {
+        self.atoms.push(BuilderAtom {
+            symbol: atom.symbol.to_string(),
+            x: atom.x,
+            y: atom.y,
+            z: atom.z,
+            is_fixed: atom.is_fixed,
+            atom_id: atom.atom_id,
+            mass,
+            vx: atom.vx,
+            vy: atom.vy,
+            vz: atom.vz,
+            extra_columns: atom.extra_columns.clone(),
+        });
+    }
+
+    /// Like [`ConFrameBuilder::build`], but rejects input that `build` would
+    /// silently paper over.
+    ///
+    /// # Errors
+    ///
+    /// * `BuildError::InconsistentMass` if two atoms with the same symbol have
+    ///   different masses (`build` keeps the first). Symbols whose mass was
+    ///   set with [`ConFrameBuilder::set_type_mass`] are not checked.
+    /// * `BuildError::PartialVelocities` if only some atoms have all three
+    ///   velocity components, which a `.convel` file cannot represent.
+    pub fn build_checked(self) -> Result<ConFrame, BuildError> {
+        let mut first_mass: HashMap<&str, f64> = HashMap::new();
+        let mut with_velocity = 0;
+        for (atom_index, atom) in self.atoms.iter().enumerate() {
+            let expected = *first_mass.entry(atom.symbol.as_str()).or_insert(atom.mass);
+            if atom.mass != expected && !self.type_masses.contains_key(&atom.symbol) {
+                return Err(BuildError::InconsistentMass {
+                    symbol: atom.symbol.clone(),
+                    atom_index,
+                    expected,
+                    found: atom.mass,
+                });
+            }
+            if atom.vx.is_some() && atom.vy.is_some() && atom.vz.is_some() {
+                with_velocity += 1;
+            }
+        }
+        let without_velocity = self.atoms.len() - with_velocity;
+        if with_velocity > 0 && without_velocity > 0 {
+            return Err(BuildError::PartialVelocities {
+                with: with_velocity,
+                without: without_velocity,
+            });
+        }
+        Ok(self.build())
+    }
+
+    /// Consumes the builder and produces a `ConFrame`.
+    ///
+    /// Atoms are grouped by symbol (in encounter order) to compute
+    /// `natm_types`, `natms_per_type`, and `masses_per_type`. Atoms of the
+    /// same type keep their insertion order; grouping is O(atoms + types).
+    /// Each type's mass is the one set with [`ConFrameBuilder::set_type_mass`],
+    /// or else that of its first atom.
+    pub fn build(self) -> ConFrame {
+        self.build_with_permutation().0
+    }
+
+    /// Like [`ConFrameBuilder::build`], but also returns the grouping
+    /// permutation: entry `k` is the insertion index of output atom `k`.
+    ///
+    /// Per-atom data tracked alongside the builder, such as charges or forces,
+    /// can be brought into frame order with `perm.iter().map(|&i| data[i])`.
+    /// This is the same convention as [`ConFrame::reorder`].
+    pub fn build_with_permutation(self) -> (ConFrame, Vec<usize>) {
+        // Assign each atom a type index, with types numbered in encounter order.
+        let mut type_lookup: HashMap<&str, usize> = HashMap::new();
+        let mut type_order: Vec<&str> = Vec::new();
+        let mut type_counts: Vec<usize> = Vec::new();
+        let mut type_masses: Vec<f64> = Vec::new();
+        let mut atom_types: Vec<usize> = Vec::with_capacity(self.atoms.len());
+
+        for atom in &self.atoms {
+            let idx = *type_lookup.entry(atom.symbol.as_str()).or_insert_with(|| {
+                type_order.push(atom.symbol.as_str());
+                type_counts.push(0);
+                let mass = self.type_masses.get(&atom.symbol).copied();
+                type_masses.push(mass.unwrap_or(atom.mass));
+                type_order.len() - 1
+            });
+            type_counts[idx] += 1;
+            atom_types.push(idx);
+        }
+
+        // Stable bucket partition: each type block starts at the running sum of
+        // the preceding counts, and atoms keep their insertion order within it.
+        let mut next_slot: Vec<usize> = Vec::with_capacity(type_counts.len());
+        let mut offset = 0;
+        for &count in &type_counts {
+            next_slot.push(offset);
+            offset += count;
+        }
+        let mut order: Vec<usize> = vec![0; self.atoms.len()];
+        for (atom_idx, &type_idx) in atom_types.iter().enumerate() {
+            order[next_slot[type_idx]] = atom_idx;
+            next_slot[type_idx] += 1;
+        }
+
+        let symbols: Vec<Rc<String>> = type_order.iter().map(|s| Rc::new(s.to_string())).collect();
+        let atom_data: Vec<AtomDatum> = order
+            .iter()
+            .map(|&i| self.atoms[i].to_atom_datum(Rc::clone(&symbols[atom_types[i]])))
+            .collect();
+        let natm_types = type_order.len();
+
+        let header = FrameHeader {
+            prebox_header: self.prebox_header,
+            boxl: self.cell,
+            angles: self.angles,
+            postbox_header: self.postbox_header,
+            natm_types,
+            natms_per_type: type_counts,
+            masses_per_type: type_masses,
+        };
+
+        (ConFrame { header, atom_data }, order)
+    }
+
+    /// Like [`ConFrameBuilder::build`], but keeps the atoms in insertion
+    /// order.
+    ///
+    /// Instead of one block per symbol, each run of consecutive atoms with the
+    /// same symbol becomes its own type block, so interleaved symbols give
+    /// several blocks for one element (in the worst case, one per atom). Such
+    /// a frame is valid for this crate's reader and writer, but tools that
+    /// expect each element in a single block may not read it as intended.
+    /// Each symbol's mass is chosen as in `build`, and every block of that
+    /// symbol gets it.
+    pub fn build_preserving_order(self) -> ConFrame {
+        let mut symbols: HashMap<&str, (Rc<String>, f64)> = HashMap::new();
+        let mut natms_per_type: Vec<usize> = Vec::new();
+        let mut masses_per_type: Vec<f64> = Vec::new();
+        let mut atom_data: Vec<AtomDatum> = Vec::with_capacity(self.atoms.len());
+        for (i, atom) in self.atoms.iter().enumerate() {
+            let (symbol, mass) = symbols.entry(atom.symbol.as_str()).or_insert_with(|| {
+                let mass = self.type_masses.get(&atom.symbol).copied();
+                (Rc::new(atom.symbol.clone()), mass.unwrap_or(atom.mass))
+            });
+            if i > 0 && self.atoms[i - 1].symbol == atom.symbol {
+                *natms_per_type.last_mut().expect("a block was started") += 1;
+            } else {
+                natms_per_type.push(1);
+                masses_per_type.push(*mass);
+            }
+            atom_data.push(atom.to_atom_datum(Rc::clone(symbol)));
+        }
+
+        let header = FrameHeader {
+            prebox_header: self.prebox_header,
+            boxl: self.cell,
+            angles: self.angles,
+            postbox_header: self.postbox_header,
+            natm_types: natms_per_type.len(),
+            natms_per_type,
+            masses_per_type,
+        };
+        ConFrame { header, atom_data }
+    }
+}
+
+/// Checks that each `(name, len)` column has `expected` entries.
+fn check_column_lengths(
+    expected: usize,
+    columns: &[(&'static str, usize)],
+) -> Result<(), BuildError> {
+    match columns.iter().find(|&&(_, len)| len != expected) {
+        Some(&(field, found)) => Err(BuildError::LengthMismatch {
+            field,
+            expected,
+            found,
+        }),
+        None => Ok(()),
+    }
+}
+
+impl BuilderAtom {
+    fn to_atom_datum(&self, symbol: Rc<String>) -> AtomDatum {
+        AtomDatum {
+            symbol,
+            x: self.x,
+            y: self.y,
+            z: self.z,
+            is_fixed: self.is_fixed,
+            atom_id: self.atom_id,
+            vx: self.vx,
+            vy: self.vy,
+            vz: self.vz,
+            extra_columns: self.extra_columns.clone(),
+        }
+    }
+}
+
+/// Options for [`ConFrame::merge_with_options`].
+#[derive(Debug, Clone)]
+pub struct MergeOptions {
+    /// Maximum absolute difference allowed between the cell lengths and angles
+    /// of the merged frames. Defaults to `1e-6`.
+    pub tolerance: f64,
+    /// Keep the original atom IDs instead of renumbering atoms `0..n` in input
+    /// order. Defaults to `false`, since IDs from separate frames usually clash.
+    pub preserve_ids: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        MergeOptions {
+            tolerance: 1e-6,
+            preserve_ids: false,
+        }
+    }
+}
+
+impl ConFrame {
+    /// Merges several frames sharing the same cell into one frame, using
+    /// `MergeOptions::default()`. See [`ConFrame::merge_with_options`].
+    pub fn merge(frames: &[ConFrame]) -> Result<ConFrame, FrameError> {
+        Self::merge_with_options(frames, &MergeOptions::default())
+    }
+
+    /// Merges several frames sharing the same cell into one frame.
+    ///
+    /// Atoms are concatenated in input order and regrouped by symbol through
+    /// [`ConFrameBuilder`], which re-derives `natms_per_type` and
+    /// `masses_per_type`. The header lines and cell come from the first frame.
+    ///
+    /// # Errors
+    ///
+    /// * `FrameError::NoFrames` if `frames` is empty.
+    /// * `FrameError::CellMismatch` if a frame's cell lengths or angles differ
+    ///   from the first frame's by more than `options.tolerance`.
+    pub fn merge_with_options(
+        frames: &[ConFrame],
+        options: &MergeOptions,
+    ) -> Result<ConFrame, FrameError> {
+        let first = frames.first().ok_or(FrameError::NoFrames)?;
+        let reference = &first.header;
+        for (index, frame) in frames.iter().enumerate().skip(1) {
+            if !cells_match(&frame.header, reference, options.tolerance) {
+                return Err(FrameError::CellMismatch { frame: index });
+            }
+        }
+
+        let mut builder = ConFrameBuilder::new(reference.boxl, reference.angles)
+            .prebox_header(reference.prebox_header.clone())
+            .postbox_header(reference.postbox_header.clone());
+        for frame in frames {
+            for (atom, mass) in frame.atom_data.iter().zip(frame.header.per_atom_masses()) {
+                builder.push_atom_datum(atom, mass);
+            }
+        }
+        if !options.preserve_ids {
+            for (id, atom) in builder.atoms.iter_mut().enumerate() {
+                atom.atom_id = id as u64;
+            }
+        }
+        Ok(builder.build())
+    }
+
+    /// Appends `other`'s atoms to this frame in place, keeping atoms grouped
+    /// by type.
+    ///
+    /// Atoms of a symbol already present are inserted at the end of that type
+    /// block; new symbols become new type blocks, taking their mass from
+    /// `other`. Atom IDs are kept as they are. The cells must agree within
+    /// `MergeOptions::default().tolerance`.
+    ///
+    /// # Errors
+    ///
+    /// * `FrameError::CellMismatch { frame: 1 }` if `other` has a different cell.
+    pub fn append_atoms_from(&mut self, other: &ConFrame) -> Result<(), FrameError> {
+        if !cells_match(
+            &other.header,
+            &self.header,
+            MergeOptions::default().tolerance,
+        ) {
+            return Err(FrameError::CellMismatch { frame: 1 });
+        }
+
+        let mut other_offset = 0;
+        for (other_type, &count) in other.header.natms_per_type.iter().enumerate() {
+            let end = (other_offset + count).min(other.atom_data.len());
+            let block = &other.atom_data[other_offset.min(end)..end];
+            other_offset = end;
+            let Some(first) = block.first() else {
+                continue;
+            };
+
+            // Find the matching type block in `self` and where it ends.
+            let mut block_end = 0;
+            let mut existing = None;
+            for (t, &n) in self.header.natms_per_type.iter().enumerate() {
+                block_end += n;
+                let symbol = self.atom_data.get(block_end - n).map(AtomDatum::symbol_str);
+                if n > 0 && symbol == Some(first.symbol_str()) {
+                    existing = Some((t, block_end));
+                    break;
+                }
+            }
+
+            match existing {
+                Some((t, insert_at)) => {
+                    let symbol = Rc::clone(&self.atom_data[insert_at - 1].symbol);
+                    let appended = block.iter().map(|a| AtomDatum {
+                        symbol: Rc::clone(&symbol),
+                        ..a.clone()
+                    });
+                    self.atom_data.splice(insert_at..insert_at, appended);
+                    self.header.natms_per_type[t] += block.len();
+                }
+                None => {
+                    let mass = other
+                        .header
+                        .masses_per_type
+                        .get(other_type)
+                        .copied()
+                        .unwrap_or(0.0);
+                    self.atom_data.extend(block.iter().cloned());
+                    self.header.natm_types += 1;
+                    self.header.natms_per_type.push(block.len());
+                    self.header.masses_per_type.push(mass);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates a new builder for constructing a `ConFrame`.
+    pub fn builder(cell: [f64; 3], angles: [f64; 3]) -> ConFrameBuilder {
+        ConFrameBuilder::new(cell, angles)
+    }
+}
+
+/// The fields of one atom that [`ConFrame::validation_issues`] checks,
+/// borrowed without the `Rc` symbol so frames can be checked on other threads.
+#[derive(Clone, Copy)]
+struct AtomView<'a> {
+    symbol: &'a str,
+    xyz: [f64; 3],
+    vel: [Option<f64>; 3],
+}
+
+impl<'a> AtomView<'a> {
+    fn of(atom: &'a AtomDatum) -> Self {
+        AtomView {
+            symbol: atom.symbol_str(),
+            xyz: [atom.x, atom.y, atom.z],
+            vel: [atom.vx, atom.vy, atom.vz],
+        }
+    }
+}
+
+/// The checks behind [`ConFrame::validation_issues`], for a frame with
+/// `natoms` atoms read through `atom`.
+fn issues_in<'a>(
+    header: &FrameHeader,
+    natoms: usize,
+    atom: impl Fn(usize) -> AtomView<'a>,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let cell_ok = header.boxl.iter().all(|l| l.is_finite() && *l >= 0.0)
+        && header.angles.iter().all(|a| a.is_finite());
+    if !cell_ok {
+        issues.push(ValidationIssue::InvalidCell);
+    }
+
+    if header.natms_per_type.len() != header.natm_types
+        || header.masses_per_type.len() != header.natm_types
+    {
+        issues.push(ValidationIssue::TypeCountMismatch {
+            natm_types: header.natm_types,
+            natms_per_type: header.natms_per_type.len(),
+            masses_per_type: header.masses_per_type.len(),
+        });
+    }
+
+    let expected = header.total_atoms();
+    if expected != natoms {
+        issues.push(ValidationIssue::AtomCountMismatch {
+            expected,
+            found: natoms,
+        });
+    }
+
+    let mut offset = 0;
+    for (type_index, &count) in header.natms_per_type.iter().enumerate() {
+        let end = (offset + count).min(natoms);
+        let start = offset.min(end);
+        if start < end {
+            let first = atom(start).symbol;
+            if let Some(pos) = (start..end).position(|i| atom(i).symbol != first) {
+                issues.push(ValidationIssue::MixedSymbols {
+                    type_index,
+                    atom_index: start + pos,
+                });
+            }
+        }
+        offset = end;
+    }
+
+    let with = (0..natoms)
+        .filter(|&i| atom(i).vel.iter().all(Option::is_some))
+        .count();
+    if with != 0 && with != natoms {
+        issues.push(ValidationIssue::PartialVelocities {
+            with,
+            without: natoms - with,
+        });
+    }
+
+    for atom_index in 0..natoms {
+        let AtomView { xyz, vel, .. } = atom(atom_index);
+        let finite =
+            xyz.iter().all(|v| v.is_finite()) && vel.iter().flatten().all(|v| v.is_finite());
+        if !finite {
+            issues.push(ValidationIssue::NonFiniteValue { atom_index });
+        }
+    }
+
+    issues
+}
+
+/// Runs [`ConFrame::validation_issues`] on every frame across rayon threads
+/// and returns `(frame_index, issues)` for the frames that have problems, in
+/// frame order.
+///
+/// Requires the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub fn validate_trajectory_parallel(frames: &[ConFrame]) -> Vec<(usize, Vec<ValidationIssue>)> {
+    use rayon::prelude::*;
+
+    // Borrow each frame's fields without the `Rc` symbols on this thread, so
+    // only `Sync` data reaches the workers.
+    let views: Vec<(&FrameHeader, Vec<AtomView>)> = frames
+        .iter()
+        .map(|frame| {
+            (
+                &frame.header,
+                frame.atom_data.iter().map(AtomView::of).collect(),
+            )
+        })
+        .collect();
+    views
+        .par_iter()
+        .enumerate()
+        .filter_map(|(i, (header, atoms))| {
+            let issues = issues_in(header, atoms.len(), |k| atoms[k]);
+            (!issues.is_empty()).then_some((i, issues))
+        })
+        .collect()
+}
+
+/// An ordered sequence of frames, e.g. every frame of a multi-frame `.con` file.
+///
+/// Dereferences to `[ConFrame]` and indexes like a slice (`traj[i]`,
+/// `traj[1..]`), so it can stand in for a `Vec<ConFrame>` while
+/// trajectory-level operations live on this type.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Trajectory(Vec<ConFrame>);
+
+impl Trajectory {
+    /// Wraps a vector of frames.
+    pub fn from_frames(frames: Vec<ConFrame>) -> Self {
+        Trajectory(frames)
+    }
+
+    /// Unwraps the trajectory into its frames.
+    pub fn into_frames(self) -> Vec<ConFrame> {
+        self.0
+    }
+
+    /// Returns an iterator over mutable references to the frames.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, ConFrame> {
+        self.0.iter_mut()
+    }
+}
+
+impl From<Vec<ConFrame>> for Trajectory {
+    fn from(frames: Vec<ConFrame>) -> Self {
+        Trajectory(frames)
+    }
+}
+
+impl FromIterator<ConFrame> for Trajectory {
+    fn from_iter<I: IntoIterator<Item = ConFrame>>(iter: I) -> Self {
+        Trajectory(iter.into_iter().collect())
+    }
+}
+
+impl std::ops::Deref for Trajectory {
+    type Target = [ConFrame];
+
+    fn deref(&self) -> &[ConFrame] {
+        &self.0
+    }
+}
+
+impl<I: std::slice::SliceIndex<[ConFrame]>> std::ops::Index<I> for Trajectory {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<I: std::slice::SliceIndex<[ConFrame]>> std::ops::IndexMut<I> for Trajectory {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl IntoIterator for Trajectory {
+    type Item = ConFrame;
+    type IntoIter = std::vec::IntoIter<ConFrame>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Trajectory {
+    type Item = &'a ConFrame;
+    type IntoIter = std::slice::Iter<'a, ConFrame>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_ordering_matches_nested_scan() {
+        let symbols = [
+            "Ni", "Cu", "H", "Cu", "Al", "Ni", "H", "Fe", "Al", "Cu", "Fe", "Ni",
+        ];
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        for (i, symbol) in symbols.iter().enumerate() {
+            builder.add_atom(
+                symbol,
+                i as f64,
+                0.0,
+                0.0,
+                i % 2 == 0,
+                i as u64,
+                1.0 + i as f64,
+            );
+        }
+        let frame = builder.build();
+
+        // The ordering produced by the original per-type scan over all atoms.
+        let mut type_order: Vec<&str> = Vec::new();
+        for symbol in symbols {
+            if !type_order.contains(&symbol) {
+                type_order.push(symbol);
+            }
+        }
+        let expected: Vec<u64> = type_order
+            .iter()
+            .flat_map(|t| (0..symbols.len() as u64).filter(|&i| symbols[i as usize] == *t))
+            .collect();
+        let ids: Vec<u64> = frame.atom_data.iter().map(|a| a.atom_id).collect();
+        assert_eq!(ids, expected);
+        assert_eq!(frame.header.natms_per_type, vec![3, 3, 2, 2, 2]);
+        assert_eq!(frame.header.masses_per_type, vec![1.0, 2.0, 3.0, 5.0, 8.0]);
+        assert!(frame.atom_data.iter().all(|a| a.x == a.atom_id as f64));
+    }
+
+    #[test]
+    fn test_builder_auto_mass() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom_auto_mass("Cu", 0.0, 0.0, 0.0, true, 0);
+        builder.add_atom_auto_mass("H", 1.0, 0.0, 0.0, false, 1);
+        builder.add_atom_auto_mass("Xx", 2.0, 0.0, 0.0, false, 2);
+        let frame = builder.build();
+        assert_eq!(frame.header.masses_per_type, vec![63.546, 1.008, 0.0]);
+    }
+
+    #[test]
+    fn test_builder_build_checked() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
+        builder.add_atom("H", 1.0, 0.0, 0.0, false, 1, 1.008);
+        builder.add_atom("Cu", 2.0, 0.0, 0.0, true, 2, 63.546);
+        assert_eq!(
+            builder.build_checked().unwrap().header.natms_per_type,
+            vec![2, 1]
+        );
+
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
+        builder.add_atom("Cu", 1.0, 0.0, 0.0, true, 1, 64.0);
+        assert_eq!(
+            builder.build_checked().unwrap_err(),
+            BuildError::InconsistentMass {
+                symbol: "Cu".to_string(),
+                atom_index: 1,
+                expected: 63.546,
+                found: 64.0,
+            }
+        );
+
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
+        builder.add_atom_with_velocity("Cu", 1.0, 0.0, 0.0, true, 1, 63.546, 0.1, 0.0, 0.0);
+        assert_eq!(
+            builder.build_checked().unwrap_err(),
+            BuildError::PartialVelocities {
+                with: 1,
+                without: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_header_block_layout() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        for (i, symbol) in ["Cu", "H", "Cu", "O", "Cu"].iter().enumerate() {
+            builder.add_atom_auto_mass(symbol, i as f64, 0.0, 0.0, false, i as u64);
+        }
+        let header = builder.build().header;
+        assert_eq!(header.total_atoms(), 5);
+        assert_eq!(header.type_offsets(), vec![0, 3, 4]);
+    }
+
+    #[test]
+    fn test_reorder() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 3, 63.546);
+        builder.add_atom("Cu", 1.0, 0.0, 0.0, true, 1, 63.546);
+        builder.add_atom("H", 2.0, 0.0, 0.0, false, 2, 1.008);
+        let mut frame = builder.build();
+
+        frame.reorder(&[2, 1, 0]).unwrap();
+        let ids: Vec<u64> = frame.atom_data.iter().map(|a| a.atom_id).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        assert_eq!(frame.header.natms_per_type, vec![1, 2]);
+        assert_eq!(frame.header.masses_per_type, vec![1.008, 63.546]);
+
+        let before = frame.clone();
+        assert_eq!(
+            frame.reorder(&[1, 0, 2]),
+            Err(FrameError::InterleavedTypes {
+                symbol: "Cu".to_string()
+            })
+        );
+        assert_eq!(
+            frame.reorder(&[0, 0, 1]),
+            Err(FrameError::InvalidPermutation { len: 3 })
+        );
+        assert_eq!(
+            frame.reorder(&[0, 1]),
+            Err(FrameError::InvalidPermutation { len: 3 })
+        );
+        assert_eq!(frame, before);
+
+        frame.sort_by_atom_id();
+        let ids: Vec<u64> = frame.atom_data.iter().map(|a| a.atom_id).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        frame.reorder(&[1, 2, 0]).unwrap();
+        frame.sort_by_atom_id();
+        let ids: Vec<u64> = frame.atom_data.iter().map(|a| a.atom_id).collect();
+        assert_eq!(ids, vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn test_distance_mic() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("H", 0.5, 0.0, 0.0, false, 0, 1.008);
+        builder.add_atom("H", 9.5, 0.0, 0.0, false, 1, 1.008);
+        let frame = builder.build();
+        assert!((frame.distance(0, 1) - 9.0).abs() < 1e-12);
+        assert!((frame.distance_mic(0, 1) - 1.0).abs() < 1e-12);
+        assert_eq!(frame.distance_mic(1, 1), 0.0);
+
+        // Hexagonal cell: a = b = 4, gamma = 120 degrees. The atoms sit one
+        // lattice vector `a - b` apart, so they are periodic images.
+        let mut builder = ConFrameBuilder::new([4.0, 4.0, 10.0], [90.0, 90.0, 120.0]);
+        builder.add_atom("H", 0.0, 0.0, 0.0, false, 0, 1.008);
+        builder.add_atom("H", 6.0, -12f64.sqrt(), 0.0, false, 1, 1.008);
+        builder.add_atom("H", 6.5, -12f64.sqrt(), 0.0, false, 2, 1.008);
+        let frame = builder.build();
+        assert!(frame.distance_mic(0, 1) < 1e-12);
+        assert!((frame.distance_mic(0, 2) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_header_time_and_seed() {
+        let base = ConFrameBuilder::new([1.0; 3], [90.0; 3]).build().header;
+        let header = |line1: &str, line2: &str| FrameHeader {
+            prebox_header: [line1.to_string(), line2.to_string()],
+            ..base.clone()
+        };
+        assert_eq!(header("", "Time 1234.5").time(), Some(1234.5));
+        assert_eq!(header("", "0.0000 TIME").time(), Some(0.0));
+        assert_eq!(header("", "time: 2e3 fs").time(), Some(2000.0));
+        assert_eq!(header("", "Time").time(), None);
+        assert_eq!(header("", "Timestep 5").time(), None);
+        assert_eq!(header("", "Generated by eOn").time(), None);
+
+        assert_eq!(header("Random Number Seed 42", "").random_seed(), Some(42));
+        assert_eq!(header("Random Number Seed", "").random_seed(), None);
+        assert_eq!(header("Seed -1", "").random_seed(), None);
+    }
+
+    #[test]
+    fn test_resolve_numeric_symbols() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("29", 0.0, 0.0, 0.0, true, 0, 63.546);
+        builder.add_atom("1", 1.0, 0.0, 0.0, false, 1, 1.008);
+        builder.add_atom("1", 2.0, 0.0, 0.0, false, 2, 1.008);
+        builder.add_atom("0", 3.0, 0.0, 0.0, false, 3, 0.0);
+        builder.add_atom("+1", 4.0, 0.0, 0.0, false, 4, 0.0);
+        builder.add_atom("C", 5.0, 0.0, 0.0, false, 5, 12.011);
+        let mut frame = builder.build();
+        let header = frame.header.clone();
+
+        frame.resolve_numeric_symbols();
+        assert_eq!(frame.symbols(), ["Cu", "H", "H", "0", "+1", "C"]);
+        assert!(Rc::ptr_eq(
+            &frame.atom_data[1].symbol,
+            &frame.atom_data[2].symbol
+        ));
+        assert_eq!(frame.header, header);
+    }
+
+    #[test]
+    fn test_retain_rederives_header() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
+        builder.add_atom("Cu", 5.0, 0.0, 0.0, true, 1, 63.546);
+        builder.add_atom("O", 1.0, 0.0, 0.0, false, 2, 15.999);
+        builder.add_atom("H", 6.0, 0.0, 0.0, false, 3, 1.008);
+        builder.add_atom("H", 2.0, 0.0, 0.0, false, 4, 1.008);
+        let frame = builder.build();
+
+        let mut left = frame.clone();
+        left.retain(|atom| atom.x < 4.0);
+        assert_eq!(left.symbols(), ["Cu", "O", "H"]);
+        assert_eq!(left.header.natms_per_type, vec![1, 1, 1]);
+        let ids: Vec<u64> = left.atom_data.iter().map(|a| a.atom_id).collect();
+        assert_eq!(ids, vec![0, 2, 4]);
+
+        // Removing every oxygen drops the type from the header.
+        let water_free = frame.filter_by_symbol(&["Cu", "H"]);
+        assert_eq!(water_free.header.natm_types, 2);
+        assert_eq!(water_free.header.natms_per_type, vec![2, 2]);
+        assert_eq!(water_free.header.masses_per_type, vec![63.546, 1.008]);
+        assert!(water_free.validation_issues().is_empty());
+
+        // Split blocks of one symbol are merged back together.
+        let mut split = frame.clone();
+        split.header.natm_types = 3;
+        split.header.natms_per_type = vec![1, 3, 1];
+        split.header.masses_per_type = vec![63.546, 20.0, 1.008];
+        split.atom_data.swap(1, 3);
+        split.retain(|atom| atom.atom_id != 2);
+        assert_eq!(split.symbols(), ["Cu", "Cu", "H", "H"]);
+        assert_eq!(split.header.natms_per_type, vec![2, 2]);
+        assert_eq!(split.header.masses_per_type, vec![63.546, 20.0]);
+
+        let mut empty = frame;
+        empty.retain(|_| false);
+        assert_eq!(empty.header.natm_types, 0);
+        assert!(empty.atom_data.is_empty() && empty.header.masses_per_type.is_empty());
+    }
+
+    #[test]
+    fn test_add_atoms_soa() {
+        let symbols = ["Cu", "H", "Cu"];
+        let xyz = [[0.0, 0.0, 0.0], [1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+        let fixed = [true, false, true];
+        let ids = [0, 1, 2];
+        let masses = [63.546, 1.008, 63.546];
+        let velocities = [[0.1, 0.0, 0.0], [0.0, 0.2, 0.0], [0.0, 0.0, 0.3]];
+
+        let mut one_by_one = ConFrameBuilder::new([10.0; 3], [90.0; 3]);
+        for i in 0..symbols.len() {
+            let [x, y, z] = xyz[i];
+            let [vx, vy, vz] = velocities[i];
+            one_by_one.add_atom_with_velocity(
+                symbols[i], x, y, z, fixed[i], ids[i], masses[i], vx, vy, vz,
+            );
+        }
+        let mut columnar = ConFrameBuilder::new([10.0; 3], [90.0; 3]);
+        columnar
+            .add_atoms_soa_with_velocity(&symbols, &xyz, &fixed, &ids, &masses, &velocities)
+            .unwrap();
+        assert_eq!(columnar.build(), one_by_one.build());
+
+        let mut builder = ConFrameBuilder::new([10.0; 3], [90.0; 3]);
+        builder
+            .add_atoms_soa(&symbols, &xyz, &fixed, &ids, &masses)
+            .unwrap();
+        let frame = builder.build();
+        assert_eq!(frame.header.natms_per_type, vec![2, 1]);
+        assert!(!frame.has_velocities());
+
+        let mut builder = ConFrameBuilder::new([10.0; 3], [90.0; 3]);
+        assert_eq!(
+            builder.add_atoms_soa(&symbols, &xyz, &fixed[..2], &ids, &masses),
+            Err(BuildError::LengthMismatch {
+                field: "fixed",
+                expected: 3,
+                found: 2
+            })
+        );
+        let err = builder
+            .add_atoms_soa_with_velocity(&symbols, &xyz, &fixed, &ids, &masses, &velocities[1..])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "velocities has 2 entries, expected one per symbol (3)"
+        );
+        assert!(builder.build().atom_data.is_empty());
+    }
+
+    #[test]
+    fn test_supercell() {
+        // Hexagonal cell: the replica along b is shifted by (-2, sqrt(12), 0).
+        let mut builder = ConFrameBuilder::new([4.0, 4.0, 10.0], [90.0, 90.0, 120.0]);
+        builder.add_atom_with_velocity("Cu", 1.0, 0.0, 0.0, true, 7, 63.546, 0.1, 0.2, 0.3);
+        builder.add_atom("H", 0.0, 1.0, 2.0, false, 3, 1.008);
+        let frame = builder.build();
+
+        let big = frame.supercell(2, 2, 1);
+        assert_eq!(big.header.boxl, [8.0, 8.0, 10.0]);
+        assert_eq!(big.header.angles, frame.header.angles);
+        assert_eq!(big.header.natms_per_type, vec![4, 4]);
+        assert_eq!(big.header.masses_per_type, frame.header.masses_per_type);
+        let ids: Vec<u64> = big.atom_data.iter().map(|a| a.atom_id).collect();
+        assert_eq!(ids, (0..8).collect::<Vec<u64>>());
+        assert_eq!(big.symbols()[..4], ["Cu"; 4]);
+        assert!(
+            big.atom_data[..4]
+                .iter()
+                .all(|a| a.is_fixed && a.vx == Some(0.1))
+        );
+
+        let cu_b = &big.atom_data[1];
+        assert!((cu_b.x + 1.0).abs() < 1e-12 && (cu_b.y - 12f64.sqrt()).abs() < 1e-12);
+        let cu_ab = &big.atom_data[3];
+        assert!((cu_ab.x - 3.0).abs() < 1e-12 && (cu_ab.y - 12f64.sqrt()).abs() < 1e-12);
+        // Every replica is a periodic image of its original in the small cell.
+        for (k, atom) in big.atom_data.iter().enumerate() {
+            let original = &frame.atom_data[k / 4];
+            let mut pair = frame.clone();
+            pair.atom_data[0].x = atom.x;
+            pair.atom_data[0].y = atom.y;
+            pair.atom_data[0].z = atom.z;
+            pair.atom_data[1] = original.clone();
+            assert!(pair.distance_mic(0, 1) < 1e-9, "replica {k}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "atom index 5 out of range")]
+    fn test_distance_checks_bounds() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("H", 0.0, 0.0, 0.0, false, 0, 1.008);
+        builder.build().distance(0, 5);
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom_with_velocity("Cu", 0.0, 0.0, 0.0, true, 0, 63.546, 0.1, 0.2, 0.3);
+        let frame = builder.build();
+
+        let mut nudged = frame.clone();
+        nudged.atom_data[0].x += 1e-7;
+        nudged.header.boxl[0] += 1e-7;
+        assert!(frame.approx_eq(&nudged, 1e-6));
+        assert!(!frame.approx_eq(&nudged, 1e-8));
+
+        let mut refixed = frame.clone();
+        refixed.atom_data[0].is_fixed = false;
+        assert!(!frame.approx_eq(&refixed, 1.0));
+
+        let mut no_velocity = frame.clone();
+        no_velocity.atom_data[0].vx = None;
+        assert!(!frame.approx_eq(&no_velocity, 1.0));
+    }
+
+    #[test]
+    fn test_builder_type_mass_overrides() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]).with_masses(
+            HashMap::from([("H".to_string(), 2.014), ("O".to_string(), 16.0)]),
+        );
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 0.0);
+        builder.add_atom("H", 1.0, 0.0, 0.0, false, 1, 1.008);
+        builder.add_atom("Cu", 2.0, 0.0, 0.0, true, 2, 63.546);
+        builder.set_type_mass("Cu", 63.546);
+        let frame = builder.build_checked().unwrap();
+        assert_eq!(frame.header.masses_per_type, vec![63.546, 2.014]);
+    }
+
+    #[test]
+    fn test_builder_basic() {
+        let mut builder = ConFrameBuilder::new([10.0, 20.0, 30.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
+        builder.add_atom("Cu", 1.0, 0.0, 0.0, true, 1, 63.546);
+        builder.add_atom("H", 2.0, 3.0, 4.0, false, 2, 1.008);
+        let frame = builder.build();
+
+        assert_eq!(frame.header.natm_types, 2);
+        assert_eq!(frame.header.natms_per_type, vec![2, 1]);
+        assert_eq!(frame.header.masses_per_type, vec![63.546, 1.008]);
+        assert_eq!(frame.atom_data.len(), 3);
+        assert_eq!(frame.atom_data[0].symbol_str(), "Cu");
+        assert_eq!(frame.atom_data[2].symbol_str(), "H");
+    }
+
+    #[test]
+    fn test_builder_with_velocities() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom_with_velocity("Cu", 0.0, 0.0, 0.0, true, 0, 63.546, 0.1, 0.2, 0.3);
+        let frame = builder.build();
+
+        assert!(frame.has_velocities());
+        assert_eq!(frame.atom_data[0].vx, Some(0.1));
+        assert_eq!(frame.atom_data[0].vy, Some(0.2));
+        assert_eq!(frame.atom_data[0].vz, Some(0.3));
     }
 
     #[test]
@@ -333,8 +2266,606 @@ mod tests {
         assert_eq!(frame.header.natm_types, 2);
         assert_eq!(frame.header.natms_per_type, vec![2, 1]);
         // Atoms should be grouped: H, H, Cu
-        assert_eq!(&*frame.atom_data[0].symbol, "H");
-        assert_eq!(&*frame.atom_data[1].symbol, "H");
-        assert_eq!(&*frame.atom_data[2].symbol, "Cu");
+        assert_eq!(frame.atom_data[0].symbol_str(), "H");
+        assert_eq!(frame.atom_data[1].symbol_str(), "H");
+        assert_eq!(frame.atom_data[2].symbol_str(), "Cu");
+    }
+
+    #[test]
+    fn test_cell_matrix_orthorhombic_is_exact_diagonal() {
+        let frame = ConFrameBuilder::new([10.0, 20.0, 30.0], [90.0, 90.0, 90.0]).build();
+        let m = frame.header.cell_matrix();
+        assert_eq!(m, [[10.0, 0.0, 0.0], [0.0, 20.0, 0.0], [0.0, 0.0, 30.0]]);
+        let inv = frame.header.cell_matrix_inverse().unwrap();
+        assert_eq!(
+            inv,
+            [[0.1, 0.0, 0.0], [0.0, 0.05, 0.0], [0.0, 0.0, 1.0 / 30.0]]
+        );
+    }
+
+    #[test]
+    fn test_cell_matrix_triclinic() {
+        let frame = ConFrameBuilder::new([3.0, 3.0, 5.0], [90.0, 90.0, 120.0]).build();
+        let m = frame.header.cell_matrix();
+        // Hexagonal cell: b is rotated 120 degrees from a in the xy plane.
+        assert!((m[1][0] - -1.5).abs() < 1e-12);
+        assert!((m[1][1] - 3.0 * 3f64.sqrt() / 2.0).abs() < 1e-12);
+        assert_eq!(m[2], [0.0, 0.0, 5.0]);
+
+        let inv = frame.header.cell_matrix_inverse().unwrap();
+        for (i, row) in m.iter().enumerate() {
+            for j in 0..3 {
+                let dot: f64 = row
+                    .iter()
+                    .zip(&inv)
+                    .map(|(r, inv_row)| r * inv_row[j])
+                    .sum();
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((dot - expected).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cell_matrix_inverse_degenerate() {
+        let frame = ConFrameBuilder::new([10.0, 0.0, 10.0], [90.0, 90.0, 90.0]).build();
+        assert!(frame.header.cell_matrix_inverse().is_none());
+    }
+
+    #[test]
+    fn test_geometric_center_and_bounding_sphere() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("H", 1.0, 0.0, 0.0, false, 0, 1.008);
+        builder.add_atom("H", -1.0, 0.0, 0.0, false, 1, 1.008);
+        builder.add_atom("O", 0.0, 2.0, 0.0, false, 2, 15.999);
+        builder.add_atom("O", 0.0, -2.0, 0.0, false, 3, 15.999);
+        let frame = builder.build();
+
+        assert_eq!(frame.geometric_center(), [0.0, 0.0, 0.0]);
+        let (center, radius) = frame.bounding_sphere();
+        assert_eq!(center, [0.0, 0.0, 0.0]);
+        assert_eq!(radius, 2.0);
+    }
+
+    #[test]
+    fn test_fractional_roundtrip_triclinic() {
+        let mut builder = ConFrameBuilder::new([4.0, 5.0, 6.0], [80.0, 95.0, 110.0]);
+        builder.add_atom("Cu", 1.0, 2.0, 3.0, false, 0, 63.546);
+        builder.add_atom("Cu", -0.5, 4.5, 5.5, false, 1, 63.546);
+        let original = builder.build();
+
+        let fracs = original.to_fractional().unwrap();
+        let mut frame = original.clone();
+        frame.from_fractional(&fracs).unwrap();
+        for (a, b) in frame.atom_data.iter().zip(&original.atom_data) {
+            assert!((a.x - b.x).abs() < 1e-12);
+            assert!((a.y - b.y).abs() < 1e-12);
+            assert!((a.z - b.z).abs() < 1e-12);
+        }
+
+        // The c lattice vector itself is fractional (0, 0, 1).
+        let c = original.header.cell_matrix()[2];
+        frame
+            .from_fractional(&[[0.0, 0.0, 1.0], [0.0, 0.0, 0.0]])
+            .unwrap();
+        assert_eq!(
+            [
+                frame.atom_data[0].x,
+                frame.atom_data[0].y,
+                frame.atom_data[0].z
+            ],
+            c
+        );
+    }
+
+    #[test]
+    fn test_fractional_errors() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 0.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 1.0, 2.0, 3.0, false, 0, 63.546);
+        let mut frame = builder.build();
+        assert_eq!(frame.to_fractional(), Err(FrameError::DegenerateCell));
+        assert_eq!(
+            frame.from_fractional(&[]),
+            Err(FrameError::AtomCountMismatch {
+                expected: 1,
+                found: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_wrap_into_cell_orthorhombic() {
+        let mut builder = ConFrameBuilder::new([10.0, 20.0, 30.0], [90.0, 90.0, 90.0]);
+        builder.add_atom_with_velocity("Cu", -1.0, 25.0, 65.0, true, 0, 63.546, 0.1, 0.2, 0.3);
+        builder.add_atom("H", 10.0, -1e-20, 5.0, false, 1, 1.008);
+        let mut frame = builder.build();
+
+        let mut kept_fixed = frame.clone();
+        kept_fixed
+            .wrap_into_cell_opts(&WrapOptions {
+                include_fixed: false,
+            })
+            .unwrap();
+        assert_eq!(kept_fixed.atom_data[0], frame.atom_data[0]);
+
+        frame.wrap_into_cell().unwrap();
+        let cu = &frame.atom_data[0];
+        assert_eq!([cu.x, cu.y, cu.z], [9.0, 5.0, 5.0]);
+        assert_eq!((cu.vx, cu.vy, cu.vz), (Some(0.1), Some(0.2), Some(0.3)));
+        let h = &frame.atom_data[1];
+        assert_eq!([h.x, h.y, h.z], [0.0, 0.0, 5.0]);
+        assert_eq!(kept_fixed.atom_data[1], frame.atom_data[1]);
+    }
+
+    #[test]
+    fn test_wrap_into_cell_triclinic() {
+        let mut builder = ConFrameBuilder::new([4.0, 5.0, 6.0], [80.0, 95.0, 110.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, false, 0, 63.546);
+        let mut frame = builder.build();
+        frame.from_fractional(&[[-0.25, 1.5, 2.75]]).unwrap();
+        frame.wrap_into_cell().unwrap();
+        let frac = frame.to_fractional().unwrap()[0];
+        for (got, want) in frac.iter().zip([0.75, 0.5, 0.75]) {
+            assert!((got - want).abs() < 1e-12);
+        }
+        assert!(frac.iter().all(|f| (0.0..1.0).contains(f)));
+
+        let mut flat = ConFrameBuilder::new([4.0, 5.0, 0.0], [90.0, 90.0, 90.0]);
+        flat.add_atom("Cu", 0.0, 0.0, 0.0, false, 0, 63.546);
+        assert_eq!(
+            flat.build().wrap_into_cell(),
+            Err(FrameError::DegenerateCell)
+        );
+    }
+
+    #[test]
+    fn test_per_atom_masses_and_center_of_mass() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 3.0);
+        builder.add_atom("H", 4.0, 0.0, 0.0, false, 1, 1.0);
+        builder.add_atom("H", 4.0, 8.0, 0.0, false, 2, 1.0);
+        let frame = builder.build();
+        assert_eq!(frame.header.per_atom_masses(), vec![3.0, 1.0, 1.0]);
+        assert!(frame.header.has_masses());
+        assert_eq!(frame.center_of_mass(), [1.6, 1.6, 0.0]);
+
+        let mut massless = frame.clone();
+        massless.header.masses_per_type = vec![0.0, 0.0];
+        assert!(!massless.header.has_masses());
+        assert_eq!(massless.center_of_mass(), massless.geometric_center());
+    }
+
+    #[test]
+    fn test_close_contacts() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 1.0, 1.0, 1.0, false, 0, 63.546);
+        builder.add_atom("Cu", 1.1, 1.0, 1.0, false, 1, 63.546);
+        builder.add_atom("Cu", 5.0, 5.0, 5.0, false, 2, 63.546);
+        // Overlaps atom 2 only through the periodic boundary along z.
+        builder.add_atom("Cu", 5.0, 5.0, 0.02, false, 3, 63.546);
+        builder.add_atom("Cu", 5.0, 5.0, 9.98, false, 4, 63.546);
+        let frame = builder.build();
+
+        let contacts = frame.close_contacts(0.5);
+        assert_eq!(contacts.len(), 2);
+        assert_eq!((contacts[0].0, contacts[0].1), (0, 1));
+        assert!((contacts[0].2 - 0.1).abs() < 1e-12);
+        assert_eq!((contacts[1].0, contacts[1].1), (3, 4));
+        assert!((contacts[1].2 - 0.04).abs() < 1e-12);
+
+        assert!(frame.close_contacts(0.01).is_empty());
+    }
+
+    #[test]
+    fn test_neighbor_list_matches_brute_force() {
+        // A skewed cell with pseudo-random atoms, large enough for a 3x3x3 grid.
+        let mut builder = ConFrameBuilder::new([9.0, 10.0, 11.0], [80.0, 100.0, 115.0]);
+        let mut seed: u64 = 42;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        for id in 0..60 {
+            let (x, y, z) = (next() * 12.0 - 4.0, next() * 10.0, next() * 11.0);
+            builder.add_atom_auto_mass(if id % 3 == 0 { "O" } else { "H" }, x, y, z, false, id);
+        }
+        let frame = builder.build();
+
+        for cutoff in [0.5, 2.5, 4.0, 20.0] {
+            let n = frame.atom_data.len();
+            let expected: Vec<(usize, usize)> = (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                .filter(|&(i, j)| frame.distance_mic(i, j) < cutoff)
+                .collect();
+            let found = frame.neighbor_list(cutoff);
+            let pairs: Vec<(usize, usize)> = found.iter().map(|&(i, j, _)| (i, j)).collect();
+            assert_eq!(pairs, expected, "cutoff {cutoff}");
+            assert!(found.iter().all(|&(i, j, d)| d == frame.distance_mic(i, j)));
+        }
+        assert!(frame.neighbor_list(0.0).is_empty());
+    }
+
+    #[test]
+    fn test_build_with_permutation() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        let charges = [-0.8, 0.4, -0.8, 0.4, 0.4];
+        for (i, symbol) in ["O", "H", "O", "H", "H"].into_iter().enumerate() {
+            builder.add_atom_auto_mass(symbol, i as f64, 0.0, 0.0, false, i as u64);
+        }
+        let (frame, perm) = builder.build_with_permutation();
+        assert_eq!(perm, vec![0, 2, 1, 3, 4]);
+        assert_eq!(frame.symbols(), ["O", "O", "H", "H", "H"]);
+        for (atom, &i) in frame.atom_data.iter().zip(&perm) {
+            assert_eq!(atom.x, i as f64);
+        }
+        let reordered: Vec<f64> = perm.iter().map(|&i| charges[i]).collect();
+        assert_eq!(reordered, vec![-0.8, -0.8, 0.4, 0.4, 0.4]);
+    }
+
+    #[test]
+    fn test_build_preserving_order() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("O", 0.0, 0.0, 0.0, false, 0, 15.999);
+        builder.add_atom("H", 1.0, 0.0, 0.0, false, 1, 1.008);
+        builder.add_atom("H", 0.0, 1.0, 0.0, false, 2, 1.008);
+        builder.add_atom("O", 5.0, 5.0, 5.0, false, 3, 16.0);
+        builder.add_atom("H", 6.0, 5.0, 5.0, false, 4, 1.008);
+        let frame = builder.build_preserving_order();
+
+        assert_eq!(frame.symbols(), ["O", "H", "H", "O", "H"]);
+        let ids: Vec<u64> = frame.atom_data.iter().map(|a| a.atom_id).collect();
+        assert_eq!(ids, vec![0, 1, 2, 3, 4]);
+        assert_eq!(frame.header.natm_types, 4);
+        assert_eq!(frame.header.natms_per_type, vec![1, 2, 1, 1]);
+        assert_eq!(
+            frame.header.masses_per_type,
+            vec![15.999, 1.008, 15.999, 1.008]
+        );
+        assert!(Rc::ptr_eq(
+            &frame.atom_data[0].symbol,
+            &frame.atom_data[3].symbol
+        ));
+
+        // The repeated blocks survive a trip through the writer and parser.
+        let text = frame.to_con_string(6);
+        let parsed = crate::parser::parse_single_frame(&mut text.lines()).unwrap();
+        assert!(parsed.approx_eq(&frame, 1e-6));
+        assert_eq!(parsed.header.natms_per_type, frame.header.natms_per_type);
+    }
+
+    #[test]
+    fn test_bonds() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom_auto_mass("O", 0.0, 0.0, 0.0, false, 0);
+        builder.add_atom_auto_mass("H", 0.96, 0.0, 0.0, false, 1);
+        // Bonded to the oxygen through the periodic boundary.
+        builder.add_atom_auto_mass("H", 0.0, 9.04, 0.0, false, 2);
+        builder.add_atom_auto_mass("H", 5.0, 5.0, 5.0, false, 3);
+        builder.add_atom_auto_mass("X", 5.5, 5.0, 5.0, false, 4);
+        let frame = builder.build();
+
+        let bonds: Vec<(usize, usize)> = frame.bonds(1.2).iter().map(|&(i, j, _)| (i, j)).collect();
+        assert_eq!(bonds, vec![(0, 1), (0, 2)]);
+        // The H-H distance of about 1.36 exceeds 2 * 0.31 * 1.2.
+        assert!(frame.bonds(0.5).is_empty());
+    }
+
+    #[test]
+    fn test_trajectory_indexing_and_slicing() {
+        let frames: Vec<ConFrame> = (0..3)
+            .map(|i| {
+                let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+                builder.add_atom("Cu", i as f64, 0.0, 0.0, false, 0, 63.546);
+                builder.build()
+            })
+            .collect();
+        let mut traj = Trajectory::from_frames(frames.clone());
+
+        assert_eq!(traj.len(), 3);
+        assert_eq!(traj[1], frames[1]);
+        assert_eq!(&traj[1..], &frames[1..]);
+        assert_eq!(traj.last(), frames.last());
+
+        for frame in traj.iter_mut() {
+            frame.atom_data[0].y = 1.0;
+        }
+        traj[2].atom_data[0].z = 2.0;
+        assert!(traj.iter().all(|f| f.atom_data[0].y == 1.0));
+
+        let xs: Vec<f64> = (&traj).into_iter().map(|f| f.atom_data[0].x).collect();
+        assert_eq!(xs, vec![0.0, 1.0, 2.0]);
+        let back = traj.into_frames();
+        assert_eq!(back[2].atom_data[0].z, 2.0);
+        assert_eq!(back.into_iter().collect::<Trajectory>().len(), 3);
+    }
+
+    #[test]
+    fn test_bounding_box_and_extent() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 1.0, -2.0, 3.0, false, 0, 63.546);
+        builder.add_atom("H", 4.0, 0.5, -1.0, false, 1, 1.008);
+        builder.add_atom("H", 2.0, 6.0, 0.0, false, 2, 1.008);
+        let frame = builder.build();
+        assert_eq!(frame.bounding_box(), ([1.0, -2.0, -1.0], [4.0, 6.0, 3.0]));
+        assert_eq!(frame.extent(), [3.0, 8.0, 4.0]);
+
+        let empty = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]).build();
+        assert_eq!(empty.bounding_box(), ([0.0; 3], [0.0; 3]));
+        assert_eq!(empty.extent(), [0.0; 3]);
+    }
+
+    #[test]
+    fn test_atoms_by_type_after_grouping() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
+        builder.add_atom("H", 1.0, 0.0, 0.0, false, 1, 1.008);
+        builder.add_atom("Cu", 2.0, 0.0, 0.0, true, 2, 63.546);
+        builder.add_atom("O", 3.0, 0.0, 0.0, false, 3, 15.999);
+        builder.add_atom("H", 4.0, 0.0, 0.0, false, 4, 1.008);
+        let frame = builder.build();
+
+        let groups: Vec<(&str, Vec<u64>)> = frame
+            .atoms_by_type()
+            .map(|(sym, atoms)| (sym, atoms.iter().map(|a| a.atom_id).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![("Cu", vec![0, 2]), ("H", vec![1, 4]), ("O", vec![3])]
+        );
+        for (sym, atoms) in frame.atoms_by_type() {
+            assert!(atoms.iter().all(|a| a.symbol_str() == sym));
+        }
+    }
+
+    #[test]
+    fn test_symbols_match_rc_symbols() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("H", 1.0, 0.0, 0.0, false, 0, 1.008);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 1, 63.546);
+        builder.add_atom("H", 2.0, 0.0, 0.0, false, 2, 1.008);
+        let frame = builder.build();
+
+        let manual: Vec<&str> = frame.atom_data.iter().map(AtomDatum::symbol_str).collect();
+        assert_eq!(frame.symbols(), manual);
+        assert_eq!(frame.symbols(), vec!["H", "H", "Cu"]);
+        assert_eq!(frame.atom_data[2].symbol_str(), "Cu");
+    }
+
+    #[test]
+    fn test_merge_frames() {
+        let mut a = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        a.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
+        a.add_atom("H", 1.0, 0.0, 0.0, false, 1, 1.008);
+        let a = a.build();
+        let mut b = ConFrameBuilder::new([10.0, 10.0, 10.0 + 1e-9], [90.0, 90.0, 90.0]);
+        b.add_atom_with_velocity("H", 2.0, 0.0, 0.0, false, 0, 1.008, 0.1, 0.2, 0.3);
+        b.add_atom("O", 3.0, 0.0, 0.0, false, 1, 15.999);
+        let b = b.build();
+
+        let merged = ConFrame::merge(&[a.clone(), b.clone()]).unwrap();
+        assert_eq!(merged.header.natms_per_type, vec![1, 2, 1]);
+        assert_eq!(merged.header.masses_per_type, vec![63.546, 1.008, 15.999]);
+        assert_eq!(merged.symbols(), vec!["Cu", "H", "H", "O"]);
+        let ids: Vec<u64> = merged.atom_data.iter().map(|at| at.atom_id).collect();
+        assert_eq!(ids, vec![0, 1, 2, 3]);
+        assert_eq!(merged.atom_data[2].vx, Some(0.1));
+
+        let options = MergeOptions {
+            preserve_ids: true,
+            ..MergeOptions::default()
+        };
+        let kept = ConFrame::merge_with_options(&[a.clone(), b], &options).unwrap();
+        let ids: Vec<u64> = kept.atom_data.iter().map(|at| at.atom_id).collect();
+        assert_eq!(ids, vec![0, 1, 0, 1]);
+
+        let mut other_cell = a.clone();
+        other_cell.header.angles[2] = 120.0;
+        assert_eq!(
+            ConFrame::merge(&[a, other_cell]),
+            Err(FrameError::CellMismatch { frame: 1 })
+        );
+        assert_eq!(ConFrame::merge(&[]), Err(FrameError::NoFrames));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json_roundtrip() {
+        let mut builder = ConFrameBuilder::new([10.0, 11.0, 12.0], [90.0, 95.0, 90.0])
+            .prebox_header(["seed".to_string(), "time".to_string()]);
+        builder.add_atom_with_velocity("Cu", 0.5, 1.5, 2.5, true, 0, 63.546, 0.1, -0.2, 0.3);
+        builder.add_atom("H", 3.0, 4.0, 5.0, false, 1, 1.008);
+        let frame = builder.build();
+
+        let json = serde_json::to_string(&frame).unwrap();
+        assert!(json.contains(r#""symbol":"Cu""#));
+        assert!(json.contains(r#""vx":null"#));
+        let back: ConFrame = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, frame);
+
+        let traj = Trajectory::from_frames(vec![frame.clone(), frame]);
+        let json = serde_json::to_string(&traj).unwrap();
+        assert!(json.starts_with('['));
+        assert_eq!(serde_json::from_str::<Trajectory>(&json).unwrap(), traj);
+    }
+
+    fn validation_frame() -> ConFrame {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
+        builder.add_atom("H", 1.0, 0.0, 0.0, false, 1, 1.008);
+        builder.add_atom("H", 2.0, 0.0, 0.0, false, 2, 1.008);
+        builder.build()
+    }
+
+    #[test]
+    fn test_validation_issues() {
+        let frame = validation_frame();
+        assert!(frame.validation_issues().is_empty());
+
+        let mut broken = frame.clone();
+        broken.header.masses_per_type.pop();
+        broken.atom_data[2].symbol = Rc::new("O".to_string());
+        broken.atom_data[0].vx = Some(0.0);
+        broken.atom_data[0].vy = Some(0.0);
+        broken.atom_data[0].vz = Some(f64::NAN);
+        assert_eq!(
+            broken.validation_issues(),
+            vec![
+                ValidationIssue::TypeCountMismatch {
+                    natm_types: 2,
+                    natms_per_type: 2,
+                    masses_per_type: 1
+                },
+                ValidationIssue::MixedSymbols {
+                    type_index: 1,
+                    atom_index: 2
+                },
+                ValidationIssue::PartialVelocities {
+                    with: 1,
+                    without: 2
+                },
+                ValidationIssue::NonFiniteValue { atom_index: 0 },
+            ]
+        );
+
+        let mut short = frame;
+        short.atom_data.pop();
+        short.header.boxl[0] = -1.0;
+        assert_eq!(
+            short.validation_issues(),
+            vec![
+                ValidationIssue::InvalidCell,
+                ValidationIssue::AtomCountMismatch {
+                    expected: 3,
+                    found: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let frame = validation_frame();
+        assert_eq!(frame.validate(), Ok(()));
+
+        let mut broken = frame;
+        broken.header.natms_per_type = vec![1, 1];
+        broken.header.masses_per_type.push(1.0);
+        let err = broken.validate().unwrap_err();
+        assert_eq!(err.issues, broken.validation_issues());
+        assert_eq!(err.issues.len(), 2);
+        assert_eq!(
+            err.to_string(),
+            "frame is inconsistent: natm_types is 2 but there are 2 atom counts and 3 masses; \
+             header lists 2 atoms, frame holds 3"
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_validate_trajectory_parallel() {
+        let mut frames = vec![validation_frame(); 4];
+        frames[2].header.natms_per_type = vec![1, 1];
+        let report = validate_trajectory_parallel(&frames);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].0, 2);
+        assert_eq!(
+            report[0].1,
+            vec![ValidationIssue::AtomCountMismatch {
+                expected: 2,
+                found: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cell_accessors_and_reciprocal_cell() {
+        let frame = ConFrameBuilder::new([2.0, 4.0, 5.0], [90.0, 90.0, 90.0]).build();
+        assert_eq!(frame.cell_lengths(), [2.0, 4.0, 5.0]);
+        assert_eq!(frame.cell_angles(), [90.0, 90.0, 90.0]);
+        assert_eq!(
+            frame.reciprocal_cell(),
+            Some([[0.5, 0.0, 0.0], [0.0, 0.25, 0.0], [0.0, 0.0, 0.2]])
+        );
+
+        let triclinic = ConFrameBuilder::new([4.0, 5.0, 6.0], [80.0, 95.0, 110.0]).build();
+        let cell = triclinic.header.cell_matrix();
+        let recip = triclinic.reciprocal_cell().unwrap();
+        for (i, a) in cell.iter().enumerate() {
+            for (j, b) in recip.iter().enumerate() {
+                let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((dot - expected).abs() < 1e-12);
+            }
+        }
+
+        let flat = ConFrameBuilder::new([2.0, 4.0, 0.0], [90.0, 90.0, 90.0]).build();
+        assert_eq!(flat.reciprocal_cell(), None);
+    }
+
+    #[test]
+    fn test_append_atoms_from() {
+        let mut cu = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        cu.add_atom("Cu", 0.0, 0.0, 0.0, true, 0, 63.546);
+        cu.add_atom("Cu", 2.5, 0.0, 0.0, true, 1, 63.546);
+        cu.add_atom("H", 5.0, 5.0, 5.0, false, 2, 1.008);
+        let mut frame = cu.build();
+
+        let mut h = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        h.add_atom("H", 1.0, 1.0, 1.0, false, 3, 1.008);
+        h.add_atom("O", 2.0, 2.0, 2.0, false, 4, 15.999);
+        let h = h.build();
+
+        frame.append_atoms_from(&h).unwrap();
+        assert_eq!(frame.header.natm_types, 3);
+        assert_eq!(frame.header.natms_per_type, vec![2, 2, 1]);
+        assert_eq!(frame.header.masses_per_type, vec![63.546, 1.008, 15.999]);
+        assert_eq!(frame.symbols(), vec!["Cu", "Cu", "H", "H", "O"]);
+        let ids: Vec<u64> = frame.atom_data.iter().map(|a| a.atom_id).collect();
+        assert_eq!(ids, vec![0, 1, 2, 3, 4]);
+        assert!(Rc::ptr_eq(
+            &frame.atom_data[2].symbol,
+            &frame.atom_data[3].symbol
+        ));
+        assert!(frame.validation_issues().is_empty());
+
+        let other_cell = ConFrameBuilder::new([12.0, 10.0, 10.0], [90.0, 90.0, 90.0]).build();
+        assert_eq!(
+            frame.append_atoms_from(&other_cell),
+            Err(FrameError::CellMismatch { frame: 1 })
+        );
+    }
+
+    #[test]
+    fn test_sort_by_id_with_remap() {
+        let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+        builder.add_atom("Cu", 0.0, 0.0, 0.0, true, 5, 63.546);
+        builder.add_atom("Cu", 1.0, 0.0, 0.0, true, 2, 63.546);
+        builder.add_atom("H", 2.0, 0.0, 0.0, false, 4, 1.008);
+        builder.add_atom("H", 3.0, 0.0, 0.0, false, 0, 1.008);
+        builder.add_atom("H", 4.0, 0.0, 0.0, false, 1, 1.008);
+        let original = builder.build();
+
+        let mut frame = original.clone();
+        let perm = frame.sort_by_id_with_remap();
+        assert_eq!(perm, vec![1, 0, 3, 4, 2]);
+
+        // Types stay grouped, ids ascend within each block.
+        let ids: Vec<u64> = frame.atom_data.iter().map(|a| a.atom_id).collect();
+        assert_eq!(ids, vec![2, 5, 0, 1, 4]);
+        assert_eq!(frame.header, original.header);
+
+        // perm[new] == old reconstructs the sorted frame from the original...
+        for (new, &old) in perm.iter().enumerate() {
+            assert_eq!(frame.atom_data[new], original.atom_data[old]);
+        }
+        // ...and inverting it recovers the original order.
+        let mut restored = vec![None; perm.len()];
+        for (new, &old) in perm.iter().enumerate() {
+            restored[old] = Some(frame.atom_data[new].clone());
+        }
+        let restored: Vec<AtomDatum> = restored.into_iter().map(Option::unwrap).collect();
+        assert_eq!(restored, original.atom_data);
     }
 }
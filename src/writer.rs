@@ -1,5 +1,7 @@
-use crate::types::ConFrame;
-use std::fs::File;
+#[cfg(feature = "serde")]
+use crate::types::json;
+use crate::types::{AtomDatum, ConFrame, ConFrameBuilder, FrameHeader};
+use std::fs::{File, OpenOptions};
 use std::io::{self, BufWriter, Write};
 use std::path::Path;
 
@@ -11,11 +13,169 @@ const FIXED_ATOM_FLAG: usize = 1;
 /// The value used to indicate a non-fixed (free) atom in the output file.
 const FREE_ATOM_FLAG: usize = 0;
 
+/// Horizontal alignment of coordinate and velocity fields within their width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    /// Pad on the right.
+    Left,
+    /// Pad on the left, so decimal points line up in fixed-width columns.
+    #[default]
+    Right,
+}
+
+/// How coordinate and velocity fields render numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Notation {
+    /// Fixed decimal places, e.g. `0.000001`.
+    #[default]
+    Fixed,
+    /// Scientific notation with `precision` mantissa digits, e.g. `1.000000e-6`.
+    Scientific,
+    /// Fixed, switching to scientific when the decimal exponent is below -4 or
+    /// at least `precision` (the same rule as C's `%g`).
+    Adaptive,
+}
+
+/// Layout options for [`ConFrameWriter`].
+///
+/// The default reproduces the writer's standard output: six decimal places
+/// and single-space separated fields with no padding. Start from
+/// `FormatOptions::default()` and override fields with the setters:
+///
+/// ```
+/// use readcon_core::writer::{FormatOptions, Notation};
+///
+/// let format = FormatOptions::default().precision(3).notation(Notation::Scientific);
+/// assert_eq!(format.coord_width, 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FormatOptions {
+    /// Number of decimal places for floating-point output.
+    pub precision: usize,
+    /// Minimum width of each coordinate and velocity field. `0` disables
+    /// padding.
+    pub coord_width: usize,
+    /// Alignment of coordinate and velocity fields within `coord_width`.
+    pub align: Align,
+    /// Decimal or scientific rendering of coordinate and velocity fields.
+    pub notation: Notation,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            precision: DEFAULT_FLOAT_PRECISION,
+            coord_width: 0,
+            align: Align::default(),
+            notation: Notation::default(),
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Sets the number of decimal places.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Sets the minimum coordinate and velocity field width.
+    pub fn coord_width(mut self, coord_width: usize) -> Self {
+        self.coord_width = coord_width;
+        self
+    }
+
+    /// Sets the alignment within `coord_width`.
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets how numbers are rendered.
+    pub fn notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    /// Writes one coordinate or velocity component using these options.
+    fn write_coord<W: Write>(&self, w: &mut W, value: f64) -> io::Result<()> {
+        let (width, prec) = (self.coord_width, self.precision);
+        let scientific = match self.notation {
+            Notation::Fixed => false,
+            Notation::Scientific => true,
+            Notation::Adaptive => {
+                let exponent = value.abs().log10().floor();
+                value != 0.0
+                    && value.is_finite()
+                    && (exponent < -4.0 || exponent >= prec.max(1) as f64)
+            }
+        };
+        match (self.align, scientific) {
+            (Align::Left, false) => write!(w, "{value:<width$.prec$}"),
+            (Align::Right, false) => write!(w, "{value:>width$.prec$}"),
+            (Align::Left, true) => write!(w, "{value:<width$.prec$e}"),
+            (Align::Right, true) => write!(w, "{value:>width$.prec$e}"),
+        }
+    }
+
+    /// Writes an atom line: three components, the fixed flag and the atom id.
+    fn write_atom_line<W: Write>(
+        &self,
+        w: &mut W,
+        xyz: [f64; 3],
+        is_fixed: bool,
+        atom_id: u64,
+    ) -> io::Result<()> {
+        for (i, value) in xyz.into_iter().enumerate() {
+            if i > 0 {
+                w.write_all(b" ")?;
+            }
+            self.write_coord(w, value)?;
+        }
+        let fixed_flag = if is_fixed {
+            FIXED_ATOM_FLAG
+        } else {
+            FREE_ATOM_FLAG
+        };
+        write!(w, " {fixed_flag} {atom_id}")
+    }
+}
+
+/// The fields of one atom that the writer reads, borrowed without the `Rc`
+/// symbol so frames can be formatted on other threads.
+#[derive(Clone, Copy)]
+struct AtomFields<'a> {
+    symbol: &'a str,
+    xyz: [f64; 3],
+    vel: [Option<f64>; 3],
+    is_fixed: bool,
+    atom_id: u64,
+    extra_columns: &'a [f64],
+}
+
+impl<'a> AtomFields<'a> {
+    fn of(atom: &'a AtomDatum) -> Self {
+        AtomFields {
+            symbol: atom.symbol_str(),
+            xyz: [atom.x, atom.y, atom.z],
+            vel: [atom.vx, atom.vy, atom.vz],
+            is_fixed: atom.is_fixed,
+            atom_id: atom.atom_id,
+            extra_columns: &atom.extra_columns,
+        }
+    }
+}
+
 /// A writer that can serialize and write `ConFrame` objects to any output stream.
 ///
 /// This struct encapsulates a writer (like a file) and provides a high-level API
 /// for writing simulation frames in the `.con` format.
 ///
+/// Output is buffered. Dropping the writer flushes the buffer but has no way
+/// to report a failure, so call [`flush`](Self::flush) or
+/// [`finish`](Self::finish) when write errors must be seen.
+///
 /// # Example
 /// ```no_run
 /// # use std::fs::File;
@@ -27,7 +187,9 @@ const FREE_ATOM_FLAG: usize = 0;
 /// ```
 pub struct ConFrameWriter<W: Write> {
     writer: BufWriter<W>,
-    precision: usize,
+    format: FormatOptions,
+    frame_separator: String,
+    frames_written: usize,
 }
 
 // General implementation for any type that implements `Write`.
@@ -38,10 +200,7 @@ impl<W: Write> ConFrameWriter<W> {
     ///
     /// * `writer` - Any type that implements `std::io::Write`, e.g., a `File`.
     pub fn new(writer: W) -> Self {
-        Self {
-            writer: BufWriter::new(writer),
-            precision: DEFAULT_FLOAT_PRECISION,
-        }
+        Self::with_format(writer, FormatOptions::default())
     }
 
     /// Creates a new `ConFrameWriter` with a custom floating-point precision.
@@ -51,43 +210,140 @@ impl<W: Write> ConFrameWriter<W> {
     /// * `writer` - Any type that implements `std::io::Write`.
     /// * `precision` - Number of decimal places for floating-point output.
     pub fn with_precision(writer: W, precision: usize) -> Self {
+        Self::with_format(writer, FormatOptions::default().precision(precision))
+    }
+
+    /// Creates a new `ConFrameWriter` with full control over the layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Any type that implements `std::io::Write`.
+    /// * `format` - Precision, field width and alignment; see [`FormatOptions`].
+    pub fn with_format(writer: W, format: FormatOptions) -> Self {
         Self {
             writer: BufWriter::new(writer),
-            precision,
+            format,
+            frame_separator: String::new(),
+            frames_written: 0,
         }
     }
 
+    /// Sets text written verbatim between consecutive frames (default empty).
+    ///
+    /// For example, `"\n"` puts a blank line between frames. The standard
+    /// reader treats a blank line after the coordinate blocks as the start of
+    /// a velocity section, so files written with a separator should be read
+    /// with `ParseOptions::skip_blank_lines` enabled.
+    pub fn set_frame_separator(&mut self, separator: String) {
+        self.frame_separator = separator;
+    }
+
+    /// Flushes buffered output to the underlying writer.
+    ///
+    /// Dropping the writer also flushes, but silently discards any error;
+    /// call this to observe write failures.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Flushes buffered output and returns the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from the final flush, in which case the underlying
+    /// writer is dropped.
+    pub fn finish(self) -> io::Result<W> {
+        self.writer.into_inner().map_err(|e| e.into_error())
+    }
+
+    /// Finishes the current output and moves on to `writer`, keeping the
+    /// format options and frame separator.
+    ///
+    /// The new output starts fresh, so no separator precedes its first frame.
+    /// This suits writing many single-frame files with one configuration:
+    ///
+    /// ```no_run
+    /// # use std::fs::File;
+    /// # use readcon_core::types::ConFrame;
+    /// # use readcon_core::writer::{ConFrameWriter, FormatOptions};
+    /// # let frames: Vec<ConFrame> = Vec::new();
+    /// let format = FormatOptions::default().precision(10);
+    /// let mut writer = ConFrameWriter::with_format(File::create("frame_00000.con")?, format);
+    /// for (i, frame) in frames.iter().enumerate() {
+    ///     if i > 0 {
+    ///         let file = File::create(format!("frame_{i:05}.con"))?;
+    ///         (writer, _) = writer.rebind(file)?;
+    ///     }
+    ///     writer.write_frame(frame)?;
+    /// }
+    /// writer.finish()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from flushing the current output, as
+    /// [`finish`](Self::finish) does; `writer` is dropped in that case.
+    pub fn rebind<W2: Write>(self, writer: W2) -> io::Result<(ConFrameWriter<W2>, W)> {
+        let ConFrameWriter {
+            writer: old,
+            format,
+            frame_separator,
+            ..
+        } = self;
+        let old = old.into_inner().map_err(|e| e.into_error())?;
+        let mut rebound = ConFrameWriter::with_format(writer, format);
+        rebound.frame_separator = frame_separator;
+        Ok((rebound, old))
+    }
+
     /// Writes a single `ConFrame` to the output stream.
     pub fn write_frame(&mut self, frame: &ConFrame) -> io::Result<()> {
-        let prec = self.precision;
+        self.write_frame_fields(&frame.header, frame.has_velocities(), |i| {
+            AtomFields::of(&frame.atom_data[i])
+        })
+    }
+
+    /// Writes one frame from its header and per-atom fields, where `atom(i)`
+    /// gives the fields of atom `i`.
+    fn write_frame_fields<'f>(
+        &mut self,
+        header: &FrameHeader,
+        has_velocities: bool,
+        atom: impl Fn(usize) -> AtomFields<'f>,
+    ) -> io::Result<()> {
+        let prec = self.format.precision;
+
+        if self.frames_written > 0 {
+            self.writer.write_all(self.frame_separator.as_bytes())?;
+        }
+        self.frames_written += 1;
 
         // --- Write the 9-line Header ---
-        writeln!(self.writer, "{}", frame.header.prebox_header[0])?;
-        writeln!(self.writer, "{}", frame.header.prebox_header[1])?;
+        writeln!(self.writer, "{}", header.prebox_header[0])?;
+        writeln!(self.writer, "{}", header.prebox_header[1])?;
         writeln!(
             self.writer,
             "{1:.0$} {2:.0$} {3:.0$}",
-            prec, frame.header.boxl[0], frame.header.boxl[1], frame.header.boxl[2]
+            prec, header.boxl[0], header.boxl[1], header.boxl[2]
         )?;
         writeln!(
             self.writer,
             "{1:.0$} {2:.0$} {3:.0$}",
-            prec, frame.header.angles[0], frame.header.angles[1], frame.header.angles[2]
+            prec, header.angles[0], header.angles[1], header.angles[2]
         )?;
-        writeln!(self.writer, "{}", frame.header.postbox_header[0])?;
-        writeln!(self.writer, "{}", frame.header.postbox_header[1])?;
-        writeln!(self.writer, "{}", frame.header.natm_types)?;
+        writeln!(self.writer, "{}", header.postbox_header[0])?;
+        writeln!(self.writer, "{}", header.postbox_header[1])?;
+        writeln!(self.writer, "{}", header.natm_types)?;
 
-        let natms_str: Vec<String> = frame
-            .header
+        let natms_str: Vec<String> = header
             .natms_per_type
             .iter()
             .map(|n| n.to_string())
             .collect();
         writeln!(self.writer, "{}", natms_str.join(" "))?;
 
-        let masses_str: Vec<String> = frame
-            .header
+        let masses_str: Vec<String> = header
             .masses_per_type
             .iter()
             .map(|m| format!("{:.1$}", m, prec))
@@ -96,58 +352,46 @@ impl<W: Write> ConFrameWriter<W> {
 
         // --- Write the Atom Data ---
         let mut atom_idx_offset = 0;
-        for (type_idx, &num_atoms_in_type) in frame.header.natms_per_type.iter().enumerate() {
-            let symbol = &frame.atom_data[atom_idx_offset].symbol;
-            writeln!(self.writer, "{}", symbol)?;
+        for (type_idx, &num_atoms_in_type) in header.natms_per_type.iter().enumerate() {
+            writeln!(self.writer, "{}", atom(atom_idx_offset).symbol)?;
             writeln!(self.writer, "Coordinates of Component {}", type_idx + 1)?;
 
             for i in 0..num_atoms_in_type {
-                let atom = &frame.atom_data[atom_idx_offset + i];
-                writeln!(
-                    self.writer,
-                    "{x:.prec$} {y:.prec$} {z:.prec$} {fixed_flag:.0} {atom_id}",
-                    prec = prec,
-                    x = atom.x,
-                    y = atom.y,
-                    z = atom.z,
-                    fixed_flag = if atom.is_fixed {
-                        FIXED_ATOM_FLAG
-                    } else {
-                        FREE_ATOM_FLAG
-                    },
-                    atom_id = atom.atom_id
+                let atom = atom(atom_idx_offset + i);
+                self.format.write_atom_line(
+                    &mut self.writer,
+                    atom.xyz,
+                    atom.is_fixed,
+                    atom.atom_id,
                 )?;
+                // Extra per-atom columns (e.g. charges) follow the atom ID.
+                for extra in atom.extra_columns {
+                    write!(self.writer, " {extra:.prec$}")?;
+                }
+                writeln!(self.writer)?;
             }
             atom_idx_offset += num_atoms_in_type;
         }
 
         // --- Write optional velocity section ---
-        if frame.has_velocities() {
+        if has_velocities {
             // Blank separator line between coordinates and velocities
             writeln!(self.writer)?;
 
             let mut vel_idx_offset = 0;
-            for (type_idx, &num_atoms_in_type) in frame.header.natms_per_type.iter().enumerate() {
-                let symbol = &frame.atom_data[vel_idx_offset].symbol;
-                writeln!(self.writer, "{}", symbol)?;
+            for (type_idx, &num_atoms_in_type) in header.natms_per_type.iter().enumerate() {
+                writeln!(self.writer, "{}", atom(vel_idx_offset).symbol)?;
                 writeln!(self.writer, "Velocities of Component {}", type_idx + 1)?;
 
                 for i in 0..num_atoms_in_type {
-                    let atom = &frame.atom_data[vel_idx_offset + i];
-                    writeln!(
-                        self.writer,
-                        "{vx:.prec$} {vy:.prec$} {vz:.prec$} {fixed_flag:.0} {atom_id}",
-                        prec = prec,
-                        vx = atom.vx.unwrap_or(0.0),
-                        vy = atom.vy.unwrap_or(0.0),
-                        vz = atom.vz.unwrap_or(0.0),
-                        fixed_flag = if atom.is_fixed {
-                            FIXED_ATOM_FLAG
-                        } else {
-                            FREE_ATOM_FLAG
-                        },
-                        atom_id = atom.atom_id
+                    let atom = atom(vel_idx_offset + i);
+                    self.format.write_atom_line(
+                        &mut self.writer,
+                        atom.vel.map(|v| v.unwrap_or(0.0)),
+                        atom.is_fixed,
+                        atom.atom_id,
                     )?;
+                    writeln!(self.writer)?;
                 }
                 vel_idx_offset += num_atoms_in_type;
             }
@@ -156,6 +400,45 @@ impl<W: Write> ConFrameWriter<W> {
         Ok(())
     }
 
+    /// Writes a frame containing only the atoms of `frame` at the indices in
+    /// `keep`.
+    ///
+    /// The kept atoms are regrouped by symbol through [`ConFrameBuilder`], so
+    /// `natm_types`, `natms_per_type` and `masses_per_type` describe only the
+    /// surviving atoms. Headers, cell, atom IDs, velocities and extra columns
+    /// are preserved. Within a type, atoms appear in the order given by `keep`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if an index is not less than
+    /// `frame.atom_data.len()` or appears more than once in `keep`; nothing is
+    /// written in that case.
+    pub fn write_frame_subset(&mut self, frame: &ConFrame, keep: &[usize]) -> io::Result<()> {
+        let natoms = frame.atom_data.len();
+        let mut seen = vec![false; natoms];
+        for &i in keep {
+            let message = match seen.get_mut(i) {
+                None => format!("atom index {i} out of range for frame with {natoms} atoms"),
+                Some(true) => format!("atom index {i} appears more than once"),
+                Some(slot) => {
+                    *slot = true;
+                    continue;
+                }
+            };
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+        }
+
+        let header = &frame.header;
+        let masses = header.per_atom_masses();
+        let mut builder = ConFrameBuilder::new(header.boxl, header.angles)
+            .prebox_header(header.prebox_header.clone())
+            .postbox_header(header.postbox_header.clone());
+        for &i in keep {
+            builder.push_atom_datum(&frame.atom_data[i], masses.get(i).copied().unwrap_or(0.0));
+        }
+        self.write_frame(&builder.build())
+    }
+
     /// Writes all frames from an iterator to the output stream.
     ///
     /// This is the most convenient way to write a multi-frame file.
@@ -167,6 +450,448 @@ impl<W: Write> ConFrameWriter<W> {
     }
 }
 
+/// Renders frames as `.con` text, exactly as [`ConFrameWriter::with_precision`]
+/// would write them.
+///
+/// # Example
+///
+/// ```
+/// use readcon_core::types::ConFrameBuilder;
+/// use readcon_core::writer::write_con_string;
+///
+/// let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+/// builder.add_atom("H", 1.0, 2.0, 3.0, false, 0, 1.008);
+/// let frame = builder.build();
+/// let text = write_con_string([&frame], 3);
+/// assert!(text.contains("10.000 10.000 10.000"));
+/// assert_eq!(text, frame.to_con_string(3));
+/// ```
+pub fn write_con_string<'a>(
+    frames: impl IntoIterator<Item = &'a ConFrame>,
+    precision: usize,
+) -> String {
+    write_con_string_with_options(frames, FormatOptions::default().precision(precision))
+}
+
+/// Renders frames as `.con` text, exactly as [`ConFrameWriter::with_format`]
+/// would write them with `format`.
+pub fn write_con_string_with_options<'a>(
+    frames: impl IntoIterator<Item = &'a ConFrame>,
+    format: FormatOptions,
+) -> String {
+    let mut writer = ConFrameWriter::with_format(Vec::new(), format);
+    writer
+        .extend(frames.into_iter())
+        .expect("writing to a Vec<u8> cannot fail");
+    let buffer = writer.finish().expect("writing to a Vec<u8> cannot fail");
+    String::from_utf8(buffer).expect("the writer only emits UTF-8")
+}
+
+/// Writes `frames` to `w` in order, formatting them on rayon threads.
+///
+/// Each frame is rendered into its own buffer with the same layout as
+/// [`ConFrameWriter::with_precision`]; the buffers are then written to `w`
+/// sequentially in input order, whatever order the threads finish in. The
+/// output is byte-for-byte identical to writing the frames one by one, at the
+/// cost of holding the whole formatted trajectory in memory before writing.
+///
+/// Requires the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub fn write_frames_parallel<W: Write>(
+    w: &mut W,
+    frames: &[ConFrame],
+    precision: usize,
+) -> io::Result<()> {
+    use rayon::prelude::*;
+
+    // `ConFrame` holds `Rc` symbols and cannot cross threads, so the fields
+    // the writer reads are copied out here, with symbols borrowed as `&str`.
+    let views: Vec<(&FrameHeader, bool, Vec<AtomFields<'_>>)> = frames
+        .iter()
+        .map(|f| {
+            (
+                &f.header,
+                f.has_velocities(),
+                f.atom_data.iter().map(AtomFields::of).collect(),
+            )
+        })
+        .collect();
+    let buffers: Vec<Vec<u8>> = views
+        .par_iter()
+        .map(|(header, has_velocities, atoms)| {
+            let mut writer = ConFrameWriter::with_precision(Vec::new(), precision);
+            writer.write_frame_fields(header, *has_velocities, |i| atoms[i])?;
+            writer.finish()
+        })
+        .collect::<io::Result<_>>()?;
+    for buf in &buffers {
+        w.write_all(buf)?;
+    }
+    Ok(())
+}
+
+/// Writes frames as a JSON array, for consumers that would rather not parse
+/// the `.con` text grammar. This layout is stable:
+///
+/// ```json
+/// [{"cell": [a, b, c], "angles": [alpha, beta, gamma],
+///   "prebox_header": ["...", "..."], "postbox_header": ["...", "..."],
+///   "atoms": [{"symbol": "Cu", "id": 0, "pos": [x, y, z], "fixed": true,
+///              "mass": 63.546, "vel": [vx, vy, vz], "extra": [q]}]}]
+/// ```
+///
+/// Angles are in degrees. Atoms are listed in frame order, so grouped by
+/// type. `vel` is omitted for atoms without velocities and holds `null` for
+/// any missing component; `extra` holds the extra atom-line columns and is
+/// omitted when there are none. [`crate::parser::parse_json`] reads it back.
+///
+/// # Errors
+///
+/// Propagates any I/O error from `w`.
+#[cfg(feature = "serde")]
+pub fn write_json<W: Write>(w: W, frames: &[ConFrame]) -> io::Result<()> {
+    let frames: Vec<json::Frame> = frames.iter().map(json::Frame::from).collect();
+    let mut w = BufWriter::new(w);
+    serde_json::to_writer(&mut w, &frames)?;
+    w.flush()
+}
+
+/// Decimal places used for the cell and coordinates in POSCAR and LAMMPS output.
+const EXPORT_FLOAT_PRECISION: usize = 10;
+
+/// Writes a frame as a VASP POSCAR file.
+///
+/// The comment line is the first pre-box header line (or the element symbols
+/// if it is empty), followed by a scaling factor of `1.0`, the cell matrix from
+/// [`crate::types::FrameHeader::cell_matrix`], the element and count lines,
+/// and the coordinates. If any atom is fixed, `Selective dynamics` is enabled
+/// and each atom gets `F F F` (fixed) or `T T T` (free) flags. With
+/// `cartesian == false` coordinates are written in Direct (fractional) form.
+/// Velocities are not written.
+///
+/// # Errors
+///
+/// Returns an `InvalidInput` error if Direct output is requested for a
+/// degenerate cell, and propagates any I/O error from `w`.
+pub fn write_poscar<W: Write>(w: &mut W, frame: &ConFrame, cartesian: bool) -> io::Result<()> {
+    let prec = EXPORT_FLOAT_PRECISION;
+    let groups: Vec<(&str, usize)> = frame
+        .atoms_by_type()
+        .map(|(symbol, atoms)| (symbol, atoms.len()))
+        .collect();
+    let symbols: Vec<&str> = groups.iter().map(|(symbol, _)| *symbol).collect();
+
+    let comment = frame.header.prebox_header[0].trim();
+    if comment.is_empty() {
+        writeln!(w, "{}", symbols.join(" "))?;
+    } else {
+        writeln!(w, "{comment}")?;
+    }
+    writeln!(w, "1.0")?;
+    for row in frame.header.cell_matrix() {
+        writeln!(w, "  {:.prec$} {:.prec$} {:.prec$}", row[0], row[1], row[2])?;
+    }
+    writeln!(w, "{}", symbols.join(" "))?;
+    let counts: Vec<String> = groups.iter().map(|(_, n)| n.to_string()).collect();
+    writeln!(w, "{}", counts.join(" "))?;
+
+    let selective = frame.atom_data.iter().any(|a| a.is_fixed);
+    if selective {
+        writeln!(w, "Selective dynamics")?;
+    }
+
+    let positions: Vec<[f64; 3]> = if cartesian {
+        writeln!(w, "Cartesian")?;
+        frame.atom_data.iter().map(|a| [a.x, a.y, a.z]).collect()
+    } else {
+        writeln!(w, "Direct")?;
+        frame
+            .to_fractional()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+    };
+
+    for (atom, pos) in frame.atom_data.iter().zip(positions) {
+        write!(w, "  {:.prec$} {:.prec$} {:.prec$}", pos[0], pos[1], pos[2])?;
+        if selective {
+            let flags = if atom.is_fixed { "F F F" } else { "T T T" };
+            write!(w, " {flags}")?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Writes a frame as a LAMMPS data file in `atomic` style.
+///
+/// Each non-empty type block becomes a LAMMPS atom type, numbered from 1 in
+/// `natms_per_type` order, with its mass in the `Masses` section. The box
+/// spans `[0, L)` along each axis of [`crate::types::FrameHeader::cell_matrix`],
+/// which is already in LAMMPS' restricted triclinic form; the `xy xz yz` tilt
+/// line is written for non-orthogonal cells. Atoms are numbered sequentially
+/// from 1. A `Velocities` section is written if the frame has velocities.
+///
+/// Returns the `(symbol, type)` mapping used, so results can be interpreted
+/// later.
+pub fn write_lammps_data<W: Write>(
+    w: &mut W,
+    frame: &ConFrame,
+) -> io::Result<Vec<(String, usize)>> {
+    let prec = EXPORT_FLOAT_PRECISION;
+
+    // (symbol, mass, atom count) per non-empty type block.
+    let mut types: Vec<(&str, f64, usize)> = Vec::new();
+    let mut offset = 0;
+    for (type_idx, &count) in frame.header.natms_per_type.iter().enumerate() {
+        if let Some(atom) = frame.atom_data.get(offset).filter(|_| count > 0) {
+            let mass = frame
+                .header
+                .masses_per_type
+                .get(type_idx)
+                .copied()
+                .unwrap_or(0.0);
+            types.push((atom.symbol_str(), mass, count));
+        }
+        offset += count;
+    }
+
+    let symbols: Vec<&str> = types.iter().map(|(symbol, _, _)| *symbol).collect();
+    writeln!(w, "LAMMPS data file ({})", symbols.join(" "))?;
+    writeln!(w)?;
+    writeln!(w, "{} atoms", frame.atom_data.len())?;
+    writeln!(w, "{} atom types", types.len())?;
+    writeln!(w)?;
+
+    let [a, b, c] = frame.header.cell_matrix();
+    writeln!(w, "{:.prec$} {:.prec$} xlo xhi", 0.0, a[0])?;
+    writeln!(w, "{:.prec$} {:.prec$} ylo yhi", 0.0, b[1])?;
+    writeln!(w, "{:.prec$} {:.prec$} zlo zhi", 0.0, c[2])?;
+    if frame.header.angles != [90.0, 90.0, 90.0] {
+        writeln!(
+            w,
+            "{:.prec$} {:.prec$} {:.prec$} xy xz yz",
+            b[0], c[0], c[1]
+        )?;
+    }
+
+    writeln!(w)?;
+    writeln!(w, "Masses")?;
+    writeln!(w)?;
+    for (i, (symbol, mass, _)) in types.iter().enumerate() {
+        writeln!(w, "{} {mass:.prec$} # {symbol}", i + 1)?;
+    }
+
+    writeln!(w)?;
+    writeln!(w, "Atoms # atomic")?;
+    writeln!(w)?;
+    let type_ids = types
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (_, _, count))| std::iter::repeat_n(i + 1, *count));
+    for (i, (atom, type_id)) in frame.atom_data.iter().zip(type_ids).enumerate() {
+        writeln!(
+            w,
+            "{} {type_id} {:.prec$} {:.prec$} {:.prec$}",
+            i + 1,
+            atom.x,
+            atom.y,
+            atom.z
+        )?;
+    }
+
+    if frame.has_velocities() {
+        writeln!(w)?;
+        writeln!(w, "Velocities")?;
+        writeln!(w)?;
+        for (i, atom) in frame.atom_data.iter().enumerate() {
+            writeln!(
+                w,
+                "{} {:.prec$} {:.prec$} {:.prec$}",
+                i + 1,
+                atom.vx.unwrap_or(0.0),
+                atom.vy.unwrap_or(0.0),
+                atom.vz.unwrap_or(0.0)
+            )?;
+        }
+    }
+
+    Ok(symbols
+        .into_iter()
+        .enumerate()
+        .map(|(i, symbol)| (symbol.to_string(), i + 1))
+        .collect())
+}
+
+/// Byte offset of `NSET` (the frame count) in a DCD file: the 4-byte record
+/// marker and the `CORD` magic precede it.
+#[cfg(feature = "dcd")]
+const DCD_NSET_OFFSET: u64 = 8;
+/// Byte offset of `NSTEP`, the fourth control word after `NSET`.
+#[cfg(feature = "dcd")]
+const DCD_NSTEP_OFFSET: u64 = DCD_NSET_OFFSET + 12;
+/// CHARMM version written in the last control word; 24 marks the
+/// little-endian CHARMM flavour read by VMD and MDAnalysis.
+#[cfg(feature = "dcd")]
+const DCD_CHARMM_VERSION: i32 = 24;
+
+/// Writes frames as a CHARMM/NAMD DCD binary trajectory.
+///
+/// Every frame stores its unit cell (lengths and angles in degrees) and its
+/// coordinates as single-precision `X`, `Y` and `Z` arrays, all in
+/// little-endian Fortran records. The frame count in the header is written
+/// as 0 and backfilled once all frames are written, which is why the writer
+/// must be seekable; it is left positioned at the end of the trajectory.
+///
+/// `timestep` is stored as the DCD `DELTA` between saved frames.
+///
+/// Fails with `InvalidInput` if there are no frames or the atom count
+/// changes between frames. Requires the `dcd` feature.
+#[cfg(feature = "dcd")]
+pub fn write_dcd<'a, W, I>(w: &mut W, frames: I, timestep: f32) -> io::Result<()>
+where
+    W: Write + std::io::Seek,
+    I: IntoIterator<Item = &'a ConFrame>,
+{
+    use std::io::SeekFrom;
+
+    fn record<W: Write>(w: &mut W, payload: &[u8]) -> io::Result<()> {
+        let marker = u32::try_from(payload.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "DCD record too large"))?
+            .to_le_bytes();
+        w.write_all(&marker)?;
+        w.write_all(payload)?;
+        w.write_all(&marker)
+    }
+
+    let mut frames = frames.into_iter().peekable();
+    let natoms = frames
+        .peek()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no frames to write"))?
+        .atom_data
+        .len();
+    let natoms_i32 = i32::try_from(natoms)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "too many atoms for DCD"))?;
+    let start = w.stream_position()?;
+
+    let mut control = [0i32; 20];
+    control[2] = 1; // NSAVC: steps between saved frames
+    control[9] = timestep.to_bits() as i32; // DELTA, stored as a float
+    control[10] = 1; // every frame carries a unit cell
+    control[19] = DCD_CHARMM_VERSION;
+    let mut header = Vec::with_capacity(84);
+    header.extend_from_slice(b"CORD");
+    for value in control {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+    record(w, &header)?;
+
+    let mut title = [b' '; 80];
+    let text = b"Created by readcon-core";
+    title[..text.len()].copy_from_slice(text);
+    let mut titles = 1i32.to_le_bytes().to_vec();
+    titles.extend_from_slice(&title);
+    record(w, &titles)?;
+    record(w, &natoms_i32.to_le_bytes())?;
+
+    let mut nframes: i32 = 0;
+    let mut buffer = Vec::with_capacity(4 * natoms);
+    for (index, frame) in frames.enumerate() {
+        if frame.atom_data.len() != natoms {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "frame {index} has {} atoms, expected {natoms}",
+                    frame.atom_data.len()
+                ),
+            ));
+        }
+
+        // CHARMM orders the cell as a, gamma, b, beta, alpha, c.
+        let [a, b, c] = frame.header.boxl;
+        let [alpha, beta, gamma] = frame.header.angles;
+        buffer.clear();
+        for value in [a, gamma, b, beta, alpha, c] {
+            buffer.extend_from_slice(&value.to_le_bytes());
+        }
+        record(w, &buffer)?;
+
+        for axis in 0..3 {
+            buffer.clear();
+            for atom in &frame.atom_data {
+                let value = [atom.x, atom.y, atom.z][axis] as f32;
+                buffer.extend_from_slice(&value.to_le_bytes());
+            }
+            record(w, &buffer)?;
+        }
+        nframes += 1;
+    }
+
+    let end = w.stream_position()?;
+    w.seek(SeekFrom::Start(start + DCD_NSET_OFFSET))?;
+    w.write_all(&nframes.to_le_bytes())?;
+    w.seek(SeekFrom::Start(start + DCD_NSTEP_OFFSET))?;
+    w.write_all(&nframes.to_le_bytes())?;
+    w.seek(SeekFrom::Start(end))?;
+    Ok(())
+}
+
+/// Largest serial number that fits the 5-column PDB serial field.
+const PDB_MAX_SERIAL: u64 = 99_999;
+
+/// Writes a frame as a PDB file: a `CRYST1` record, one `HETATM` record per
+/// atom, and `END`.
+///
+/// Records follow the fixed-width PDB v3.3 layout (80 columns). Serial numbers
+/// are `atom_id + 1` when the IDs are unique and fit the serial field, and
+/// sequential from 1 otherwise. Element symbols are upper-cased and truncated
+/// to two characters. Every atom is placed in residue `MOL` 1 with occupancy
+/// `1.00` and temperature factor `0.00`.
+pub fn write_pdb<W: Write>(w: &mut W, frame: &ConFrame) -> io::Result<()> {
+    let [a, b, c] = frame.header.boxl;
+    let [alpha, beta, gamma] = frame.header.angles;
+    writeln!(
+        w,
+        "CRYST1{a:9.3}{b:9.3}{c:9.3}{alpha:7.2}{beta:7.2}{gamma:7.2} P 1           1"
+    )?;
+
+    let ids_usable = {
+        let mut seen = std::collections::HashSet::with_capacity(frame.atom_data.len());
+        frame
+            .atom_data
+            .iter()
+            .all(|atom| atom.atom_id < PDB_MAX_SERIAL && seen.insert(atom.atom_id))
+    };
+
+    for (i, atom) in frame.atom_data.iter().enumerate() {
+        let serial = if ids_usable {
+            atom.atom_id + 1
+        } else {
+            (i as u64 + 1).min(PDB_MAX_SERIAL)
+        };
+        let element: String = atom
+            .symbol_str()
+            .chars()
+            .take(2)
+            .collect::<String>()
+            .to_uppercase();
+        // One-letter elements start in column 14, two-letter ones in column 13.
+        let name = if element.len() == 1 {
+            format!(" {element}")
+        } else {
+            element.clone()
+        };
+        // Residue `MOL` 1 on chain A, occupancy 1.00, temperature factor 0.00.
+        writeln!(
+            w,
+            "HETATM{serial:>5} {name:<4} MOL A   1    {x:8.3}{y:8.3}{z:8.3}  1.00  0.00          \
+             {element:>2}  ",
+            x = atom.x,
+            y = atom.y,
+            z = atom.z,
+        )?;
+    }
+    writeln!(w, "END")
+}
+
 // Implementation block specifically for when the writer is a `File`.
 impl ConFrameWriter<File> {
     /// Creates a new `ConFrameWriter` that writes to a file at the given path.
@@ -182,4 +907,38 @@ impl ConFrameWriter<File> {
         let file = File::create(path)?;
         Ok(Self::with_precision(file, precision))
     }
+
+    /// Creates a `ConFrameWriter` that appends to the file at `path`,
+    /// creating it if needed.
+    ///
+    /// Frames are self-delimiting, so appending to an existing `.con` file
+    /// yields a valid multi-frame file. If the file is not empty, the frame
+    /// separator (see [`ConFrameWriter::set_frame_separator`]) is written
+    /// before the first appended frame as well.
+    pub fn from_path_append<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_path_append_with_precision(path, DEFAULT_FLOAT_PRECISION)
+    }
+
+    /// Like [`ConFrameWriter::from_path_append`], with a custom precision.
+    pub fn from_path_append_with_precision<P: AsRef<Path>>(
+        path: P,
+        precision: usize,
+    ) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let existing_len = file.metadata()?.len();
+        let mut writer = Self::with_precision(file, precision);
+        if existing_len > 0 {
+            writer.frames_written = 1;
+        }
+        Ok(writer)
+    }
+
+    /// Creates a new `ConFrameWriter` that writes to a file with custom layout options.
+    pub fn from_path_with_format<P: AsRef<Path>>(
+        path: P,
+        format: FormatOptions,
+    ) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self::with_format(file, format))
+    }
 }
@@ -1,3 +1,12 @@
+// Not every test binary uses every helper.
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use tempfile::NamedTempFile;
+
 // Kanged and expanded from [1]
 #[macro_export]
 macro_rules! test_case {
@@ -9,5 +18,30 @@ macro_rules! test_case {
     };
 }
 
+/// `tiny_cuh2.con` with its first header line replaced by `frame {i}`, so
+/// frames can be told apart after parsing.
+pub fn labelled_frame(i: usize) -> String {
+    let frame = fs::read_to_string(test_case!("tiny_cuh2.con")).expect("Can't find test.");
+    frame.replacen("Random Number Seed", &format!("frame {i}"), 1)
+}
+
+/// `n` concatenated copies of [`labelled_frame`], labelled `frame 0` onwards.
+pub fn labelled_frames(n: usize) -> String {
+    (0..n).map(labelled_frame).collect()
+}
+
+/// A temporary file holding `contents`, removed when the handle is dropped.
+/// `suffix` sets the extension, e.g. `".con.gz"`.
+pub fn temp_file(suffix: &str, contents: impl AsRef<[u8]>) -> NamedTempFile {
+    let mut file = tempfile::Builder::new()
+        .prefix("readcon_")
+        .suffix(suffix)
+        .tempfile()
+        .expect("Can't create temp file.");
+    file.write_all(contents.as_ref())
+        .expect("Can't write temp file.");
+    file
+}
+
 // References
 // [1]: https://stackoverflow.com/a/74550371/1895378
@@ -1,7 +1,8 @@
 mod common;
 use readcon_core::iterators::ConFrameIterator;
+use readcon_core::parser::{self, ParseOptions};
 use readcon_core::types::ConFrameBuilder;
-use readcon_core::writer::ConFrameWriter;
+use readcon_core::writer::{self, Align, ConFrameWriter, FormatOptions, Notation};
 use std::fs;
 use std::path::Path;
 
@@ -42,14 +43,175 @@ fn test_writer_roundtrip() {
     );
 }
 
+#[test]
+fn test_format_options_default_matches_new() {
+    let fdat = fs::read_to_string(test_case!("tiny_cuh2.convel")).expect("Can't find test file.");
+    let frames: Vec<_> = ConFrameIterator::new(&fdat).map(|r| r.unwrap()).collect();
+
+    let mut plain: Vec<u8> = Vec::new();
+    let mut formatted: Vec<u8> = Vec::new();
+    {
+        ConFrameWriter::new(&mut plain)
+            .extend(frames.iter())
+            .unwrap();
+        ConFrameWriter::with_format(&mut formatted, FormatOptions::default())
+            .extend(frames.iter())
+            .unwrap();
+    }
+    assert_eq!(plain, formatted);
+}
+
+#[test]
+fn test_format_options_fixed_width_columns() {
+    let fdat = fs::read_to_string(test_case!("tiny_cuh2.convel")).expect("Can't find test file.");
+    let frames: Vec<_> = ConFrameIterator::new(&fdat).map(|r| r.unwrap()).collect();
+
+    let format = FormatOptions::default()
+        .precision(4)
+        .coord_width(12)
+        .align(Align::Right)
+        .notation(Notation::Fixed);
+    let mut buffer: Vec<u8> = Vec::new();
+    {
+        let mut writer = ConFrameWriter::with_format(&mut buffer, format);
+        writer.extend(frames.iter()).unwrap();
+    }
+    let output = String::from_utf8(buffer).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[11], "      0.6394       0.9045       6.9753 1 0");
+    // Velocity lines use the same columns.
+    assert_eq!(lines[20], "      0.0012       0.0023      -0.0035 1 0");
+
+    let reparsed: Vec<_> = ConFrameIterator::new(&output).map(|r| r.unwrap()).collect();
+    assert_eq!(reparsed.len(), 1);
+    assert!(reparsed[0].has_velocities());
+}
+
+#[test]
+fn test_writer_scientific_notation_roundtrip() {
+    let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+    builder.add_atom("H", 1e-20, 1e12, 1.5, false, 0, 1.008);
+    let frame = builder.build();
+
+    let write = |notation| {
+        let format = FormatOptions::default().notation(notation);
+        let mut buffer: Vec<u8> = Vec::new();
+        ConFrameWriter::with_format(&mut buffer, format)
+            .write_frame(&frame)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    };
+
+    let fixed = write(Notation::Fixed);
+    assert!(fixed.contains("0.000000 1000000000000.000000 1.500000 0 0"));
+
+    let scientific = write(Notation::Scientific);
+    assert!(scientific.contains("1.000000e-20 1.000000e12 1.500000e0 0 0"));
+
+    let adaptive = write(Notation::Adaptive);
+    assert!(adaptive.contains("1.000000e-20 1.000000e12 1.500000 0 0"));
+
+    for output in [scientific, adaptive] {
+        let reread = ConFrameIterator::new(&output).next().unwrap().unwrap();
+        let atom = &reread.atom_data[0];
+        assert_eq!(atom.x, 1e-20);
+        assert_eq!(atom.y, 1e12);
+        assert_eq!(atom.z, 1.5);
+    }
+}
+
+#[test]
+fn test_write_frame_subset() {
+    let fdat = fs::read_to_string(test_case!("tiny_cuh2.convel")).expect("Can't find test file.");
+    let frame = ConFrameIterator::new(&fdat).next().unwrap().unwrap();
+
+    // Keep one Cu and one H atom.
+    let mut buffer: Vec<u8> = Vec::new();
+    {
+        let mut writer = ConFrameWriter::new(&mut buffer);
+        writer.write_frame_subset(&frame, &[3, 1]).unwrap();
+    }
+    let output = String::from_utf8(buffer).unwrap();
+    let subset = ConFrameIterator::new(&output).next().unwrap().unwrap();
+
+    assert_eq!(subset.header.prebox_header, frame.header.prebox_header);
+    assert_eq!(subset.header.boxl, frame.header.boxl);
+    assert_eq!(subset.header.natm_types, 2);
+    assert_eq!(subset.header.natms_per_type, vec![1, 1]);
+    // Types appear in the order first seen in `keep`: H, then Cu.
+    let masses = &frame.header.masses_per_type;
+    assert_eq!(subset.header.masses_per_type, vec![masses[1], masses[0]]);
+    assert_eq!(subset.atom_data[0], frame.atom_data[3]);
+    assert_eq!(subset.atom_data[1], frame.atom_data[1]);
+    assert!(subset.has_velocities());
+
+    let mut sink: Vec<u8> = Vec::new();
+    let err = ConFrameWriter::new(&mut sink)
+        .write_frame_subset(&frame, &[0, 4])
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let err = ConFrameWriter::new(&mut sink)
+        .write_frame_subset(&frame, &[1, 3, 1])
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("more than once"));
+    assert!(sink.is_empty());
+}
+
+#[test]
+fn test_writer_append_across_sessions() {
+    let fdat =
+        fs::read_to_string(test_case!("tiny_multi_cuh2.con")).expect("Can't find test file.");
+    let frames: Vec<_> = ConFrameIterator::new(&fdat).map(|r| r.unwrap()).collect();
+    assert_eq!(frames.len(), 2);
+
+    let file = common::temp_file(".con", "");
+    for frame in &frames {
+        // A fresh writer per frame, as after a process restart.
+        let mut writer = ConFrameWriter::from_path_append(file.path()).unwrap();
+        writer.write_frame(frame).unwrap();
+    }
+
+    let contents = fs::read_to_string(file.path()).unwrap();
+    let reread: Vec<_> = ConFrameIterator::new(&contents)
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(reread, frames);
+}
+
 #[test]
 fn test_builder_roundtrip() {
     let mut builder = ConFrameBuilder::new([15.345600, 21.702000, 100.000000], [90.0, 90.0, 90.0])
         .prebox_header(["Random Number Seed".to_string(), "Time".to_string()])
         .postbox_header(["0 0".to_string(), "218 0 1".to_string()]);
-    builder.add_atom("Cu", 0.639400000000001, 0.904500000000000, 6.975299999999995, true, 0, 63.546);
-    builder.add_atom("Cu", 3.196999999999999, 0.904500000000000, 6.975299999999995, true, 1, 63.546);
-    builder.add_atom("H", 8.682299999999999, 9.946999999999997, 11.732999999999993, false, 2, 1.008);
+    builder.add_atom(
+        "Cu",
+        0.639400000000001,
+        0.904500000000000,
+        6.975299999999995,
+        true,
+        0,
+        63.546,
+    );
+    builder.add_atom(
+        "Cu",
+        3.196999999999999,
+        0.904500000000000,
+        6.975299999999995,
+        true,
+        1,
+        63.546,
+    );
+    builder.add_atom(
+        "H",
+        8.682299999999999,
+        9.946999999999997,
+        11.732999999999993,
+        false,
+        2,
+        1.008,
+    );
     let frame = builder.build();
 
     let mut buffer: Vec<u8> = Vec::new();
@@ -74,8 +236,7 @@ fn test_builder_roundtrip() {
 
 #[test]
 fn test_writer_precision_default_vs_high() {
-    let mut builder =
-        ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
+    let mut builder = ConFrameBuilder::new([10.0, 10.0, 10.0], [90.0, 90.0, 90.0]);
     builder.add_atom("Cu", 1.23456789012345678, 0.0, 0.0, false, 0, 63.546);
     let frame = builder.build();
 
@@ -86,11 +247,12 @@ fn test_writer_precision_default_vs_high() {
         w.write_frame(&frame).unwrap();
     }
     let s6 = String::from_utf8(buf6).unwrap();
-    let frames6: Vec<_> = ConFrameIterator::new(&s6)
-        .map(|r| r.unwrap())
-        .collect();
+    let frames6: Vec<_> = ConFrameIterator::new(&s6).map(|r| r.unwrap()).collect();
     // 6 decimal places means ~1e-6 precision loss
     assert!((frames6[0].atom_data[0].x - 1.234568).abs() < 1e-5);
+    assert!(frames6[0].approx_eq(&frame, 1e-6));
+    assert!(!frames6[0].approx_eq(&frame, 1e-9));
+    assert_ne!(frames6[0], frame);
 
     // High precision (17)
     let mut buf17: Vec<u8> = Vec::new();
@@ -99,11 +261,10 @@ fn test_writer_precision_default_vs_high() {
         w.write_frame(&frame).unwrap();
     }
     let s17 = String::from_utf8(buf17).unwrap();
-    let frames17: Vec<_> = ConFrameIterator::new(&s17)
-        .map(|r| r.unwrap())
-        .collect();
+    let frames17: Vec<_> = ConFrameIterator::new(&s17).map(|r| r.unwrap()).collect();
     // 17 decimal places preserves the full f64
     assert!((frames17[0].atom_data[0].x - 1.23456789012345678).abs() < 1e-14);
+    assert!(frames17[0].approx_eq(&frame, 1e-14));
 }
 
 #[test]
@@ -129,3 +290,457 @@ fn test_builder_velocity_roundtrip() {
     assert_eq!(frames[0].atom_data[0].vx, Some(0.1));
     assert_eq!(frames[0].atom_data[1].vz, Some(0.6));
 }
+
+#[test]
+fn test_writer_extra_columns_roundtrip() {
+    let fdat = "\
+PREBOX1
+PREBOX2
+10.0 10.0 10.0
+90.0 90.0 90.0
+POSTBOX1
+POSTBOX2
+1
+2
+63.546
+Cu
+Coordinates of Component 1
+0.0 0.0 0.0 1 0 -0.25
+1.0 0.0 0.0 1 1 0.75
+";
+    let frames: Vec<_> = ConFrameIterator::new(fdat).map(|r| r.unwrap()).collect();
+    assert_eq!(frames[0].atom_data[1].extra_columns, vec![0.75]);
+
+    let mut buffer: Vec<u8> = Vec::new();
+    {
+        let mut writer = ConFrameWriter::new(&mut buffer);
+        writer.extend(frames.iter()).unwrap();
+    }
+    let written = String::from_utf8(buffer).unwrap();
+    let frames_roundtrip: Vec<_> = ConFrameIterator::new(&written)
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(frames, frames_roundtrip);
+}
+
+#[test]
+fn test_writer_frame_separator_roundtrip() {
+    let fdat_original =
+        fs::read_to_string(test_case!("tiny_multi_cuh2.convel")).expect("Can't find test file.");
+    let frames_original: Vec<_> = ConFrameIterator::new(&fdat_original)
+        .map(|r| r.unwrap())
+        .collect();
+    assert!(frames_original.len() > 1);
+
+    let mut buffer: Vec<u8> = Vec::new();
+    {
+        let mut writer = ConFrameWriter::new(&mut buffer);
+        writer.set_frame_separator("\n\n".to_string());
+        writer
+            .extend(frames_original.iter())
+            .expect("Failed to write to buffer.");
+    }
+    let fdat_separated = String::from_utf8(buffer).expect("Buffer is not valid UTF-8.");
+    assert!(fdat_separated.contains("\n\n\nRandom Number Seed"));
+    assert!(!fdat_separated.ends_with("\n\n"));
+
+    let options = ParseOptions {
+        skip_blank_lines: true,
+        ..ParseOptions::default()
+    };
+    let frames_roundtrip: Vec<_> = ConFrameIterator::with_options(&fdat_separated, options.clone())
+        .map(|r| r.expect("tolerant reader should parse separated frames"))
+        .collect();
+    assert_eq!(frames_original, frames_roundtrip);
+
+    let mut skipper = ConFrameIterator::with_options(&fdat_separated, options);
+    assert!(skipper.forward().unwrap().is_ok());
+    assert_eq!(skipper.next().unwrap().unwrap(), frames_original[1]);
+}
+
+#[test]
+fn test_tolerant_reader_coordinates_only() {
+    let fdat_original =
+        fs::read_to_string(test_case!("tiny_multi_cuh2.con")).expect("Can't find test file.");
+    let frames_original: Vec<_> = ConFrameIterator::new(&fdat_original)
+        .map(|r| r.unwrap())
+        .collect();
+
+    let mut buffer: Vec<u8> = Vec::new();
+    {
+        let mut writer = ConFrameWriter::new(&mut buffer);
+        writer.set_frame_separator("\n".to_string());
+        writer
+            .extend(frames_original.iter())
+            .expect("Failed to write to buffer.");
+    }
+    let fdat_separated = String::from_utf8(buffer).expect("Buffer is not valid UTF-8.");
+    let options = ParseOptions {
+        skip_blank_lines: true,
+        ..ParseOptions::default()
+    };
+    let frames_roundtrip: Vec<_> = ConFrameIterator::with_options(&fdat_separated, options)
+        .map(|r| r.expect("tolerant reader should parse separated frames"))
+        .collect();
+    assert_eq!(frames_original, frames_roundtrip);
+    assert!(frames_roundtrip.iter().all(|f| !f.has_velocities()));
+}
+
+#[test]
+fn test_write_poscar() {
+    let mut builder = ConFrameBuilder::new([10.0, 20.0, 30.0], [90.0, 90.0, 90.0])
+        .prebox_header(["CuH2 slab".to_string(), String::new()]);
+    builder.add_atom("Cu", 5.0, 5.0, 15.0, true, 0, 63.546);
+    builder.add_atom("H", 2.5, 10.0, 3.0, false, 1, 1.008);
+    builder.add_atom("H", 1.0, 2.0, 3.0, false, 2, 1.008);
+    let frame = builder.build();
+
+    let mut buffer: Vec<u8> = Vec::new();
+    writer::write_poscar(&mut buffer, &frame, false).expect("Failed to write POSCAR.");
+    let poscar = String::from_utf8(buffer).expect("Buffer is not valid UTF-8.");
+    let lines: Vec<&str> = poscar.lines().collect();
+    assert_eq!(lines[0], "CuH2 slab");
+    assert_eq!(lines[1], "1.0");
+    assert_eq!(lines[2], "  10.0000000000 0.0000000000 0.0000000000");
+    assert_eq!(lines[5], "Cu H");
+    assert_eq!(lines[6], "1 2");
+    assert_eq!(lines[7], "Selective dynamics");
+    assert_eq!(lines[8], "Direct");
+    assert_eq!(lines[9], "  0.5000000000 0.2500000000 0.5000000000 F F F");
+    assert_eq!(lines[10], "  0.2500000000 0.5000000000 0.1000000000 T T T");
+    assert_eq!(lines.len(), 12);
+
+    let mut free = frame.clone();
+    free.atom_data.iter_mut().for_each(|a| a.is_fixed = false);
+    let mut buffer: Vec<u8> = Vec::new();
+    writer::write_poscar(&mut buffer, &free, true).expect("Failed to write POSCAR.");
+    let poscar = String::from_utf8(buffer).expect("Buffer is not valid UTF-8.");
+    let lines: Vec<&str> = poscar.lines().collect();
+    assert_eq!(lines[7], "Cartesian");
+    assert_eq!(lines[8], "  5.0000000000 5.0000000000 15.0000000000");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json_roundtrip() {
+    for file in ["tiny_multi_cuh2.con", "tiny_cuh2.convel"] {
+        let contents = fs::read_to_string(test_case!(file)).expect("Can't find test file.");
+        let frames: Vec<_> = ConFrameIterator::new(&contents)
+            .map(|f| f.unwrap())
+            .collect();
+        let mut buffer: Vec<u8> = Vec::new();
+        writer::write_json(&mut buffer, &frames).expect("Failed to write JSON.");
+        let json = String::from_utf8(buffer).expect("Buffer is not valid UTF-8.");
+        assert_eq!(
+            parser::parse_json(&json).expect("Failed to parse JSON."),
+            frames
+        );
+    }
+
+    let frame = ConFrameIterator::new(
+        &fs::read_to_string(test_case!("tiny_cuh2.convel")).expect("Can't find test file."),
+    )
+    .next()
+    .unwrap()
+    .unwrap();
+    let mut buffer: Vec<u8> = Vec::new();
+    writer::write_json(&mut buffer, std::slice::from_ref(&frame)).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+    let atom = &value[0]["atoms"][0];
+    assert_eq!(value[0]["cell"][0], frame.header.boxl[0]);
+    assert_eq!(atom["symbol"], "Cu");
+    assert_eq!(atom["id"], 0);
+    assert_eq!(atom["mass"], frame.header.masses_per_type[0]);
+    assert!(atom["vel"].is_array() && atom["pos"].is_array() && atom["fixed"].is_boolean());
+    assert!(parser::parse_json("[{\"cell\": [1, 2]}]").is_err());
+    assert!(atom.get("extra").is_none());
+
+    // Extra columns and partial velocities are kept.
+    let mut odd = frame.clone();
+    odd.atom_data[0].extra_columns = vec![0.5, -1.25];
+    odd.atom_data[1].vy = None;
+    odd.atom_data[2].vx = None;
+    odd.atom_data[2].vy = None;
+    odd.atom_data[2].vz = None;
+    let mut buffer: Vec<u8> = Vec::new();
+    writer::write_json(&mut buffer, std::slice::from_ref(&odd)).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+    assert_eq!(
+        value[0]["atoms"][0]["extra"],
+        serde_json::json!([0.5, -1.25])
+    );
+    assert!(value[0]["atoms"][1]["vel"][1].is_null());
+    assert!(value[0]["atoms"][2].get("vel").is_none());
+    let json = String::from_utf8(buffer).unwrap();
+    assert_eq!(parser::parse_json(&json).unwrap(), vec![odd]);
+
+    // Header bookkeeping that falls short of the atoms still writes them all.
+    let mut short = frame.clone();
+    short.header.masses_per_type.truncate(1);
+    short.header.natms_per_type = vec![2, 1];
+    let mut buffer: Vec<u8> = Vec::new();
+    writer::write_json(&mut buffer, std::slice::from_ref(&short)).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+    let atoms = value[0]["atoms"].as_array().unwrap();
+    assert_eq!(atoms.len(), short.atom_data.len());
+    assert_eq!(atoms[0]["mass"], frame.header.masses_per_type[0]);
+    assert_eq!(atoms[2]["mass"], 0.0);
+    assert_eq!(atoms[3]["mass"], 0.0);
+}
+
+#[test]
+fn test_writer_rebind_keeps_format() {
+    let contents = fs::read_to_string(test_case!("tiny_multi_cuh2.con")).unwrap();
+    let frames: Vec<_> = ConFrameIterator::new(&contents)
+        .map(|f| f.unwrap())
+        .collect();
+    let format = FormatOptions::default().precision(3);
+
+    let mut writer = ConFrameWriter::with_format(Vec::new(), format.clone());
+    writer.set_frame_separator("\n".to_string());
+    let mut outputs = Vec::new();
+    for (i, frame) in frames.iter().enumerate() {
+        if i > 0 {
+            let (rebound, previous) = writer.rebind(Vec::new()).unwrap();
+            outputs.push(previous);
+            writer = rebound;
+        }
+        writer.write_frame(frame).unwrap();
+    }
+    outputs.push(writer.finish().unwrap());
+
+    assert_eq!(outputs.len(), frames.len());
+    for (output, frame) in outputs.iter().zip(&frames) {
+        let mut expected = ConFrameWriter::with_format(Vec::new(), format.clone());
+        expected.write_frame(frame).unwrap();
+        // Each output holds exactly one frame, with the precision kept and no
+        // separator in front.
+        assert_eq!(output, &expected.finish().unwrap());
+    }
+}
+
+#[test]
+fn test_poscar_roundtrip() {
+    let frame = ConFrameIterator::new(
+        &fs::read_to_string(test_case!("tiny_cuh2.con")).expect("Can't find test file."),
+    )
+    .next()
+    .unwrap()
+    .unwrap();
+    for cartesian in [false, true] {
+        let mut buffer: Vec<u8> = Vec::new();
+        writer::write_poscar(&mut buffer, &frame, cartesian).expect("Failed to write POSCAR.");
+        let poscar = String::from_utf8(buffer).expect("Buffer is not valid UTF-8.");
+        let parsed = parser::parse_poscar(&poscar).expect("Failed to parse POSCAR.");
+        assert!(parsed.approx_eq(&frame, 1e-8), "cartesian = {cartesian}");
+    }
+}
+
+#[test]
+fn test_write_pdb() {
+    let mut builder = ConFrameBuilder::new([15.3456, 21.702, 100.0], [90.0, 90.0, 120.0]);
+    builder.add_atom("Cu", 0.6394, 0.9045, 6.9753, true, 0, 63.546);
+    builder.add_atom("H", -8.6823, 9.947, 11.733, false, 1, 1.008);
+    let frame = builder.build();
+
+    let mut buffer: Vec<u8> = Vec::new();
+    writer::write_pdb(&mut buffer, &frame).expect("Failed to write PDB.");
+    let pdb = String::from_utf8(buffer).expect("Buffer is not valid UTF-8.");
+    let lines: Vec<&str> = pdb.lines().collect();
+
+    assert_eq!(
+        lines[0],
+        "CRYST1   15.346   21.702  100.000  90.00  90.00 120.00 P 1           1"
+    );
+    assert_eq!(
+        lines[1],
+        "HETATM    1 CU   MOL A   1       0.639   0.904   6.975  1.00  0.00          CU  "
+    );
+    assert_eq!(
+        lines[2],
+        "HETATM    2  H   MOL A   1      -8.682   9.947  11.733  1.00  0.00           H  "
+    );
+    assert_eq!(lines[1].len(), 80);
+    // Fixed columns: serial 7-11, name 13-16, x 31-38, element 77-78.
+    assert_eq!(&lines[2][6..11], "    2");
+    assert_eq!(&lines[2][12..16], " H  ");
+    assert_eq!(&lines[2][30..38], "  -8.682");
+    assert_eq!(&lines[2][76..78], " H");
+    assert_eq!(lines[3], "END");
+
+    // Duplicate IDs fall back to sequential serials.
+    let mut dup = frame.clone();
+    dup.atom_data[1].atom_id = 0;
+    let mut buffer: Vec<u8> = Vec::new();
+    writer::write_pdb(&mut buffer, &dup).expect("Failed to write PDB.");
+    let pdb = String::from_utf8(buffer).expect("Buffer is not valid UTF-8.");
+    assert_eq!(&pdb.lines().nth(2).unwrap()[6..11], "    2");
+}
+
+#[test]
+fn test_write_lammps_data() {
+    let mut builder = ConFrameBuilder::new([10.0, 12.0, 14.0], [90.0, 90.0, 90.0]);
+    builder.add_atom_with_velocity("Cu", 1.0, 2.0, 3.0, true, 0, 63.546, 0.1, 0.2, 0.3);
+    builder.add_atom_with_velocity("H", 4.0, 5.0, 6.0, false, 1, 1.008, -0.1, 0.0, 0.5);
+    builder.add_atom_with_velocity("Cu", 7.0, 8.0, 9.0, true, 2, 63.546, 0.0, 0.0, 0.0);
+    let frame = builder.build();
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mapping = writer::write_lammps_data(&mut buffer, &frame).expect("Failed to write data.");
+    assert_eq!(mapping, vec![("Cu".to_string(), 1), ("H".to_string(), 2)]);
+
+    let data = String::from_utf8(buffer).expect("Buffer is not valid UTF-8.");
+    let lines: Vec<&str> = data.lines().collect();
+    assert!(lines.contains(&"3 atoms"));
+    assert!(lines.contains(&"2 atom types"));
+    assert!(lines.contains(&"0.0000000000 12.0000000000 ylo yhi"));
+    assert!(!data.contains("xy xz yz"));
+    assert!(lines.contains(&"2 1.0080000000 # H"));
+    assert!(lines.contains(&"2 1 7.0000000000 8.0000000000 9.0000000000"));
+    assert!(lines.contains(&"3 2 4.0000000000 5.0000000000 6.0000000000"));
+    let vel_start = lines.iter().position(|l| *l == "Velocities").unwrap();
+    assert_eq!(
+        lines[vel_start + 4],
+        "3 -0.1000000000 0.0000000000 0.5000000000"
+    );
+
+    // Triclinic cells get a tilt line; a cell with gamma = 60 has xy = b / 2.
+    let mut triclinic = ConFrameBuilder::new([4.0, 4.0, 4.0], [90.0, 90.0, 60.0]);
+    triclinic.add_atom("Cu", 0.0, 0.0, 0.0, false, 0, 63.546);
+    let mut buffer: Vec<u8> = Vec::new();
+    writer::write_lammps_data(&mut buffer, &triclinic.build()).expect("Failed to write data.");
+    let data = String::from_utf8(buffer).expect("Buffer is not valid UTF-8.");
+    let tilt = data.lines().find(|l| l.ends_with("xy xz yz")).unwrap();
+    assert!(tilt.starts_with("2.0000000000 0.0000000000 0.0000000000"));
+    assert!(!data.contains("Velocities"));
+}
+
+/// Reads the frame count, atom count and the first atom's coordinates of the
+/// first frame from a DCD written by `write_dcd`.
+#[cfg(feature = "dcd")]
+fn read_dcd_summary(data: &[u8]) -> (i32, i32, [f32; 3]) {
+    let i32_at = |offset: usize| i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    let f32_at = |offset: usize| f32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    let mut pos = 0;
+    let mut records = Vec::new();
+    while pos < data.len() {
+        let len = i32_at(pos) as usize;
+        assert_eq!(
+            i32_at(pos + 4 + len) as usize,
+            len,
+            "mismatched record markers"
+        );
+        records.push((pos + 4, len));
+        pos += len + 8;
+    }
+    let (header, _) = records[0];
+    assert_eq!(&data[header..header + 4], b"CORD");
+    let nset = i32_at(header + 4);
+    let natoms = i32_at(records[2].0);
+    // Records 3 and 4 are the first frame's unit cell and X array.
+    let x = f32_at(records[4].0);
+    let y = f32_at(records[5].0);
+    let z = f32_at(records[6].0);
+    assert_eq!(records.len(), 3 + nset as usize * 4);
+    (nset, natoms, [x, y, z])
+}
+
+#[cfg(feature = "dcd")]
+#[test]
+fn test_write_dcd() {
+    use std::io::Cursor;
+
+    let fdat =
+        fs::read_to_string(test_case!("tiny_multi_cuh2.con")).expect("Can't find test file.");
+    let frames: Vec<_> = ConFrameIterator::new(&fdat).map(|r| r.unwrap()).collect();
+    let two_frames = &frames[..2];
+
+    let mut cursor = Cursor::new(Vec::new());
+    writer::write_dcd(&mut cursor, two_frames, 1.0).expect("Failed to write DCD.");
+    assert_eq!(cursor.position() as usize, cursor.get_ref().len());
+
+    let (nset, natoms, first) = read_dcd_summary(cursor.get_ref());
+    assert_eq!(nset, 2);
+    assert_eq!(natoms as usize, frames[0].atom_data.len());
+    let atom = &frames[0].atom_data[0];
+    assert_eq!(first, [atom.x as f32, atom.y as f32, atom.z as f32]);
+
+    let mut mismatched = frames[1].clone();
+    mismatched.atom_data.pop();
+    let mut cursor = Cursor::new(Vec::new());
+    let err = writer::write_dcd(&mut cursor, [&frames[0], &mismatched], 1.0).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_write_frames_parallel_matches_sequential() {
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.con")).expect("Can't find test.");
+    let base: Vec<_> = ConFrameIterator::new(&fdat).map(|r| r.unwrap()).collect();
+    // Vary the header so any reordering would show up in the output.
+    let frames: Vec<_> = (0..50)
+        .map(|i| {
+            let mut frame = base[i % base.len()].clone();
+            frame.header.prebox_header[0] = format!("frame {i}");
+            frame
+        })
+        .collect();
+
+    let mut sequential: Vec<u8> = Vec::new();
+    ConFrameWriter::with_precision(&mut sequential, 8)
+        .extend(frames.iter())
+        .unwrap();
+    let mut parallel: Vec<u8> = Vec::new();
+    writer::write_frames_parallel(&mut parallel, &frames, 8).unwrap();
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn test_con_string_matches_writer() {
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.con")).expect("Can't find test.");
+    let frames: Vec<_> = ConFrameIterator::new(&fdat).map(|r| r.unwrap()).collect();
+
+    let mut buffer: Vec<u8> = Vec::new();
+    ConFrameWriter::new(&mut buffer)
+        .extend(frames.iter())
+        .unwrap();
+    assert_eq!(
+        writer::write_con_string(&frames, 6),
+        String::from_utf8(buffer).unwrap()
+    );
+
+    let format = FormatOptions::default()
+        .precision(3)
+        .coord_width(12)
+        .notation(Notation::Scientific);
+    let mut buffer: Vec<u8> = Vec::new();
+    ConFrameWriter::with_format(&mut buffer, format.clone())
+        .extend(frames.iter())
+        .unwrap();
+    let text = writer::write_con_string_with_options(&frames, format);
+    assert_eq!(text, String::from_utf8(buffer).unwrap());
+    assert!(text.contains("e0"));
+
+    let frame = &frames[0];
+    assert_eq!(frame.to_string(), frame.to_con_string(6));
+    assert_eq!(format!("{frame:.3}"), frame.to_con_string(3));
+    let reparsed = ConFrameIterator::new(&frame.to_string())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert!(reparsed.approx_eq(frame, 1e-6));
+}
+
+#[test]
+fn test_writer_finish_returns_inner() {
+    let fdat = fs::read_to_string(test_case!("tiny_cuh2.con")).expect("Can't find test.");
+    let frame = ConFrameIterator::new(&fdat).next().unwrap().unwrap();
+
+    let mut writer = ConFrameWriter::new(Vec::new());
+    writer.write_frame(&frame).unwrap();
+    writer.flush().unwrap();
+    let buffer = writer.finish().unwrap();
+    let text = String::from_utf8(buffer).unwrap();
+    let reparsed = ConFrameIterator::new(&text).next().unwrap().unwrap();
+    assert!(reparsed.approx_eq(&frame, 1e-6));
+}
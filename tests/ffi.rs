@@ -0,0 +1,274 @@
+mod common;
+
+use readcon_core::ffi::{self, RKR_ERR_INCOMPLETE_FRAME, RKR_ERR_IO, RKR_ERR_NULL_POINTER, RKR_OK};
+use readcon_core::iterators::ConFrameIterator;
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::fs;
+use std::ptr;
+
+fn last_error_message() -> String {
+    let mut buffer = [0 as c_char; 256];
+    let len = unsafe { ffi::rkr_last_error_message(buffer.as_mut_ptr(), buffer.len()) };
+    assert!(len >= 0);
+    unsafe { CStr::from_ptr(buffer.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn test_last_error_reporting() {
+    assert_eq!(ffi::rkr_last_error_code(), RKR_OK);
+    assert_eq!(last_error_message(), "");
+
+    let missing = CString::new("does/not/exist.con").unwrap();
+    assert!(unsafe { ffi::rkr_read_first_frame(missing.as_ptr()) }.is_null());
+    assert_eq!(ffi::rkr_last_error_code(), RKR_ERR_IO);
+    assert!(!last_error_message().is_empty());
+
+    assert!(unsafe { ffi::read_con_file_iterator(ptr::null()) }.is_null());
+    assert_eq!(ffi::rkr_last_error_code(), RKR_ERR_NULL_POINTER);
+    assert_eq!(last_error_message(), "filename is NULL");
+
+    let fdat = fs::read_to_string("resources/test/tiny_cuh2.con").unwrap();
+    let lines: Vec<&str> = fdat.lines().collect();
+    let truncated = common::temp_file(".con", lines[..lines.len() - 1].join("\n"));
+    let path = CString::new(truncated.path().to_str().unwrap()).unwrap();
+    let frames = unsafe { ffi::rkr_read_all_frames(path.as_ptr(), &mut 0) };
+    assert!(frames.is_null());
+    assert_eq!(ffi::rkr_last_error_code(), RKR_ERR_INCOMPLETE_FRAME);
+    assert!(last_error_message().contains("atom data"));
+
+    // An empty file has no frames, and only undecodable text is a UTF-8 error.
+    let empty = common::temp_file(".con", "");
+    let path = CString::new(empty.path().to_str().unwrap()).unwrap();
+    assert!(unsafe { ffi::rkr_read_first_frame(path.as_ptr()) }.is_null());
+    assert_eq!(ffi::rkr_last_error_code(), ffi::RKR_ERR_NO_FRAMES);
+    fs::write(empty.path(), [0xffu8, 0xfe]).unwrap();
+    assert!(unsafe { ffi::rkr_read_first_frame(path.as_ptr()) }.is_null());
+    assert_eq!(ffi::rkr_last_error_code(), ffi::RKR_ERR_INVALID_UTF8);
+    assert!(unsafe { ffi::read_con_file_iterator(path.as_ptr()) }.is_null());
+    assert_eq!(ffi::rkr_last_error_code(), ffi::RKR_ERR_INVALID_UTF8);
+
+    // Messages are truncated to the buffer and stay null-terminated.
+    let mut small = [1 as c_char; 5];
+    assert_eq!(
+        unsafe { ffi::rkr_last_error_message(small.as_mut_ptr(), small.len()) },
+        4
+    );
+    assert_eq!(small[4], 0);
+    assert_eq!(
+        unsafe { ffi::rkr_last_error_message(ptr::null_mut(), 8) },
+        -1
+    );
+}
+
+#[test]
+fn test_frame_get_atom() {
+    let path = CString::new("resources/test/tiny_multi_cuh2.con").unwrap();
+    let frame = unsafe { ffi::rkr_read_first_frame(path.as_ptr()) };
+    assert!(!frame.is_null());
+
+    let num_atoms = unsafe { ffi::rkr_frame_num_atoms(frame) };
+    let c_frame_ptr = unsafe { ffi::rkr_frame_to_c_frame(frame) };
+    let c_frame = unsafe { &*c_frame_ptr };
+    assert_eq!(num_atoms, c_frame.num_atoms);
+    let bulk = unsafe { std::slice::from_raw_parts(c_frame.atoms, c_frame.num_atoms) };
+
+    let mut atom = std::mem::MaybeUninit::<ffi::CAtom>::uninit();
+    for (i, expected) in bulk.iter().enumerate() {
+        assert_eq!(
+            unsafe { ffi::rkr_frame_get_atom(frame, i, atom.as_mut_ptr()) },
+            0
+        );
+        let atom = unsafe { atom.assume_init_ref() };
+        assert_eq!(atom.atomic_number, expected.atomic_number);
+        assert_eq!(
+            (atom.x, atom.y, atom.z),
+            (expected.x, expected.y, expected.z)
+        );
+        assert_eq!(atom.mass, expected.mass);
+        assert_eq!(atom.atom_id, expected.atom_id);
+    }
+    assert_eq!(
+        unsafe { ffi::rkr_frame_get_atom(frame, num_atoms, atom.as_mut_ptr()) },
+        -1
+    );
+    assert_eq!(ffi::rkr_last_error_code(), ffi::RKR_ERR_INDEX_OUT_OF_RANGE);
+
+    unsafe {
+        ffi::free_c_frame(c_frame_ptr);
+        ffi::free_rkr_frame(frame);
+    }
+}
+
+#[test]
+fn test_read_con_buffer_iterator() {
+    let fdat = fs::read("resources/test/tiny_multi_cuh2.convel").unwrap();
+    let iterator =
+        unsafe { ffi::read_con_buffer_iterator(fdat.as_ptr() as *const c_char, fdat.len()) };
+    drop(fdat);
+    assert!(!iterator.is_null());
+
+    let mut count = 0;
+    loop {
+        let frame = unsafe { ffi::con_frame_iterator_next(iterator) };
+        if frame.is_null() {
+            break;
+        }
+        assert_eq!(unsafe { ffi::rkr_frame_num_atoms(frame) }, 4);
+        unsafe { ffi::free_rkr_frame(frame) };
+        count += 1;
+    }
+    assert_eq!(count, 2);
+    unsafe { ffi::free_con_frame_iterator(iterator) };
+
+    let invalid = [0xffu8, 0xfe];
+    let iterator = unsafe { ffi::read_con_buffer_iterator(invalid.as_ptr() as *const c_char, 2) };
+    assert!(iterator.is_null());
+    assert_eq!(ffi::rkr_last_error_code(), ffi::RKR_ERR_INVALID_UTF8);
+}
+
+#[test]
+fn test_frame_cell_matrix_and_mass_types() {
+    let path = CString::new("resources/test/tiny_cuh2.con").unwrap();
+    let frame = unsafe { ffi::rkr_read_first_frame(path.as_ptr()) };
+    assert!(!frame.is_null());
+
+    let mut matrix = [0.0f64; 9];
+    assert_eq!(
+        unsafe { ffi::rkr_frame_get_cell_matrix(frame, matrix.as_mut_ptr()) },
+        0
+    );
+    assert_eq!(
+        matrix,
+        [15.3456, 0.0, 0.0, 0.0, 21.702, 0.0, 0.0, 0.0, 100.0]
+    );
+
+    let count =
+        unsafe { ffi::rkr_frame_get_mass_types(frame, ptr::null_mut(), ptr::null_mut(), 0) };
+    assert_eq!(count, 2);
+    let mut symbols = [ptr::null_mut::<c_char>(); 1];
+    let mut masses = [0.0f64; 1];
+    let total = unsafe {
+        ffi::rkr_frame_get_mass_types(frame, symbols.as_mut_ptr(), masses.as_mut_ptr(), 1)
+    };
+    assert_eq!(total, 2);
+    assert_eq!(
+        unsafe { CStr::from_ptr(symbols[0]) }.to_str().unwrap(),
+        "Cu"
+    );
+    assert_eq!(masses[0], 63.546);
+
+    unsafe {
+        ffi::rkr_free_string(symbols[0]);
+        ffi::free_rkr_frame(frame);
+    }
+}
+
+#[test]
+fn test_writer_write_frame_appends() {
+    let input = CString::new("resources/test/tiny_multi_cuh2.con").unwrap();
+    let mut num_frames = 0;
+    let frames = unsafe { ffi::rkr_read_all_frames(input.as_ptr(), &mut num_frames) };
+    assert!(!frames.is_null());
+
+    let out = common::temp_file(".con", "");
+    let out_c = CString::new(out.path().to_str().unwrap()).unwrap();
+    let writer = unsafe { ffi::create_writer_from_path_c(out_c.as_ptr()) };
+    assert!(!writer.is_null());
+    for i in (0..num_frames).rev() {
+        let frame = unsafe { *frames.add(i) };
+        assert_eq!(unsafe { ffi::rkr_writer_write_frame(writer, frame) }, 0);
+    }
+    assert_eq!(
+        unsafe { ffi::rkr_writer_write_frame(writer, ptr::null()) },
+        -1
+    );
+    unsafe { ffi::free_rkr_writer(writer) };
+
+    let written = fs::read_to_string(out.path()).unwrap();
+    let original = fs::read_to_string("resources/test/tiny_multi_cuh2.con").unwrap();
+    let reparsed: Vec<_> = ConFrameIterator::new(&written)
+        .map(|r| r.unwrap())
+        .collect();
+    let mut expected: Vec<_> = ConFrameIterator::new(&original)
+        .map(|r| r.unwrap())
+        .collect();
+    expected.reverse();
+    assert_eq!(reparsed, expected);
+
+    unsafe { ffi::free_rkr_frame_array(frames, num_frames) };
+}
+
+unsafe extern "C" fn collect_output(data: *const u8, len: usize, user_data: *mut c_void) -> isize {
+    let sink = unsafe { &mut *(user_data as *mut Vec<u8>) };
+    sink.extend_from_slice(unsafe { std::slice::from_raw_parts(data, len) });
+    len as isize
+}
+
+unsafe extern "C" fn reject_output(_: *const u8, _: usize, _: *mut c_void) -> isize {
+    -1
+}
+
+#[test]
+fn test_writer_from_callback() {
+    let input = CString::new("resources/test/tiny_multi_cuh2.con").unwrap();
+    let mut num_frames = 0;
+    let frames = unsafe { ffi::rkr_read_all_frames(input.as_ptr(), &mut num_frames) };
+    assert!(!frames.is_null());
+
+    let mut output: Vec<u8> = Vec::new();
+    let sink = &mut output as *mut Vec<u8> as *mut c_void;
+    let writer = unsafe { ffi::create_writer_from_callback_c(Some(collect_output), sink) };
+    assert!(!writer.is_null());
+    assert_eq!(
+        unsafe { ffi::rkr_writer_extend(writer, frames.cast(), num_frames) },
+        0
+    );
+    unsafe { ffi::free_rkr_writer(writer) };
+    let original = fs::read_to_string("resources/test/tiny_multi_cuh2.con").unwrap();
+    let written = String::from_utf8(output).unwrap();
+    let reparsed: Vec<_> = ConFrameIterator::new(&written)
+        .map(|r| r.unwrap())
+        .collect();
+    let expected: Vec<_> = ConFrameIterator::new(&original)
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(reparsed, expected);
+
+    // Output is buffered, so a failing sink is reported when it is flushed.
+    let writer =
+        unsafe { ffi::create_writer_from_callback_c(Some(reject_output), ptr::null_mut()) };
+    assert_eq!(unsafe { ffi::rkr_writer_write_frame(writer, *frames) }, 0);
+    unsafe { ffi::free_rkr_writer(writer) };
+    assert_eq!(ffi::rkr_last_error_code(), RKR_ERR_IO);
+    assert!(last_error_message().contains("write callback failed"));
+
+    assert!(unsafe { ffi::create_writer_from_callback_c(None, ptr::null_mut()) }.is_null());
+    assert_eq!(ffi::rkr_last_error_code(), RKR_ERR_NULL_POINTER);
+
+    unsafe { ffi::free_rkr_frame_array(frames, num_frames) };
+}
+
+#[test]
+fn test_header_line_buffer_bounds() {
+    let path = CString::new("resources/test/tiny_cuh2.con").unwrap();
+    let frame = unsafe { ffi::rkr_read_first_frame(path.as_ptr()) };
+    assert!(!frame.is_null());
+
+    let mut buffer = [1 as c_char; 8];
+    let ret = unsafe { ffi::rkr_frame_get_header_line(frame, true, 0, buffer.as_mut_ptr(), 0) };
+    assert_eq!(ret, -1);
+    assert_eq!(ffi::rkr_last_error_code(), RKR_ERR_NULL_POINTER);
+    assert_eq!(buffer, [1; 8], "a zero-length buffer must not be written");
+
+    let ret = unsafe { ffi::rkr_frame_get_header_line(frame, true, 0, buffer.as_mut_ptr(), 1) };
+    assert_eq!(ret, 0);
+    assert_eq!(buffer[0], 0);
+
+    let ret = unsafe { ffi::rkr_frame_get_header_line(frame, true, 0, buffer.as_mut_ptr(), 7) };
+    assert_eq!(ret, 6);
+    let copied = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+    assert_eq!(copied.to_str().unwrap(), "Random");
+    unsafe { ffi::free_rkr_frame(frame) };
+}
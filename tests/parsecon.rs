@@ -1,7 +1,12 @@
 mod common;
+use readcon_core::error::ParseError;
 use readcon_core::iterators::{self, ConFrameIterator};
+use readcon_core::parser::ParseOptions;
+use readcon_core::types::ConFrame;
+use std::cell::RefCell;
 use std::fs;
 use std::path::Path;
+use std::rc::Rc;
 
 #[test]
 fn test_cuh2_parsing() {
@@ -22,7 +27,7 @@ fn test_cuh2_parsing() {
 
         // Check the first atom
         let first_atom = &frame.atom_data[0];
-        assert_eq!(&*first_atom.symbol, "Cu");
+        assert_eq!(first_atom.symbol_str(), "Cu");
         assert_eq!(first_atom.x, 0.63939999999999997);
         assert_eq!(first_atom.y, 0.90449999999999997);
         assert_eq!(first_atom.z, -0.00009999999999977);
@@ -31,7 +36,7 @@ fn test_cuh2_parsing() {
 
         // Check the last atom
         let last_atom = &frame.atom_data.last().unwrap();
-        assert_eq!(&*last_atom.symbol, "H");
+        assert_eq!(last_atom.symbol_str(), "H");
         assert_eq!(last_atom.x, 7.94209999999999994);
         assert_eq!(last_atom.y, 9.94699999999999918);
         assert_eq!(last_atom.z, 4.75760000000000094);
@@ -64,7 +69,7 @@ fn test_multi_parsing() {
     assert_eq!(first_frame.atom_data.len(), 4);
 
     let first_atom = &first_frame.atom_data[0];
-    assert_eq!(&*first_atom.symbol, "Cu");
+    assert_eq!(first_atom.symbol_str(), "Cu");
     assert_eq!(first_atom.x, 0.6394);
     assert_eq!(first_atom.y, 0.9045);
     assert_eq!(first_atom.z, 6.9753);
@@ -72,7 +77,7 @@ fn test_multi_parsing() {
     assert_eq!(first_atom.atom_id, 0);
 
     let last_atom = &first_frame.atom_data.last().unwrap();
-    assert_eq!(&*last_atom.symbol, "H");
+    assert_eq!(last_atom.symbol_str(), "H");
     assert_eq!(last_atom.x, 7.9421);
     assert_eq!(last_atom.y, 9.947);
     assert_eq!(last_atom.z, 11.733);
@@ -86,7 +91,7 @@ fn test_multi_parsing() {
     assert_eq!(second_frame.atom_data.len(), 4);
 
     let second_atom = &second_frame.atom_data[1];
-    assert_eq!(&*second_atom.symbol, "Cu");
+    assert_eq!(second_atom.symbol_str(), "Cu");
     assert_eq!(second_atom.x, 3.1969);
     assert_eq!(second_atom.y, 0.9045);
     assert_eq!(second_atom.z, 6.9752);
@@ -118,7 +123,7 @@ fn test_iterator_forward() {
         .expect("Parsing second frame should succeed");
     assert_eq!(second_frame.atom_data.len(), 4);
     let second_atom = &second_frame.atom_data[1];
-    assert_eq!(&*second_atom.symbol, "Cu");
+    assert_eq!(second_atom.symbol_str(), "Cu");
     assert_eq!(second_atom.x, 3.1969);
     assert_eq!(second_atom.y, 0.9045);
     assert_eq!(second_atom.z, 6.9752);
@@ -160,7 +165,7 @@ fn test_read_first_frame() {
     let frame = iterators::read_first_frame(&path).expect("read_first_frame should succeed");
     assert_eq!(frame.header.natm_types, 2);
     assert_eq!(frame.atom_data.len(), 218);
-    assert_eq!(&*frame.atom_data[0].symbol, "Cu");
+    assert_eq!(frame.atom_data[0].symbol_str(), "Cu");
     assert_eq!(frame.atom_data[0].atom_id, 0);
 }
 
@@ -170,7 +175,7 @@ fn test_read_first_frame_multi() {
     let path = test_case!("tiny_multi_cuh2.con");
     let frame = iterators::read_first_frame(&path).expect("read_first_frame should succeed");
     assert_eq!(frame.atom_data.len(), 4);
-    assert_eq!(&*frame.atom_data[0].symbol, "Cu");
+    assert_eq!(frame.atom_data[0].symbol_str(), "Cu");
     assert_eq!(frame.atom_data[0].x, 0.6394);
 }
 
@@ -182,3 +187,537 @@ fn test_read_all_frames_matches_iterator() {
     assert_eq!(frames[0].atom_data.len(), 4);
     assert_eq!(frames[1].atom_data.len(), 4);
 }
+
+#[test]
+fn test_read_n_frames() {
+    let path = test_case!("tiny_multi_cuh2.con");
+    let all = iterators::read_all_frames(&path).unwrap();
+    assert_eq!(iterators::read_n_frames(&path, 1).unwrap(), all[..1]);
+    assert_eq!(iterators::read_n_frames(&path, 5).unwrap(), all);
+    assert!(iterators::read_n_frames(&path, 0).unwrap().is_empty());
+
+    // Only the requested frames are parsed, so a bad later frame is not seen.
+    let bad = common::temp_file(".con", frames_with_two_bad());
+    assert_eq!(iterators::read_n_frames(bad.path(), 1).unwrap().len(), 1);
+    assert!(iterators::read_n_frames(bad.path(), 2).is_err());
+}
+
+#[test]
+fn test_read_valid_prefix() {
+    let path = test_case!("tiny_multi_cuh2.con");
+    let (frames, err) = iterators::read_valid_prefix(&path).expect("file should be readable");
+    assert_eq!(frames.len(), 2);
+    assert!(err.is_none());
+
+    // Keep the first frame intact and truncate the second in its atom data.
+    let fdat = fs::read_to_string(&path).expect("Can't find test.");
+    let first = ConFrameIterator::new(&fdat).next().unwrap().unwrap();
+    let first_frame_lines = 9 + 2 * first.header.natm_types + first.atom_data.len();
+    let mut lines: Vec<&str> = fdat.lines().collect();
+    lines.truncate(first_frame_lines + 12);
+    let truncated = common::temp_file(".con", lines.join("\n"));
+
+    let (frames, err) =
+        iterators::read_valid_prefix(truncated.path()).expect("file should be readable");
+    assert_eq!(frames, vec![first]);
+    assert!(matches!(err, Some(ParseError::IncompleteFrame)));
+}
+
+#[test]
+fn test_count_frames() {
+    let path = test_case!("tiny_multi_cuh2.con");
+    assert_eq!(iterators::count_frames(&path).unwrap(), 2);
+    let path = test_case!("tiny_multi_cuh2.convel");
+    let frames = iterators::read_all_frames(&path).unwrap();
+    assert_eq!(iterators::count_frames(&path).unwrap(), frames.len());
+
+    // Cut the second frame short inside its atom lines.
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.con")).expect("Can't find test.");
+    let mut lines: Vec<&str> = fdat.lines().collect();
+    lines.truncate(lines.len() - 2);
+    let truncated = common::temp_file(".con", lines.join("\n"));
+
+    assert!(iterators::count_frames(truncated.path()).is_err());
+    let (count, err) =
+        iterators::count_valid_prefix(truncated.path()).expect("file should be readable");
+    assert_eq!(count, 1);
+    assert!(matches!(err, Some(ParseError::IncompleteFrame)));
+}
+
+/// Four copies of `tiny_cuh2.con`, with frames 1 and 3 given a malformed atom line.
+fn frames_with_two_bad() -> String {
+    let frame = fs::read_to_string(test_case!("tiny_cuh2.con")).expect("Can't find test.");
+    let bad = frame.replacen("3.19699999999999873", "not-a-number", 1);
+    [&frame, &bad, &frame, &bad]
+        .map(|f| f.trim_end())
+        .join("\n")
+}
+
+#[test]
+fn test_on_error_callback_recovering() {
+    let contents = frames_with_two_bad();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let sink = Rc::clone(&seen);
+    let options = ParseOptions {
+        recover: true,
+        on_error: Some(Rc::new(RefCell::new(move |index, err: &ParseError| {
+            sink.borrow_mut().push((index, err.to_string()));
+        }))),
+        ..ParseOptions::default()
+    };
+
+    let results: Vec<_> = ConFrameIterator::with_options(&contents, options).collect();
+    let indices: Vec<usize> = seen.borrow().iter().map(|(i, _)| *i).collect();
+    assert_eq!(indices, vec![1, 3]);
+    assert!(seen.borrow()[0].1.contains("invalid number format"));
+    assert_eq!(results.len(), 4);
+    assert!(results[0].is_ok() && results[2].is_ok());
+    assert!(results[1].is_err() && results[3].is_err());
+}
+
+#[test]
+fn test_validate_reports_every_error() {
+    let errors = iterators::validate(&frames_with_two_bad());
+    let indices: Vec<usize> = errors.iter().map(|(i, _)| *i).collect();
+    assert_eq!(indices, vec![1, 3]);
+    assert!(errors[0].1.to_string().contains("invalid number format"));
+
+    let clean = fs::read_to_string(test_case!("tiny_multi_cuh2.con")).unwrap();
+    assert!(iterators::validate(&clean).is_empty());
+}
+
+#[test]
+fn test_on_error_callback_strict_stops() {
+    let contents = frames_with_two_bad();
+    let calls = Rc::new(RefCell::new(0));
+    let sink = Rc::clone(&calls);
+    let options = ParseOptions {
+        on_error: Some(Rc::new(RefCell::new(move |_, _: &ParseError| {
+            *sink.borrow_mut() += 1
+        }))),
+        ..ParseOptions::default()
+    };
+
+    let results: Vec<_> = ConFrameIterator::with_options(&contents, options).collect();
+    assert_eq!(*calls.borrow(), 1);
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
+
+#[test]
+fn test_frame_index_random_access() {
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.convel")).expect("Can't find test.");
+    let frames: Vec<_> = ConFrameIterator::new(&fdat).map(|r| r.unwrap()).collect();
+    let index = iterators::FrameIndex::build(&fdat).expect("index should build");
+    assert_eq!(index.len(), frames.len());
+
+    let offsets = index.offsets();
+    assert_eq!((offsets[0].byte_offset, offsets[0].line_index), (0, 0));
+    for (i, offset) in offsets.iter().enumerate() {
+        let line = fdat.lines().nth(offset.line_index).unwrap();
+        assert!(fdat[offset.byte_offset..].starts_with(line));
+        assert_eq!(index.frame(&fdat, i).unwrap(), frames[i]);
+    }
+    assert!(matches!(
+        index.frame(&fdat, frames.len()),
+        Err(ParseError::FrameIndexOutOfRange { index: 2, len: 2 })
+    ));
+    // Contents other than those indexed give an error rather than a panic.
+    let short = &fdat[..offsets[1].byte_offset - 1];
+    assert!(matches!(
+        index.frame(short, 1),
+        Err(ParseError::IncompleteFrame)
+    ));
+    let split = offsets[1].byte_offset;
+    let mid_char = format!("{}\u{e9}{}", &fdat[..split - 1], &fdat[split..]);
+    assert!(matches!(
+        index.frame(&mid_char, 0),
+        Err(ParseError::IncompleteFrame)
+    ));
+
+    let lines: Vec<&str> = fdat.lines().collect();
+    let truncated = lines[..lines.len() - 3].join("\n");
+    assert!(matches!(
+        iterators::FrameIndex::build(&truncated),
+        Err(ParseError::IncompleteVelocitySection)
+    ));
+
+    // Building reads only headers, so bad atom lines surface per frame.
+    let corrupt = frames_with_two_bad();
+    let index = iterators::FrameIndex::build(&corrupt).expect("index should build");
+    assert_eq!(index.len(), 4);
+    assert!(index.frame(&corrupt, 0).is_ok());
+    assert!(index.frame(&corrupt, 1).is_err());
+    assert!(index.frame(&corrupt, 2).is_ok());
+    assert!(index.frame(&corrupt, 3).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_frame_index_serde_roundtrip() {
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.con")).expect("Can't find test.");
+    let index = iterators::FrameIndex::build(&fdat).expect("index should build");
+    let json = serde_json::to_string(&index).unwrap();
+    let restored: iterators::FrameIndex = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, index);
+    assert_eq!(
+        restored.frame(&fdat, 1).unwrap(),
+        index.frame(&fdat, 1).unwrap()
+    );
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parse_frames_parallel_corrupt_middle_frame() {
+    let frame = fs::read_to_string(test_case!("tiny_cuh2.con")).expect("Can't find test.");
+    let lines: Vec<&str> = frame.lines().collect();
+    let mut corrupt = lines.clone();
+    corrupt[6] = "two";
+    let fdat = [lines.join("\n"), corrupt.join("\n"), lines.join("\n")].join("\n");
+
+    let results = iterators::parse_frames_parallel(&fdat);
+    assert_eq!(results.len(), 3);
+    let expected = ConFrameIterator::new(&frame).next().unwrap().unwrap();
+    assert_eq!(results[0].as_ref().unwrap(), &expected);
+    assert!(matches!(
+        results[1],
+        Err(ParseError::InvalidNumberFormat(_))
+    ));
+    assert_eq!(results[2].as_ref().unwrap(), &expected);
+
+    let clean = fs::read_to_string(test_case!("tiny_multi_cuh2.convel")).expect("Can't find test.");
+    let sequential: Vec<_> = ConFrameIterator::new(&clean).map(|r| r.unwrap()).collect();
+    let parallel: Vec<_> = iterators::parse_frames_parallel(&clean)
+        .into_iter()
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(parallel, sequential);
+    // Symbols are shared across frames, as with the sequential iterator.
+    for (a, b) in parallel[0].atom_data.iter().zip(&parallel[1].atom_data) {
+        assert!(Rc::ptr_eq(&a.symbol, &b.symbol));
+    }
+}
+
+#[test]
+fn test_indexed_reader_back_to_front() {
+    let fdat = common::labelled_frames(3);
+    let label = |r: Option<Result<ConFrame, ParseError>>| {
+        r.unwrap().unwrap().header.prebox_header[0].clone()
+    };
+
+    let reader = iterators::IndexedFrameReader::new(&fdat).expect("index should build");
+    let reversed: Vec<String> = reader.rev().map(|r| label(Some(r))).collect();
+    assert_eq!(reversed, ["frame 2", "frame 1", "frame 0"]);
+
+    let mut reader = iterators::IndexedFrameReader::new(&fdat).expect("index should build");
+    assert_eq!(label(reader.next_back()), "frame 2");
+    assert_eq!(label(reader.next()), "frame 0");
+    assert_eq!(label(reader.next()), "frame 1");
+    assert!(reader.next_back().is_none());
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn test_indexed_reader_exact_size() {
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.convel")).expect("Can't find test.");
+    let reader = iterators::IndexedFrameReader::new(&fdat).expect("index should build");
+    let len = reader.len();
+    assert_eq!(len, 2);
+    assert_eq!(reader.collect::<Vec<_>>().len(), len);
+
+    let mut reader = iterators::IndexedFrameReader::new(&fdat).expect("index should build");
+    reader.next_back();
+    assert_eq!(reader.len(), 1);
+    assert_eq!(reader.size_hint(), (1, Some(1)));
+    reader.next();
+    assert_eq!(reader.len(), 0);
+}
+
+#[test]
+fn test_skip_frames() {
+    let fdat = common::labelled_frames(5);
+
+    let mut iter = ConFrameIterator::new(&fdat);
+    assert_eq!(iter.skip_frames(0).unwrap(), 0);
+    assert_eq!(iter.skip_frames(3).unwrap(), 3);
+    let next = iter.next().unwrap().unwrap();
+    assert_eq!(next.header.prebox_header[0], "frame 3");
+    assert_eq!(iter.skip_frames(10).unwrap(), 1);
+    assert!(iter.next().is_none());
+
+    let truncated: String = fdat.lines().take(30).collect::<Vec<_>>().join("\n");
+    let mut iter = ConFrameIterator::new(&truncated);
+    assert!(matches!(
+        iter.skip_frames(5),
+        Err(ParseError::IncompleteFrame)
+    ));
+}
+
+#[test]
+fn test_stride() {
+    let fdat = common::labelled_frames(7);
+    let labels = |k: usize| -> Vec<String> {
+        ConFrameIterator::new(&fdat)
+            .stride(k)
+            .map(|r| r.unwrap().header.prebox_header[0].clone())
+            .collect()
+    };
+
+    assert_eq!(labels(3), ["frame 0", "frame 3", "frame 6"]);
+    assert_eq!(labels(4), ["frame 0", "frame 4"]);
+    assert_eq!(labels(1).len(), 7);
+    assert_eq!(labels(10), ["frame 0"]);
+
+    // A truncated frame inside a skipped stretch is reported once.
+    let truncated: String = fdat.lines().take(30).collect::<Vec<_>>().join("\n");
+    let results: Vec<_> = ConFrameIterator::new(&truncated).stride(3).collect();
+    assert_eq!(results.len(), 2);
+    assert!(matches!(results[1], Err(ParseError::IncompleteFrame)));
+}
+
+#[test]
+#[should_panic(expected = "stride must be at least 1")]
+fn test_stride_zero_panics() {
+    let _ = ConFrameIterator::new("").stride(0);
+}
+
+#[test]
+fn test_leading_bom_is_skipped() {
+    let plain = fs::read_to_string(test_case!("tiny_cuh2.con")).expect("Can't find test.");
+    let expected = ConFrameIterator::new(&plain).next().unwrap().unwrap();
+    let with_bom = format!("\u{FEFF}{plain}");
+
+    let frame = ConFrameIterator::new(&with_bom).next().unwrap().unwrap();
+    assert_eq!(frame, expected);
+    let mut reader = iterators::IndexedFrameReader::new(&with_bom).expect("index should build");
+    assert_eq!(reader.next().unwrap().unwrap(), expected);
+
+    let file = common::temp_file(".con", &with_bom);
+    assert_eq!(
+        iterators::read_all_frames(file.path()).unwrap(),
+        vec![expected]
+    );
+}
+
+#[test]
+fn test_symbols_shared_across_frames() {
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.con")).expect("Can't find test.");
+    let frames: Vec<ConFrame> = ConFrameIterator::new(&fdat).map(|r| r.unwrap()).collect();
+    assert!(frames.len() > 1);
+    let first = &frames[0].atom_data;
+    for frame in &frames[1..] {
+        for (a, b) in first.iter().zip(&frame.atom_data) {
+            assert!(Rc::ptr_eq(&a.symbol, &b.symbol));
+        }
+    }
+}
+
+#[test]
+fn test_filter_by_header() {
+    // Odd frames get a cell twice as long in x.
+    let fdat: String = (0..6)
+        .map(|i| {
+            let labelled = common::labelled_frame(i);
+            if i % 2 == 1 {
+                labelled.replacen("15.345600\t", "30.691200\t", 1)
+            } else {
+                labelled
+            }
+        })
+        .collect();
+
+    let mut iter = ConFrameIterator::new(&fdat);
+    assert_eq!(
+        iter.peek_header().unwrap().unwrap().prebox_header[0],
+        "frame 0"
+    );
+    assert_eq!(
+        iter.peek_header().unwrap().unwrap().prebox_header[0],
+        "frame 0"
+    );
+
+    let labels: Vec<String> = ConFrameIterator::new(&fdat)
+        .filter_by_header(|h| h.boxl[0] > 20.0)
+        .map(|r| r.unwrap().header.prebox_header[0].clone())
+        .collect();
+    assert_eq!(labels, ["frame 1", "frame 3", "frame 5"]);
+
+    let none: Vec<_> = ConFrameIterator::new(&fdat)
+        .filter_by_header(|_| false)
+        .collect();
+    assert!(none.is_empty());
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_read_gzip_compressed() {
+    use std::io::Write;
+
+    let plain = fs::read_to_string(test_case!("tiny_multi_cuh2.con")).expect("Can't find test.");
+    let expected = iterators::read_all_frames(&test_case!("tiny_multi_cuh2.con")).unwrap();
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(plain.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    // Detected by extension, and by magic bytes when the extension is missing.
+    for suffix in [".con.gz", ".con"] {
+        let file = common::temp_file(suffix, &compressed);
+        let frames = iterators::read_all_frames(file.path());
+        let streamed: Result<Vec<ConFrame>, _> = iterators::ConFrameReader::open(file.path())
+            .unwrap()
+            .collect();
+        assert_eq!(frames.unwrap(), expected);
+        assert_eq!(streamed.unwrap(), expected);
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_read_all_frames_async() {
+    let path = test_case!("tiny_multi_cuh2.convel");
+    let frames = iterators::read_all_frames_async(&path).await.unwrap();
+    assert_eq!(frames, iterators::read_all_frames(&path).unwrap());
+    assert!(
+        iterators::read_all_frames_async(Path::new("does/not/exist.con"))
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn test_con_frame_reader_matches_iterator() {
+    for name in [
+        "tiny_cuh2.con",
+        "tiny_multi_cuh2.con",
+        "tiny_cuh2.convel",
+        "tiny_multi_cuh2.convel",
+        "tiny_cuh2_crlf.convel",
+        "sulfolene.con",
+    ] {
+        let fdat = fs::read_to_string(test_case!(name)).expect("Can't find test.");
+        let expected: Vec<ConFrame> = ConFrameIterator::new(&fdat).map(Result::unwrap).collect();
+        let streamed: Vec<ConFrame> = iterators::ConFrameReader::new(fdat.as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(streamed, expected, "{name}");
+    }
+
+    let fdat = fs::read_to_string(test_case!("tiny_cuh2_truncated.convel")).unwrap();
+    let mut reader = iterators::ConFrameReader::new(fdat.as_bytes());
+    let err = reader.next().unwrap().unwrap_err();
+    assert!(err.downcast_ref::<ParseError>().is_some());
+
+    // A bad counts line ends the stream instead of resyncing mid-frame.
+    let good = fs::read_to_string(test_case!("tiny_cuh2.con")).expect("Can't find test.");
+    let bad = good.replacen("\n2 2\n", "\n2 x\n", 1);
+    let fdat = format!("{bad}{good}{good}");
+    let mut reader = iterators::ConFrameReader::new(fdat.as_bytes());
+    assert!(reader.next().unwrap().is_err());
+    assert!(reader.next().is_none());
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn test_trajectory_stats() {
+    let stats = iterators::stats(&test_case!("tiny_multi_cuh2.con")).unwrap();
+    let frames: Vec<ConFrame> =
+        iterators::read_all_frames(&test_case!("tiny_multi_cuh2.con")).unwrap();
+    assert_eq!(stats.frames, frames.len());
+    let counts: Vec<usize> = frames.iter().map(|f| f.atom_data.len()).collect();
+    assert_eq!(stats.min_atoms, *counts.iter().min().unwrap());
+    assert_eq!(stats.max_atoms, *counts.iter().max().unwrap());
+    assert!(!stats.has_velocities);
+    let symbols: Vec<&str> = stats.symbols.iter().map(String::as_str).collect();
+    assert_eq!(symbols, ["Cu", "H"]);
+
+    let stats = iterators::stats(&test_case!("tiny_cuh2.convel")).unwrap();
+    assert_eq!((stats.frames, stats.mean_atoms), (1, 4.0));
+    assert!(stats.has_velocities);
+
+    assert!(iterators::stats(&test_case!("tiny_cuh2_truncated.convel")).is_err());
+}
+
+#[test]
+fn test_optional_coordinate_labels() {
+    for file in ["tiny_multi_cuh2.con", "tiny_cuh2.convel"] {
+        let contents = fs::read_to_string(test_case!(file)).unwrap();
+        let unlabelled: String = contents
+            .lines()
+            .filter(|line| !line.starts_with("Coordinates of Component"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let lenient = ParseOptions {
+            optional_coordinate_labels: true,
+            ..ParseOptions::default()
+        };
+
+        let expected: Vec<ConFrame> = ConFrameIterator::new(&contents)
+            .map(Result::unwrap)
+            .collect();
+        let parsed: Vec<ConFrame> = ConFrameIterator::with_options(&unlabelled, lenient.clone())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(parsed, expected, "{file}");
+        // Labelled files still parse the same in lenient mode.
+        let relabelled: Vec<ConFrame> = ConFrameIterator::with_options(&contents, lenient.clone())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(relabelled, expected, "{file}");
+
+        let mut skipper = ConFrameIterator::with_options(&unlabelled, lenient);
+        let mut skipped = 0;
+        while let Some(result) = skipper.forward() {
+            result.unwrap();
+            skipped += 1;
+        }
+        assert_eq!(skipped, expected.len(), "{file}");
+
+        assert!(
+            ConFrameIterator::new(&unlabelled).any(|r| r.is_err()),
+            "{file}"
+        );
+    }
+}
+
+#[test]
+fn test_comments_and_blank_lines_between_frames() {
+    let contents = fs::read_to_string(test_case!("tiny_multi_cuh2.con")).unwrap();
+    let expected: Vec<ConFrame> = ConFrameIterator::new(&contents)
+        .map(Result::unwrap)
+        .collect();
+    let frame_text = expected[0].to_con_string(17);
+    let noisy =
+        format!("# written by some tool\n\n{frame_text}\n  # step 2\n\n\n{frame_text}# end\n");
+    let options = ParseOptions {
+        skip_blank_lines: true,
+        comment_prefix: Some("#".to_string()),
+        ..ParseOptions::default()
+    };
+
+    let frames: Vec<ConFrame> = ConFrameIterator::with_options(&noisy, options.clone())
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(frames.len(), 2);
+    assert!(frames.iter().all(|f| f.approx_eq(&expected[0], 1e-12)));
+    assert_eq!(
+        frames[1].header.prebox_header,
+        expected[0].header.prebox_header
+    );
+
+    let mut skipper = ConFrameIterator::with_options(&noisy, options);
+    assert!(skipper.peek_header().unwrap().is_ok());
+    assert!(matches!(skipper.forward(), Some(Ok(()))));
+    assert!(matches!(skipper.forward(), Some(Ok(()))));
+    assert!(skipper.forward().is_none());
+
+    // Without a comment prefix the comment is read as the first header line.
+    let plain = ParseOptions {
+        skip_blank_lines: true,
+        ..ParseOptions::default()
+    };
+    let first = ConFrameIterator::with_options(&noisy, plain)
+        .next()
+        .unwrap();
+    assert!(first.is_err_and(|e| matches!(e, ParseError::InvalidNumberFormat(_))));
+}
@@ -1,5 +1,7 @@
 mod common;
+use readcon_core::error::ParseError;
 use readcon_core::iterators::ConFrameIterator;
+use readcon_core::parser::{self, ParseOptions};
 use std::fs;
 use std::path::Path;
 
@@ -21,7 +23,7 @@ fn test_convel_single_frame() {
 
     // Check coordinate data is still correct
     let first_atom = &frame.atom_data[0];
-    assert_eq!(&*first_atom.symbol, "Cu");
+    assert_eq!(first_atom.symbol_str(), "Cu");
     assert!((first_atom.x - 0.6394).abs() < 1e-4);
     assert!(first_atom.is_fixed);
 
@@ -31,7 +33,7 @@ fn test_convel_single_frame() {
     assert_eq!(first_atom.vz, Some(-0.003456));
 
     let last_atom = &frame.atom_data[3];
-    assert_eq!(&*last_atom.symbol, "H");
+    assert_eq!(last_atom.symbol_str(), "H");
     assert_eq!(last_atom.vx, Some(0.045678));
     assert_eq!(last_atom.vy, Some(-0.056789));
     assert_eq!(last_atom.vz, Some(-0.06789));
@@ -47,11 +49,7 @@ fn test_convel_multi_frame() {
     let frames: Vec<_> = parser
         .map(|r| r.expect("Failed to parse convel frame"))
         .collect();
-    assert_eq!(
-        frames.len(),
-        2,
-        "Expected 2 frames in multi-frame convel"
-    );
+    assert_eq!(frames.len(), 2, "Expected 2 frames in multi-frame convel");
 
     // Both frames should have velocities
     assert!(frames[0].has_velocities());
@@ -74,8 +72,7 @@ fn test_convel_multi_frame() {
 
 #[test]
 fn test_con_files_have_no_velocities() {
-    let fdat =
-        fs::read_to_string(test_case!("tiny_cuh2.con")).expect("Can't find con test file.");
+    let fdat = fs::read_to_string(test_case!("tiny_cuh2.con")).expect("Can't find con test file.");
     let parser = ConFrameIterator::new(&fdat);
 
     let frames: Vec<_> = parser
@@ -113,3 +110,187 @@ fn test_convel_forward_skip() {
     // No more frames
     assert!(parser.next().is_none());
 }
+
+/// Drops the symbol line that precedes each "Velocities of Component" line.
+fn strip_velocity_symbol_lines(contents: &str) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut out = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        let next_is_velocity_label = lines
+            .get(i + 1)
+            .is_some_and(|next| next.contains("Velocities of Component"));
+        if !next_is_velocity_label {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[test]
+fn test_convel_without_velocity_symbol_lines() {
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.convel"))
+        .expect("Can't find multi convel test file.");
+    let stripped = strip_velocity_symbol_lines(&fdat);
+    let options = ParseOptions {
+        velocity_symbol_line: false,
+        ..ParseOptions::default()
+    };
+
+    let expected: Vec<_> = ConFrameIterator::new(&fdat)
+        .map(|r| r.expect("Failed to parse convel frame"))
+        .collect();
+    let frames: Vec<_> = ConFrameIterator::with_options(&stripped, options.clone())
+        .map(|r| r.expect("Failed to parse convel frame without symbol lines"))
+        .collect();
+    assert_eq!(frames, expected);
+
+    // forward() must skip the shorter velocity blocks too.
+    let mut parser = ConFrameIterator::with_options(&stripped, options);
+    assert!(parser.forward().unwrap().is_ok());
+    let second = parser.next().unwrap().expect("second frame should parse");
+    assert_eq!(second.atom_data[0].vx, Some(0.001111));
+    assert!(parser.next().is_none());
+
+    // Parsing with the default options reports the layout mismatch.
+    let err = ConFrameIterator::new(&stripped)
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert!(matches!(err, ParseError::VelocityBlockMismatch { .. }));
+}
+
+#[test]
+fn test_convel_crlf_line_endings() {
+    let crlf = fs::read_to_string(test_case!("tiny_cuh2_crlf.convel"))
+        .expect("Can't find CRLF convel test file.");
+    assert!(crlf.contains("\r\n"), "fixture lost its CRLF line endings");
+    let lf =
+        fs::read_to_string(test_case!("tiny_cuh2.convel")).expect("Can't find convel test file.");
+
+    let crlf_frame = ConFrameIterator::new(&crlf)
+        .next()
+        .expect("no frame")
+        .expect("Failed to parse CRLF convel frame");
+    let lf_frame = ConFrameIterator::new(&lf).next().unwrap().unwrap();
+
+    assert_eq!(
+        crlf_frame.header.prebox_header,
+        lf_frame.header.prebox_header
+    );
+    assert_eq!(
+        crlf_frame.header.postbox_header,
+        lf_frame.header.postbox_header
+    );
+    assert!(crlf_frame.has_velocities());
+    assert_eq!(crlf_frame.atom_data, lf_frame.atom_data);
+}
+
+#[test]
+fn test_truncated_velocity_section_all_paths() {
+    let fdat = fs::read_to_string(test_case!("tiny_cuh2_truncated.convel"))
+        .expect("Can't find truncated convel test file.");
+
+    let mut iter = ConFrameIterator::new(&fdat);
+    assert!(matches!(
+        iter.next(),
+        Some(Err(ParseError::IncompleteVelocitySection))
+    ));
+    assert!(iter.next().is_none());
+
+    let mut iter = ConFrameIterator::new(&fdat);
+    assert!(matches!(
+        iter.forward(),
+        Some(Err(ParseError::IncompleteVelocitySection))
+    ));
+
+    // Also when the truncated frame follows a complete one.
+    let complete =
+        fs::read_to_string(test_case!("tiny_cuh2.convel")).expect("Can't find convel test file.");
+    let two = format!("{complete}{fdat}");
+    let mut iter = ConFrameIterator::new(&two);
+    assert!(iter.next().unwrap().is_ok());
+    assert!(matches!(
+        iter.next(),
+        Some(Err(ParseError::IncompleteVelocitySection))
+    ));
+    let mut iter = ConFrameIterator::new(&two);
+    assert!(matches!(iter.forward(), Some(Ok(()))));
+    assert!(matches!(
+        iter.forward(),
+        Some(Err(ParseError::IncompleteVelocitySection))
+    ));
+
+    #[cfg(feature = "parallel")]
+    {
+        let results = readcon_core::iterators::parse_frames_parallel(&two);
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            results[1],
+            Err(ParseError::IncompleteVelocitySection)
+        ));
+    }
+}
+
+/// `contents` with every "Velocities of Component N" line removed.
+fn without_velocity_labels(contents: &str) -> String {
+    contents
+        .lines()
+        .filter(|line| !line.contains("Velocities of Component"))
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+#[test]
+fn test_convel_without_velocity_labels() {
+    for name in ["tiny_cuh2.convel", "tiny_multi_cuh2.convel"] {
+        let fdat = fs::read_to_string(test_case!(name)).expect("Can't find convel test file.");
+        let expected: Vec<_> = ConFrameIterator::new(&fdat).map(Result::unwrap).collect();
+        let unlabelled = without_velocity_labels(&fdat);
+        let lenient = ParseOptions {
+            optional_velocity_labels: true,
+            ..ParseOptions::default()
+        };
+
+        let frames: Vec<_> = ConFrameIterator::with_options(&unlabelled, lenient.clone())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(frames, expected, "{name}");
+        assert!(frames.iter().all(|f| f.has_velocities()));
+        let blank_tolerant = ParseOptions {
+            skip_blank_lines: true,
+            ..lenient.clone()
+        };
+        let frames: Vec<_> = ConFrameIterator::with_options(&unlabelled, blank_tolerant)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(frames, expected, "{name}");
+
+        let mut skipper = ConFrameIterator::with_options(&unlabelled, lenient);
+        for _ in &expected {
+            assert!(matches!(skipper.forward(), Some(Ok(()))));
+        }
+        assert!(skipper.forward().is_none());
+
+        let first = ConFrameIterator::new(&unlabelled).next().unwrap();
+        assert!(matches!(
+            first,
+            Err(ParseError::VelocityBlockMismatch { component: 1, .. })
+        ));
+    }
+}
+
+#[test]
+fn test_counted_parse_includes_velocities() {
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.convel")).unwrap();
+    let expected: Vec<_> = ConFrameIterator::new(&fdat).map(Result::unwrap).collect();
+    let mut lines = fdat.lines().peekable();
+    let (first, consumed) = parser::parse_single_frame_counted(&mut lines).unwrap();
+    assert_eq!(first, expected[0]);
+    // Header, two coordinate blocks, blank separator, two velocity blocks.
+    assert_eq!(consumed, 9 + 2 * 4 + 1 + 2 * 4);
+    let (second, _) = parser::parse_single_frame_counted(&mut lines).unwrap();
+    assert_eq!(second, expected[1]);
+    assert!(lines.next().is_none());
+}
@@ -1,4 +1,5 @@
 import os
+import pickle
 import tempfile
 import pytest
 
@@ -57,6 +58,77 @@ class TestReadConvel:
         assert frames[1].has_velocities
 
 
+class TestReadConIter:
+    def test_iterates_lazily(self):
+        it = readcon.read_con_iter(_resource("tiny_multi_cuh2.con"))
+        assert iter(it) is it
+        first = next(it)
+        assert len(first) == 4
+        rest = list(it)
+        assert len(rest) == 1
+
+    def test_matches_read_con(self):
+        path = _resource("tiny_multi_cuh2.convel")
+        eager = readcon.read_con(path)
+        lazy = list(readcon.read_con_iter(path))
+        assert [len(f) for f in lazy] == [len(f) for f in eager]
+        assert lazy[1].atoms[0].vx == pytest.approx(eager[1].atoms[0].vx)
+
+    def test_parse_error_raises_on_next(self, tmp_path):
+        with open(_resource("tiny_cuh2.con")) as f:
+            good = f.read()
+        path = tmp_path / "bad.con"
+        path.write_text(good + "\n".join(good.splitlines()[:9]) + "\n")
+        it = readcon.read_con_iter(str(path))
+        next(it)
+        with pytest.raises(IOError):
+            next(it)
+
+    def test_missing_file(self):
+        with pytest.raises(IOError):
+            readcon.read_con_iter("/nonexistent/file.con")
+
+
+class TestTrajectory:
+    def test_len_and_index(self):
+        path = _resource("tiny_multi_cuh2.convel")
+        eager = readcon.read_con(path)
+        traj = readcon.load(path)
+        assert len(traj) == 2
+        assert traj[1].atoms[0].vx == pytest.approx(eager[1].atoms[0].vx)
+
+    def test_negative_index(self):
+        traj = readcon.load(_resource("tiny_multi_cuh2.con"))
+        assert traj[-1].atoms[2].x == pytest.approx(8.8549)
+        with pytest.raises(IndexError):
+            traj[-3]
+        with pytest.raises(IndexError):
+            traj[2]
+
+    def test_slicing(self):
+        traj = readcon.load(_resource("tiny_multi_cuh2.con"))
+        assert len(traj[:]) == 2
+        assert len(traj[::2]) == 1
+        reversed_frames = traj[::-1]
+        assert [f.atoms[2].x for f in reversed_frames] == pytest.approx([8.8549, 8.6823])
+        assert traj[5:] == []
+
+    def test_corrupt_late_frame_only_fails_on_access(self, tmp_path):
+        with open(_resource("tiny_cuh2.con")) as f:
+            good = f.read()
+        bad = good.replace("3.19699999999999873", "not-a-number", 1)
+        path = tmp_path / "corrupt.con"
+        path.write_text(good + good + bad)
+        traj = readcon.load(str(path))
+        assert len(traj) == 3
+        assert len(traj[0]) == 4
+        assert len(traj[1]) == 4
+        with pytest.raises(IOError):
+            traj[2]
+        with pytest.raises(IOError):
+            traj[-1]
+
+
 class TestReadConString:
     def test_read_string(self):
         with open(_resource("tiny_cuh2.con")) as f:
@@ -66,6 +138,25 @@ class TestReadConString:
         assert len(frames[0]) == 4
 
 
+class TestValidateConString:
+    def test_valid_contents(self):
+        with open(_resource("tiny_multi_cuh2.con")) as f:
+            contents = f.read()
+        assert readcon.validate_con_string(contents) == []
+
+    def test_mass_count_mismatch(self):
+        with open(_resource("tiny_multi_cuh2.con")) as f:
+            lines = f.read().splitlines()
+        # Line 9 of the second frame lists one mass for two atom types.
+        frame_len = len(lines) // 2
+        lines[frame_len + 8] = lines[frame_len + 8].split()[0]
+        issues = readcon.validate_con_string("\n".join(lines) + "\n")
+        assert len(issues) == 1
+        index, message = issues[0]
+        assert index == 1
+        assert "masses" in message
+
+
 class TestWriteCon:
     def test_roundtrip(self):
         frames = readcon.read_con(_resource("tiny_multi_cuh2.con"))
@@ -88,6 +179,28 @@ class TestWriteCon:
         assert len(frames2[0]) == len(frames[0])
 
 
+class TestConWriter:
+    def test_streaming_write(self, tmp_path):
+        frames = readcon.read_con(_resource("tiny_multi_cuh2.convel"))
+        path = str(tmp_path / "stream.convel")
+        with readcon.ConWriter(path, precision=6) as w:
+            for frame in frames:
+                w.write(frame)
+        reread = readcon.read_con(path)
+        assert len(reread) == 2
+        assert reread[1].has_velocities
+        assert reread[1].atoms[2].x == pytest.approx(frames[1].atoms[2].x)
+
+    def test_write_after_close_raises(self, tmp_path):
+        frame = readcon.read_con(_resource("tiny_cuh2.con"))[0]
+        w = readcon.ConWriter(str(tmp_path / "closed.con"))
+        w.write(frame)
+        w.close()
+        w.close()
+        with pytest.raises(ValueError):
+            w.write(frame)
+
+
 class TestConvelWriteRoundtrip:
     def test_convel_roundtrip(self):
         frames = readcon.read_con(_resource("tiny_cuh2.convel"))
@@ -168,6 +281,101 @@ class TestConFrameConstructor:
         assert "natoms=1" in r
 
 
+class TestArrays:
+    def test_symbols(self):
+        frame = readcon.read_con(_resource("tiny_cuh2.con"))[0]
+        assert frame.symbols == ["Cu", "Cu", "H", "H"]
+
+    def test_positions_and_masses(self):
+        np = pytest.importorskip("numpy")
+        frame = readcon.read_con(_resource("tiny_cuh2.con"))[0]
+        positions = frame.positions
+        assert positions.shape == (4, 3)
+        assert positions.dtype == np.float64
+        assert positions[0, 0] == pytest.approx(frame.atoms[0].x)
+        assert frame.masses.shape == (4,)
+        assert frame.masses[0] == pytest.approx(63.546, abs=0.01)
+        assert frame.velocities is None
+
+    def test_velocities(self):
+        pytest.importorskip("numpy")
+        frame = readcon.read_con(_resource("tiny_cuh2.convel"))[0]
+        velocities = frame.velocities
+        assert velocities.shape == (4, 3)
+        assert velocities[0, 0] == pytest.approx(0.001234, abs=1e-6)
+
+
+class TestFromArrays:
+    def test_roundtrip_through_arrays(self):
+        np = pytest.importorskip("numpy")
+        frame = readcon.read_con(_resource("tiny_cuh2.convel"))[0]
+        fixed = np.array([a.is_fixed for a in frame.atoms])
+        rebuilt = readcon.ConFrame.from_arrays(
+            frame.symbols,
+            frame.positions,
+            frame.cell,
+            frame.angles,
+            velocities=frame.velocities,
+            fixed=fixed,
+            masses=frame.masses,
+        )
+        assert rebuilt.has_velocities
+        assert rebuilt.symbols == frame.symbols
+        np.testing.assert_allclose(rebuilt.positions, frame.positions)
+        np.testing.assert_allclose(rebuilt.velocities, frame.velocities)
+        assert [a.is_fixed for a in rebuilt.atoms] == list(fixed)
+
+    def test_minimal(self):
+        np = pytest.importorskip("numpy")
+        frame = readcon.ConFrame.from_arrays(
+            ["Cu", "H"], np.zeros((2, 3)), [10.0, 10.0, 10.0], [90.0, 90.0, 90.0]
+        )
+        assert len(frame) == 2
+        assert not frame.has_velocities
+        assert frame.velocities is None
+
+    def test_length_mismatch_raises(self):
+        np = pytest.importorskip("numpy")
+        with pytest.raises(ValueError):
+            readcon.ConFrame.from_arrays(
+                ["Cu", "H"], np.zeros((3, 3)), [10.0] * 3, [90.0] * 3
+            )
+        with pytest.raises(ValueError):
+            readcon.ConFrame.from_arrays(
+                ["Cu", "H"],
+                np.zeros((2, 3)),
+                [10.0] * 3,
+                [90.0] * 3,
+                masses=np.ones(3),
+            )
+
+
+class TestEqualityAndPickle:
+    def test_value_equality(self):
+        path = _resource("tiny_multi_cuh2.convel")
+        first, second = readcon.read_con(path), readcon.read_con(path)
+        assert first[0] == second[0]
+        assert first[0] != first[1]
+        assert first[0].atoms[0] == second[0].atoms[0]
+        assert first[0].atoms[0] != first[0].atoms[1]
+
+    def test_hash_as_dict_key(self):
+        path = _resource("tiny_cuh2.con")
+        frame = readcon.read_con(path)[0]
+        cache = {frame: "seen"}
+        assert cache[readcon.read_con(path)[0]] == "seen"
+        assert hash(frame.atoms[0]) == hash(readcon.read_con(path)[0].atoms[0])
+
+    def test_pickle_roundtrip(self):
+        frame = readcon.read_con(_resource("tiny_cuh2.convel"))[0]
+        restored = pickle.loads(pickle.dumps(frame))
+        assert restored == frame
+        assert restored.has_velocities
+        assert restored.prebox_header == frame.prebox_header
+        atom = pickle.loads(pickle.dumps(frame.atoms[0]))
+        assert atom == frame.atoms[0]
+
+
 class TestMass:
     def test_mass_from_file(self):
         frames = readcon.read_con(_resource("tiny_cuh2.con"))
@@ -217,6 +425,24 @@ class TestPrecision:
         frames2 = readcon.read_con_string(output)
         assert frames2[0].atoms[0].x == pytest.approx(1.23456789012345, abs=1e-14)
 
+    def test_format_options(self):
+        frame = readcon.ConFrame(
+            cell=[10.0, 10.0, 10.0],
+            angles=[90.0, 90.0, 90.0],
+            atoms=[readcon.Atom(symbol="Cu", x=1.23456789, y=-0.5, z=0.0, atom_id=3)],
+        )
+        default = readcon.write_con_string([frame])
+        assert default == readcon.write_con_string(
+            [frame], precision=6, column_width=None, scientific=False
+        )
+
+        padded = readcon.write_con_string([frame], precision=3, column_width=10)
+        assert "     1.235     -0.500      0.000 0 3" in padded.splitlines()
+
+        sci = readcon.write_con_string([frame], precision=3, scientific=True)
+        assert "1.235e0 -5.000e-1 0.000e0 0 3" in sci.splitlines()
+        assert readcon.read_con_string(sci)[0].atoms[0].x == pytest.approx(1.235)
+
 
 class TestErrorHandling:
     def test_bad_file_path(self):
@@ -226,3 +452,23 @@ class TestErrorHandling:
     def test_malformed_data(self):
         with pytest.raises(OSError):
             readcon.read_con_string("not a valid con file\n")
+
+
+class TestAseVelocities:
+    def test_roundtrip_keeps_velocities(self):
+        pytest.importorskip("ase")
+        frame = readcon.read_con(_resource("tiny_cuh2.convel"))[0]
+        ase_atoms = frame.to_ase()
+        assert ase_atoms.has("momenta")
+        back = readcon.ConFrame.from_ase(ase_atoms)
+        assert back.has_velocities
+        for orig, new in zip(frame.atoms, back.atoms):
+            assert new.vx == pytest.approx(orig.vx, abs=1e-9)
+            assert new.vz == pytest.approx(orig.vz, abs=1e-9)
+
+    def test_no_momenta_means_no_velocities(self):
+        pytest.importorskip("ase")
+        frame = readcon.read_con(_resource("tiny_cuh2.con"))[0]
+        back = readcon.ConFrame.from_ase(frame.to_ase())
+        assert not back.has_velocities
+        assert back.atoms[0].vx is None
@@ -0,0 +1,104 @@
+#![cfg(feature = "rpc")]
+
+mod common;
+
+use readcon_core::iterators::ConFrameIterator;
+use readcon_core::rpc::client::RpcClient;
+use readcon_core::rpc::server;
+use readcon_core::writer;
+use std::fs;
+use std::path::Path;
+
+/// Starts a server on a free loopback port in a background thread and
+/// returns its address. The thread lives until the test binary exits.
+fn spawn_server() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Can't bind loopback.");
+    let addr = listener.local_addr().unwrap().to_string();
+    listener.set_nonblocking(true).unwrap();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let local = tokio::task::LocalSet::new();
+        local.block_on(&runtime, async {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            server::serve(listener).await.unwrap();
+        });
+    });
+    addr
+}
+
+#[test]
+fn test_parse_bytes_loopback() {
+    let client = RpcClient::new(&spawn_server()).unwrap();
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.convel")).expect("Can't find test.");
+    let expected: Vec<_> = ConFrameIterator::new(&fdat).map(|r| r.unwrap()).collect();
+
+    let frames = client.parse_bytes(fdat.as_bytes()).unwrap();
+    assert_eq!(frames, expected);
+
+    // A file the server rejects is an error, not a frame parsed locally.
+    let truncated = fdat.lines().take(12).collect::<Vec<_>>().join("\n");
+    assert!(client.parse_bytes(truncated.as_bytes()).is_err());
+}
+
+#[test]
+fn test_parse_bytes_streaming_loopback() {
+    let client = RpcClient::new(&spawn_server()).unwrap();
+    // More frames than one default chunk, so several pushes are needed.
+    let fdat = common::labelled_frames(150);
+    let expected: Vec<String> = (0..150).map(|i| format!("frame {i}")).collect();
+
+    let mut labels = Vec::new();
+    client
+        .parse_bytes_streaming(fdat.as_bytes(), |frame| {
+            labels.push(frame.header.prebox_header[0].clone());
+        })
+        .unwrap();
+    assert_eq!(labels, expected);
+
+    // The frames before a bad one arrive, then the error is returned.
+    let truncated = format!("{}{}", common::labelled_frames(100), "frame 100\n1 2 3\n");
+    let mut labels = Vec::new();
+    let result = client.parse_bytes_streaming(truncated.as_bytes(), |frame| {
+        labels.push(frame.header.prebox_header[0].clone());
+    });
+    assert!(result.is_err());
+    assert_eq!(labels, expected[..100]);
+}
+
+#[test]
+fn test_write_frames_keeps_masses() {
+    let client = RpcClient::new(&spawn_server()).unwrap();
+    let fdat = fs::read_to_string(test_case!("tiny_multi_cuh2.con")).expect("Can't find test.");
+    let mut frames: Vec<_> = ConFrameIterator::new(&fdat).map(|r| r.unwrap()).collect();
+    // Masses that no fallback would produce.
+    for frame in &mut frames {
+        frame.header.masses_per_type = vec![60.0, 2.5];
+    }
+
+    let written = String::from_utf8(client.write_frames(&frames).unwrap()).unwrap();
+    assert_eq!(written, writer::write_con_string(&frames, 6));
+    let reparsed = client.parse_bytes(written.as_bytes()).unwrap();
+    assert_eq!(reparsed.len(), frames.len());
+    for frame in &reparsed {
+        assert_eq!(frame.header.masses_per_type, vec![60.0, 2.5]);
+    }
+}
+
+#[test]
+fn test_adjacent_types_with_one_symbol_keep_masses() {
+    let client = RpcClient::new(&spawn_server()).unwrap();
+    // Two Cu types, one atom each, with different masses.
+    let fdat = "Random Number Seed\nTime\n10.0 10.0 10.0\n90.0 90.0 90.0\n0 0\n218 0 1\n2\n1 1\n\
+                63.546 65.0\nCu\nCoordinates of Component 1\n0.0 0.0 0.0 1 0\n\
+                Cu\nCoordinates of Component 2\n1.0 1.0 1.0 0 1\n";
+    let expected: Vec<_> = ConFrameIterator::new(fdat).map(|r| r.unwrap()).collect();
+    assert_eq!(expected[0].header.natms_per_type, vec![1, 1]);
+
+    let frames = client.parse_bytes(fdat.as_bytes()).unwrap();
+    assert_eq!(frames, expected);
+    let written = String::from_utf8(client.write_frames(&frames).unwrap()).unwrap();
+    assert_eq!(written, writer::write_con_string(&expected, 6));
+}